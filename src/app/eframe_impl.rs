@@ -10,7 +10,7 @@ use crate::helper::{Helper, ProcessName, ProcessState};
 use crate::inits::init_text_styles;
 use crate::{NODE_MIDDLE, P2POOL_MIDDLE, SECOND, XMRIG_MIDDLE, XMRIG_PROXY_MIDDLE, XVB_MIDDLE};
 use derive_more::derive::{Deref, DerefMut};
-use log::debug;
+use log::{debug, info};
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
@@ -71,6 +71,28 @@ impl eframe::App for App {
             || self.og_pool_vec != self.pool_vec;
         drop(og);
 
+        // The remote nodes found for a network can not be used on another one.
+        // When the network changes, forget them so the crawler and the pinger look for new ones.
+        if self.crawler.lock().unwrap().network != self.state.gupax.network {
+            let network = self.state.gupax.network;
+            info!(
+                "App | Switching to {network}, forgetting the remote nodes of the previous network"
+            );
+            let mut crawler_lock = self.crawler.lock().unwrap();
+            crawler_lock.network = network;
+            crawler_lock.nodes = RemoteNodes::default();
+            drop(crawler_lock);
+            let mut ping_lock = self.ping.lock().unwrap();
+            ping_lock.network = network;
+            ping_lock.nodes = self.known_nodes.lock().unwrap().reliable_nodes(
+                self.state.p2pool.crawl_settings.nb_nodes_medium.into(),
+                network,
+            );
+            ping_lock.pinged = false;
+            drop(ping_lock);
+            self.state.p2pool.selected_remote_node = None;
+            self.backup_hosts.lock().unwrap().clear();
+        }
         // crawl/pinged/selected remote node refresh
         if self.state.gupax.auto.crawl || self.tab == Tab::P2pool {
             let mut crawler_lock = self.crawler.lock().unwrap();
@@ -174,7 +174,10 @@ impl App {
                         self.state.status.submenu = SubmenuStatus::Benchmarks
                     }
                     SubmenuStatus::P2pool => self.state.status.submenu = SubmenuStatus::Processes,
-                    SubmenuStatus::Benchmarks => self.state.status.submenu = SubmenuStatus::P2pool,
+                    SubmenuStatus::Calculator => self.state.status.submenu = SubmenuStatus::P2pool,
+                    SubmenuStatus::Benchmarks => {
+                        self.state.status.submenu = SubmenuStatus::Calculator
+                    }
                 },
                 Tab::Gupax => flip!(self.state.gupax.simple),
                 Tab::Node => flip!(self.state.node.simple),
@@ -193,7 +196,10 @@ impl App {
             match self.tab {
                 Tab::Status => match self.state.status.submenu {
                     SubmenuStatus::Processes => self.state.status.submenu = SubmenuStatus::P2pool,
-                    SubmenuStatus::P2pool => self.state.status.submenu = SubmenuStatus::Benchmarks,
+                    SubmenuStatus::P2pool => self.state.status.submenu = SubmenuStatus::Calculator,
+                    SubmenuStatus::Calculator => {
+                        self.state.status.submenu = SubmenuStatus::Benchmarks
+                    }
                     SubmenuStatus::Benchmarks => {
                         self.state.status.submenu = SubmenuStatus::Processes
                     }
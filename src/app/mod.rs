@@ -17,29 +17,37 @@ use crate::components::node::Ping;
 use crate::components::node::RemoteNodes;
 use crate::components::update::Update;
 use crate::disk::consts::GUPAX_P2POOL_API_DIRECTORY;
+use crate::disk::consts::KNOWN_NODES_TOML;
 use crate::disk::consts::NODE_TOML;
 use crate::disk::consts::POOL_TOML;
 use crate::disk::consts::STATE_TOML;
+use crate::disk::consts::XVB_HISTORY_TOML;
 use crate::disk::create_gupax_dir;
 use crate::disk::create_gupax_p2pool_dir;
 use crate::disk::get_gupax_data_path;
 use crate::disk::gupax_p2pool_api::GupaxP2poolApi;
+use crate::disk::known_nodes::KnownNodes;
 use crate::disk::node::Node;
+use crate::disk::payout_export::PayoutExport;
 use crate::disk::pool::Pool;
 use crate::disk::state::GupaxTheme;
 use crate::disk::state::State;
+use crate::disk::xvb_history::XvbHistory;
 use crate::errors::ErrorButtons;
 use crate::errors::ErrorFerris;
 use crate::errors::ErrorState;
 use crate::helper::Helper;
 use crate::helper::Process;
 use crate::helper::ProcessName;
+use crate::helper::chain_advisor::ChainAdvisor;
 use crate::helper::crawler::Crawler;
 use crate::helper::node::ImgNode;
 use crate::helper::node::PubNodeApi;
 use crate::helper::notification::NotificationApi;
+use crate::helper::observer::Observer;
 use crate::helper::p2pool::ImgP2pool;
 use crate::helper::p2pool::PubP2poolApi;
+use crate::helper::payout_scanner::PayoutScanner;
 use crate::helper::sys_info::Sys;
 use crate::helper::xrig::xmrig::ImgXmrig;
 use crate::helper::xrig::xmrig::PubXmrigApi;
@@ -47,6 +55,8 @@ use crate::helper::xrig::xmrig_proxy::ImgProxy;
 use crate::helper::xrig::xmrig_proxy::PubXmrigProxyApi;
 use crate::helper::xvb::PubXvbApi;
 use crate::helper::xvb::priv_stats::RuntimeMode;
+use crate::helper::xvb::registration::XvbRegistration;
+use crate::helper::xvb::what_if::{WhatIf, WhatIfOutcome};
 use crate::inits::init_text_styles;
 use crate::miscs::cmp_f64;
 use crate::miscs::get_exe;
@@ -149,6 +159,7 @@ pub struct App {
     pub ip_public: Arc<Mutex<Option<Ipv4Addr>>>,
     pub proxy_port_reachable: Arc<Mutex<bool>>, // is the proxy port reachable from public ip ?
     pub crawler: Arc<Mutex<Crawler>>,
+    pub known_nodes: Arc<Mutex<KnownNodes>>, // Reputation of remote nodes, shared by [Ping] and [Crawler]
     // STDIN Buffer
     pub node_stdin: String, // The buffer between the node console and the [Helper]
     pub p2pool_stdin: String, // The buffer between the p2pool console and the [Helper]
@@ -156,6 +167,14 @@ pub struct App {
     pub xmrig_proxy_stdin: String, // The buffer between the xmrig-proxy console and the [Helper]
     // Sudo State
     pub sudo: Arc<Mutex<SudoState>>, // This is just a dummy struct on [Windows].
+    pub payout_scanner: Arc<Mutex<PayoutScanner>>, // Verification of the payouts with the view key
+    pub payout_export: Arc<Mutex<PayoutExport>>, // Export of the payouts to CSV/JSON
+    pub chain_advisor: Arc<Mutex<ChainAdvisor>>, // Difficulties of the P2Pool chains for the advisor
+    pub observer: Arc<Mutex<Observer>>, // Blocks, shares and payouts fetched from the P2Pool observer
+    pub xvb_what_if: Option<(WhatIf, WhatIfOutcome)>, // Inputs and result of the what-if of the XvB tab
+    pub xvb_goal_projection: Option<(WhatIf, WhatIfOutcome)>, // Round projected for the goal mode of the XvB tab
+    pub xvb_history: Arc<Mutex<XvbHistory>>, // Rounds and raffles of XvB, recorded by the XvB process
+    pub xvb_registration: Arc<Mutex<XvbRegistration>>, // Verification and registration of the address to XvB
     // State from [--flags]
     pub no_startup: bool,
     // Gupax-P2Pool API
@@ -179,6 +198,7 @@ pub struct App {
     pub state_path: PathBuf,            // State file path
     pub node_path: PathBuf,             // Node file path
     pub pool_path: PathBuf,             // Pool file path
+    pub xvb_history_path: PathBuf,      // XvB history file path
     pub backup_hosts: BackupNodes,      // P2Pool backup nodes
     pub version: &'static str,          // Gupax version
     pub name_version: String,           // [Gupax vX.X.X]
@@ -224,6 +244,12 @@ impl App {
         if let Err(e) = Pool::save(&self.pool_vec, &self.pool_path) {
             error!("Pool list: {e}");
         }
+        if let Err(e) = self.known_nodes.lock().unwrap().save() {
+            error!("Known nodes: {e}");
+        }
+        if let Err(e) = self.xvb_history.lock().unwrap().save() {
+            error!("XvB history: {e}");
+        }
     }
 
     #[cold]
@@ -272,6 +298,7 @@ impl App {
         let notifications_api = Arc::new(Mutex::new(NotificationApi {
             notifications: vec![],
         }));
+        let known_nodes = arc_mut!(KnownNodes::default());
 
         info!("App Init | Sysinfo...");
         // We give this to the [Helper] thread.
@@ -308,7 +335,7 @@ impl App {
         let sysinfo = arc_mut!(sysinfo);
         let mut app = Self {
             tab: Tab::default(),
-            ping: arc_mut!(Ping::new(RemoteNodes::default())),
+            ping: arc_mut!(Ping::new(RemoteNodes::default(), known_nodes.clone())),
             size: vec2(APP_DEFAULT_WIDTH, APP_DEFAULT_HEIGHT),
             must_resize: true,
             og: arc_mut!(State::new()),
@@ -363,7 +390,8 @@ impl App {
             xvb_api,
             xmrig_api,
             xmrig_proxy_api,
-            crawler: Crawler::new(),
+            crawler: Crawler::new(known_nodes.clone()),
+            known_nodes,
             p2pool_img,
             xmrig_img,
             node_stdin: String::with_capacity(10),
@@ -371,6 +399,14 @@ impl App {
             xmrig_stdin: String::with_capacity(10),
             xmrig_proxy_stdin: String::with_capacity(10),
             sudo: arc_mut!(SudoState::new()),
+            payout_scanner: arc_mut!(PayoutScanner::default()),
+            chain_advisor: arc_mut!(ChainAdvisor::default()),
+            observer: arc_mut!(Observer::default()),
+            xvb_what_if: None,
+            xvb_goal_projection: None,
+            xvb_history: arc_mut!(XvbHistory::default()),
+            xvb_registration: arc_mut!(XvbRegistration::default()),
+            payout_export: arc_mut!(PayoutExport::default()),
             resizing: false,
             alpha: 0,
             no_startup: false,
@@ -390,6 +426,7 @@ impl App {
             state_path: PathBuf::new(),
             node_path: PathBuf::new(),
             pool_path: PathBuf::new(),
+            xvb_history_path: PathBuf::new(),
             backup_hosts: Arc::new(Mutex::new(vec![])),
             version: GUPAX_VERSION,
             name_version: format!("Gupax {GUPAX_VERSION}"),
@@ -442,6 +479,8 @@ impl App {
         app.node_path.push(NODE_TOML);
         app.pool_path.clone_from(&app.os_data_path);
         app.pool_path.push(POOL_TOML);
+        app.xvb_history_path.clone_from(&app.os_data_path);
+        app.xvb_history_path.push(XVB_HISTORY_TOML);
         // Set GupaxP2poolApi path
         app.gupax_p2pool_api_path = crate::disk::get_gupax_p2pool_path(&app.os_data_path);
         app.gupax_p2pool_api
@@ -546,6 +585,21 @@ impl App {
         app.og_pool_vec.clone_from(&app.pool_vec);
         debug!("Pool Vec:");
         debug!("{:#?}", app.pool_vec);
+        // Read known nodes
+        // The reliable nodes of previous runs are available for the ping without needing to crawl again.
+        info!("App Init | Reading known nodes...");
+        *app.known_nodes.lock().unwrap() =
+            KnownNodes::get(&app.os_data_path.join(KNOWN_NODES_TOML));
+        app.ping.lock().unwrap().nodes = app.known_nodes.lock().unwrap().reliable_nodes(
+            app.state.p2pool.crawl_settings.nb_nodes_medium.into(),
+            app.state.gupax.network,
+        );
+        app.ping.lock().unwrap().network = app.state.gupax.network;
+        app.crawler.lock().unwrap().network = app.state.gupax.network;
+        // Read the history of the XvB rounds, the XvB process of the [Helper] keeps recording in it.
+        info!("App Init | Reading XvB history...");
+        *app.xvb_history.lock().unwrap() = XvbHistory::get(&app.xvb_history_path);
+        app.helper.lock().unwrap().xvb_history = Arc::clone(&app.xvb_history);
 
         //----------------------------------------------------------------------------------------------------
         // Read [GupaxP2poolApi] disk files
@@ -714,6 +768,7 @@ impl App {
 
         // Set saved choice of use of sidechain HR
         app.xvb_api.lock().unwrap().use_p2pool_sidechain_hr = app.state.xvb.use_p2pool_sidechain_hr;
+        app.xvb_api.lock().unwrap().split = app.state.xvb.split;
 
         // Set saved choice for notifications
         app.notifications_api.lock().unwrap().notifications = app.state.gupax.notifications.clone();
@@ -11,7 +11,7 @@ use crate::helper::{Helper, ProcessName, ProcessSignal, ProcessState};
 use crate::utils::constants::*;
 use crate::utils::errors::{ErrorButtons, ErrorFerris};
 use crate::utils::macros::sleep;
-use crate::utils::regex::Regexes;
+use crate::utils::monero_address::{Network, check_address};
 use egui::*;
 use log::{debug, error, warn};
 use strum::IntoEnumIterator;
@@ -284,6 +284,7 @@ impl crate::app::App {
                                 &self.helper,
                                 &self.state.node,
                                 &self.state.gupax.absolute_node_path,
+                                self.state.gupax.network,
                             );
                         }
                         ProcessName::P2pool => {
@@ -393,7 +394,8 @@ impl crate::app::App {
                                         Helper::start_node(
                                         &self.helper,
                                         &self.state.node,
-                                        &self.state.gupax.absolute_node_path);                                    }
+                                        &self.state.gupax.absolute_node_path,
+                                        self.state.gupax.network);                                    }
                                     }
                                 } else {
                                     // if for some reason we were stuck for 3s, we go back to process_running check
@@ -408,7 +410,8 @@ impl crate::app::App {
                                         Helper::start_node(
                                         &self.helper,
                                         &self.state.node,
-                                        &self.state.gupax.absolute_node_path);                                    }
+                                        &self.state.gupax.absolute_node_path,
+                                        self.state.gupax.network);                                    }
                                 }
                                 },
                             ProcessName::P2pool => {
@@ -536,10 +539,9 @@ impl crate::app::App {
     fn status_submenu(state_submenu: &mut SubmenuStatus, ui: &mut Ui) {
         ui.group(|ui| {
             let spacing = spacing(ui);
-            // should be calculated from the len of variants and their name
-            let width = ((ui.available_width() / 1.5 / 3.0) - spacing).max(0.0);
             let variants = SubmenuStatus::iter();
             let nb_variants = variants.len();
+            let width = ((ui.available_width() / 1.5 / nb_variants as f32) - spacing).max(0.0);
             for (nb, variant) in variants.enumerate() {
                 if ui
                     .add_sized(
@@ -694,8 +696,12 @@ impl crate::app::App {
             }
             ProcessName::P2pool => {
                 // check if p2pool address is valid.
-                if !Regexes::addr_ok(&self.state.p2pool.address) {
-                    return Err(format!("Error: {P2POOL_ADDRESS}"));
+                if let Err(e) = check_address(
+                    &self.state.p2pool.address,
+                    ProcessName::P2pool,
+                    self.state.gupax.network,
+                ) {
+                    return Err(format!("Error: {e}\n{P2POOL_ADDRESS}"));
                 }
                 // check if a remote node is selected if button unchecked and not in advanced mode
                 if !self.state.p2pool.local_node
@@ -711,7 +717,16 @@ impl crate::app::App {
             ProcessName::Xmrig => &self.state.gupax.xmrig_path,
             ProcessName::XmrigProxy => &self.state.gupax.xmrig_proxy_path,
             ProcessName::Xvb => {
-                if !Regexes::addr_ok(&self.state.p2pool.address) {
+                if self.state.gupax.network != Network::Mainnet {
+                    return Err(format!("Error: {XVB_MAINNET_ONLY}"));
+                }
+                if check_address(
+                    &self.state.p2pool.address,
+                    ProcessName::Xvb,
+                    Network::Mainnet,
+                )
+                .is_err()
+                {
                     return Err(format!("Error: {XVB_NOT_CONFIGURED}"));
                 }
                 ""
@@ -23,8 +23,9 @@ use egui::{TextStyle, Ui};
 
 use crate::components::gupax::{FileType, FileWindow};
 use crate::disk::state::Gupax;
+use crate::helper::ProcessName;
 use crate::miscs::height_txt_before_button;
-use crate::regex::Regexes;
+use crate::utils::monero_address::{ADDRESS_LEN, INTEGRATED_ADDRESS_LEN, Network, check_address};
 use crate::{
     GREEN, GUPAX_SELECT, LIGHT_GRAY, NODE_DB_DIR, NODE_DB_PATH_EMPTY, NODE_PATH_OK, RED, SPACE,
 };
@@ -198,24 +199,46 @@ pub fn path_db_field(ui: &mut Ui, path: &mut String, file_window: &Arc<Mutex<Fil
         });
     });
 }
-pub fn monero_address_field(address: &mut String, ui: &mut Ui, hover: &str) {
+// The address is decoded to show precisely why it can not be used by this process.
+pub fn monero_address_field(
+    address: &mut String,
+    ui: &mut Ui,
+    hover: &str,
+    process: ProcessName,
+    network: Network,
+) {
     ui.group(|ui| {
         let text;
         let color;
         let len = format!("{:02}", address.len());
+        let max_len = if address.len() > ADDRESS_LEN {
+            INTEGRATED_ADDRESS_LEN
+        } else {
+            ADDRESS_LEN
+        };
+        let mut error = None;
         if address.is_empty() {
-            text = format!("Monero Address [{len}/95] ➖");
+            text = format!("Monero Address [{len}/{max_len}] ➖");
             color = Color32::LIGHT_GRAY;
-        } else if Regexes::addr_ok(address) {
-            text = format!("Monero Address [{len}/95] ✔");
-            color = Color32::from_rgb(100, 230, 100);
         } else {
-            text = format!("Monero Address [{len}/95] ❌");
-            color = Color32::from_rgb(230, 50, 50);
+            match check_address(address, process, network) {
+                Ok(decoded) => {
+                    text = format!("Monero Address [{len}/{max_len}] ✔ {}", decoded.kind);
+                    color = Color32::from_rgb(100, 230, 100);
+                }
+                Err(e) => {
+                    text = format!("Monero Address [{len}/{max_len}] ❌");
+                    color = Color32::from_rgb(230, 50, 50);
+                    error = Some(e);
+                }
+            }
         }
         ui.style_mut().spacing.text_edit_width = ui.available_width();
         ui.vertical_centered(|ui| {
             ui.label(RichText::new(text).color(color));
+            if let Some(error) = error {
+                ui.label(RichText::new(error.to_string()).color(color));
+            }
             // ui.set_max_width(95.0 * 3.0);
             ui.add_space(SPACE);
             ui.add(
@@ -223,7 +246,7 @@ pub fn monero_address_field(address: &mut String, ui: &mut Ui, hover: &str) {
                     .horizontal_align(egui::Align::Center),
             )
             .on_hover_text(hover);
-            address.truncate(95);
+            address.truncate(INTEGRATED_ADDRESS_LEN);
         });
     });
 }
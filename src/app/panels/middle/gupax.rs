@@ -7,6 +7,7 @@ use crate::components::update::check_binary_path;
 use crate::disk::state::*;
 use crate::helper::notification::NotificationApi;
 use crate::miscs::height_txt_before_button;
+use crate::utils::monero_address::Network;
 use common::state_edit_field::slider_state_field;
 use log::debug;
 use std::path::Path;
@@ -349,6 +350,32 @@ impl Gupax {
                     );
                 });
             });
+            debug!("Gupax Tab | Rendering Network selection");
+            ui.group(|ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add(Label::new(
+                        RichText::new("Network").underline().color(LIGHT_GRAY),
+                    ))
+                    .on_hover_text(GUPAX_NETWORK);
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        for (network, name, hover) in [
+                            (Network::Mainnet, "Mainnet", GUPAX_NETWORK_MAINNET),
+                            (Network::Stagenet, "Stagenet", GUPAX_NETWORK_STAGENET),
+                            (Network::Testnet, "Testnet", GUPAX_NETWORK_TESTNET),
+                        ] {
+                            if ui
+                                .selectable_label(self.network == network, name)
+                                .on_hover_text(hover)
+                                .clicked()
+                            {
+                                self.network = network;
+                            }
+                            ui.separator();
+                        }
+                    });
+                });
+            });
         });
     }
     /// widget: AutoStart variant and selectable label (true) or checkbox (false)
@@ -19,12 +19,15 @@ use crate::app::Tab;
 use crate::app::eframe_impl::ProcessStatesGui;
 use crate::app::keys::KeyPressed;
 use crate::components::gupax::FileWindow;
+use crate::disk::status::Hash;
 use crate::helper::ProcessName;
+use crate::helper::xvb::current_controllable_hr;
 use crate::regex::REGEXES;
 use crate::utils::constants::*;
 use common::state_edit_field::StateTextEdit;
 use egui::*;
 use log::debug;
+use std::net::SocketAddr;
 mod about;
 pub mod common;
 mod gupax;
@@ -54,6 +57,21 @@ impl crate::app::App {
                 Tab::About => self.about_show(key, ui),
                 Tab::Status => {
                     debug!("App | Entering [Status] Tab");
+                    // payouts are verified with the local node, started by Gupax or not.
+                    let (rpc_port, _) = self.state.node.current_ports(
+                        states.is_alive(ProcessName::Node),
+                        &self.helper.lock().unwrap().img_node.lock().unwrap(),
+                        self.state.gupax.network,
+                    );
+                    let node_rpc = SocketAddr::from(([127, 0, 0, 1], rpc_port));
+                    // the observer of the chain P2Pool runs on, or would start on
+                    let chain = if states.is_alive(ProcessName::P2pool) {
+                        self.p2pool_img.lock().unwrap().chain.parse().ok()
+                    } else {
+                        None
+                    }
+                    .unwrap_or_else(|| self.state.p2pool.current_chain());
+                    let observer_url = self.state.p2pool.observers.url(&chain).to_string();
                     crate::disk::state::Status::show(
                         &mut self.state.status,
                         &self.state.gupax.show_processes,
@@ -69,6 +87,14 @@ impl crate::app::App {
                         self.max_threads,
                         &self.gupax_p2pool_api,
                         &self.benchmarks,
+                        &self.payout_scanner,
+                        &self.payout_export,
+                        &self.observer,
+                        &observer_url,
+                        &chain,
+                        &self.state.p2pool.address,
+                        self.state.gupax.network,
+                        node_rpc,
                         ctx,
                         ui,
                     );
@@ -98,12 +124,23 @@ impl crate::app::App {
                         &self.node_api,
                         &mut self.node_stdin,
                         &self.file_window,
+                        self.state.gupax.network,
                         ui,
                     );
                 }
                 Tab::P2pool => {
-                    let (rpc_port, zmq_port) = self.state.node.ports();
+                    let (rpc_port, zmq_port) = self.state.node.ports(self.state.gupax.network);
                     debug!("App | Entering [P2Pool] Tab");
+                    let status = &self.state.status;
+                    let hashrate = if status.manual_hash {
+                        Hash::convert_to_hash(status.hashrate, status.hash_metric) as u64
+                    } else {
+                        current_controllable_hr(
+                            states.is_alive(ProcessName::XmrigProxy),
+                            &self.xmrig_proxy_api,
+                            &self.xmrig_api,
+                        ) as u64
+                    };
                     crate::disk::state::P2pool::show(
                         &mut self.state.p2pool,
                         &mut self.node_vec,
@@ -119,6 +156,10 @@ impl crate::app::App {
                         zmq_port,
                         rpc_port,
                         &self.crawler,
+                        &self.chain_advisor,
+                        hashrate,
+                        states.is_alive(ProcessName::P2pool),
+                        &self.p2pool_img,
                     );
                 }
                 Tab::Xmrig => {
@@ -132,6 +173,7 @@ impl crate::app::App {
                         ctx,
                         ui,
                         self.state.p2pool.stratum_port(),
+                        self.state.gupax.network,
                     );
                 }
                 Tab::XmrigProxy => {
@@ -148,6 +190,7 @@ impl crate::app::App {
                         &self.ip_public,
                         &self.proxy_port_reachable,
                         &self.helper,
+                        self.state.gupax.network,
                     );
                 }
                 Tab::Xvb => {
@@ -160,6 +203,10 @@ impl crate::app::App {
                         &self.xvb_api,
                         &self.xmrig_api,
                         &self.xmrig_proxy_api,
+                        &self.xvb_history,
+                        &self.xvb_registration,
+                        &mut self.xvb_what_if,
+                        &mut self.xvb_goal_projection,
                         states.is_alive(ProcessName::Xvb),
                     );
                 }
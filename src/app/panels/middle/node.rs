@@ -33,6 +33,7 @@ use crate::components::gupax::FileWindow;
 use crate::disk::state::{Node, StartOptionsMode};
 use crate::helper::node::PubNodeApi;
 use crate::helper::{Process, ProcessName};
+use crate::utils::monero_address::Network;
 use crate::{P2POOL_IN, P2POOL_LOG, P2POOL_OUT, SPACE};
 
 impl Node {
@@ -43,6 +44,7 @@ impl Node {
         api: &Arc<Mutex<PubNodeApi>>,
         buffer: &mut String,
         file_window: &Arc<Mutex<FileWindow>>,
+        network: Network,
         ui: &mut egui::Ui,
     ) {
         ui.style_mut().override_text_style = Some(TextStyle::Body);
@@ -75,8 +77,8 @@ impl Node {
             if !self.simple {
                 //---------------------------------------------------------------------------------------------------- Arguments
                 debug!("Node Tab | Rendering [Arguments]");
-                let default_args_simple = self.start_options(StartOptionsMode::Simple);
-                let default_args_advanced = self.start_options(StartOptionsMode::Advanced);
+                let default_args_simple = self.start_options(StartOptionsMode::Simple, network);
+                let default_args_advanced = self.start_options(StartOptionsMode::Advanced, network);
                 start_options_field(
                     ui,
                     &mut self.arguments,
@@ -0,0 +1,183 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, Mutex};
+
+use egui::{Button, RichText, TextEdit, TextStyle, Ui};
+use egui_extras::{Column, TableBuilder};
+use log::debug;
+
+use crate::{
+    app::submenu_enum::SubmenuP2pool,
+    constants::*,
+    disk::state::{P2pool, P2poolChain},
+    helper::chain_advisor::{ChainAdvisor, ChainStats, advise, recommend, warning},
+    utils::{
+        human::{HumanNumber, HumanTime},
+        xmr::AtomicUnit,
+    },
+};
+
+// What the running P2Pool knows, more recent than the observers.
+pub struct RunningChain {
+    pub stats: ChainStats,
+    pub monero_difficulty: u64,
+    pub block_reward: AtomicUnit,
+}
+
+impl P2pool {
+    // [current] is the chain P2Pool starts on with the current settings.
+    pub(super) fn advisor(
+        &mut self,
+        ui: &mut Ui,
+        advisor: &Arc<Mutex<ChainAdvisor>>,
+        hashrate: u64,
+        running: Option<RunningChain>,
+        current: P2poolChain,
+    ) {
+        debug!("P2Pool Tab | Rendering [Chain advisor]");
+        let height = ui.text_style_height(&TextStyle::Body) * 1.5;
+        let lock = advisor.lock().unwrap();
+        let mut stats = lock.stats.clone();
+        let (mut monero_difficulty, mut block_reward) = (lock.monero_difficulty, lock.block_reward);
+        let (fetching, msg) = (lock.fetching, lock.msg.clone());
+        drop(lock);
+        if let Some(running) = running {
+            stats.retain(|s| s.chain != running.stats.chain);
+            stats.push(running.stats);
+            if running.monero_difficulty > 0 {
+                monero_difficulty = running.monero_difficulty;
+                block_reward = running.block_reward;
+            }
+        }
+        let mut advices = advise(hashrate, monero_difficulty, block_reward, &stats);
+        advices.sort_by_key(|a| match a.chain {
+            P2poolChain::Main => 0,
+            P2poolChain::Mini => 1,
+            P2poolChain::Nano => 2,
+        });
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Chain advisor").underline().color(LIGHT_GRAY))
+                    .on_hover_text(P2POOL_ADVISOR);
+                ui.separator();
+                ui.label(format!("Hashrate: {} H/s", HumanNumber::from_u64(hashrate)))
+                    .on_hover_text(P2POOL_ADVISOR_HASHRATE);
+                ui.separator();
+                if ui
+                    .add_enabled(!fetching, Button::new("Fetch difficulties"))
+                    .on_hover_text(P2POOL_ADVISOR_FETCH)
+                    .clicked()
+                {
+                    ChainAdvisor::start(advisor, self.observers.clone());
+                }
+                ui.label(msg);
+            });
+            if self.submenu == SubmenuP2pool::Advanced {
+                ui.horizontal(|ui| {
+                    let width = ui.available_width() / 3.0 - SPACE * 6.0;
+                    for (name, url) in [
+                        ("Main", &mut self.observers.main),
+                        ("Mini", &mut self.observers.mini),
+                        ("Nano", &mut self.observers.nano),
+                    ] {
+                        ui.label(format!("{name} observer:"));
+                        ui.add_sized([width, height], TextEdit::singleline(url))
+                            .on_hover_text(P2POOL_ADVISOR_OBSERVER);
+                    }
+                });
+            }
+            if hashrate == 0 {
+                ui.label(P2POOL_ADVISOR_NO_HASHRATE);
+                return;
+            }
+            let best = recommend(&advices).map(|a| a.chain.clone());
+            if let Some(warning) = warning(&current, &advices) {
+                let warning = if self.submenu == SubmenuP2pool::Simple {
+                    format!("{warning} {P2POOL_ADVISOR_SIMPLE}")
+                } else {
+                    warning
+                };
+                ui.label(RichText::new(warning).color(ORANGE));
+            } else if let Some(best) = &best {
+                ui.label(RichText::new(format!("Recommended: P2Pool {best}")).color(GREEN));
+            }
+            if advices.is_empty() {
+                return;
+            }
+            let width = ui.available_width() / 5.0 - SPACE;
+            TableBuilder::new(ui)
+                .vscroll(false)
+                .columns(Column::exact(width), 5)
+                .header(height, |mut header| {
+                    for (title, hover) in [
+                        ("Chain", P2POOL_ADVISOR_CHAIN),
+                        ("Shares in Window", STATUS_CALCULATOR_WINDOW_SHARES),
+                        ("Chance to be Paid", STATUS_CALCULATOR_WINDOW_CHANCE),
+                        ("Payout Mean", STATUS_CALCULATOR_PAYOUT_MEAN),
+                        ("Monthly Variation", P2POOL_ADVISOR_VARIATION),
+                    ] {
+                        header.col(|ui| {
+                            ui.strong(title).on_hover_text(hover);
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(height, advices.len(), |mut row| {
+                        let advice = &advices[row.index()];
+                        row.col(|ui| {
+                            let name = format!("P2Pool {}", advice.chain);
+                            if best.as_ref() == Some(&advice.chain) {
+                                ui.label(RichText::new(name).color(GREEN));
+                            } else {
+                                ui.label(name);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{:.3}", advice.earnings.window_shares));
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                HumanNumber::from_f64_to_percent_6_point(
+                                    advice.earnings.window_chance * 100.0,
+                                )
+                                .as_str(),
+                            );
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                advice
+                                    .earnings
+                                    .payout_mean
+                                    .map(|s| HumanTime::from_u64(s as u64).display(false))
+                                    .unwrap_or_else(|| "???".to_string()),
+                            );
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                advice
+                                    .variation
+                                    .map(|v| format!("±{:.0}%", v * 100.0))
+                                    .unwrap_or_else(|| "???".to_string()),
+                            );
+                        });
+                    });
+                });
+        });
+    }
+}
@@ -7,16 +7,21 @@ use egui::{
 use log::debug;
 
 use crate::{
-    app::BackupNodes,
+    app::{BackupNodes, panels::middle::common::state_edit_field::StateTextEdit},
     components::node::{Ping, RemoteNode, format_ms},
-    disk::state::P2pool,
+    disk::state::{Gupax, P2pool},
     helper::{crawler::Crawler, p2pool::PubP2poolApi},
     miscs::height_txt_before_button,
-    utils::constants::{
-        BUTTON_DISABLED_BY_EMPTY_LIST_NODES, CRAWLER_PARAMETERS_HELP, EXPECT_BUTTON_DISABLED,
-        ORANGE, P2POOL_AUTO_NODE, P2POOL_AUTOSWITCH_LOCAL_NODE, P2POOL_BACKUP_HOST_SIMPLE,
-        P2POOL_COMMUNITY_NODE_WARNING, P2POOL_PING, P2POOL_SELECT_FASTEST, P2POOL_SELECT_LAST,
-        P2POOL_SELECT_NEXT, P2POOL_SELECT_RANDOM, SPACE,
+    utils::{
+        constants::{
+            BUTTON_DISABLED_BY_EMPTY_LIST_NODES, CRAWLER_ALLOWLIST, CRAWLER_DENYLIST,
+            CRAWLER_DIVERSITY, CRAWLER_FULL_RPC, CRAWLER_PARAMETERS_HELP, CRAWLER_PREFIX_TABLE,
+            CRAWLER_SPY_NODES, EXPECT_BUTTON_DISABLED, ORANGE, P2POOL_AUTO_FAILOVER,
+            P2POOL_AUTO_NODE, P2POOL_AUTOSWITCH_LOCAL_NODE, P2POOL_BACKUP_HOST_SIMPLE,
+            P2POOL_COMMUNITY_NODE_WARNING, P2POOL_PING, P2POOL_SELECT_FASTEST, P2POOL_SELECT_LAST,
+            P2POOL_SELECT_NEXT, P2POOL_SELECT_RANDOM, SPACE,
+        },
+        ip_rules::parse_cidr_list,
     },
 };
 
@@ -52,6 +57,10 @@ impl P2pool {
                     self.slider_timeout(ui);
                     ui.add_space(ui.spacing().item_spacing.x);
                 });
+                ui.group(|ui| {
+                    ui.set_max_width(0.0);
+                    self.crawl_rules(ui);
+                });
             });
     }
 
@@ -210,7 +219,7 @@ impl P2pool {
         debug!("P2Pool Tab | Rendering [Auto-*] buttons");
         ui.group(|ui| {
             ui.horizontal(|ui| {
-                let width = (((ui.available_width() - ui.spacing().item_spacing.x) / 3.0)
+                let width = (((ui.available_width() - ui.spacing().item_spacing.x * 2.0) / 4.0)
                     - SPACE * 1.5)
                     .max(ui.text_style_height(&TextStyle::Button) * 7.0);
                 let size = vec2(
@@ -238,6 +247,12 @@ impl P2pool {
                 {
                     api.lock().unwrap().prefer_local_node = self.prefer_local_node;
                 }
+                ui.separator();
+                ui.add_sized(
+                    size,
+                    Checkbox::new(&mut self.auto_failover, "Auto-failover"),
+                )
+                .on_hover_text(P2POOL_AUTO_FAILOVER);
             })
         });
     }
@@ -278,6 +293,42 @@ impl P2pool {
             crawl_progress(crawler, ui);
         });
     }
+    // rules deciding which found nodes can be used as backup nodes
+    fn crawl_rules(&mut self, ui: &mut Ui) {
+        let settings = &mut self.crawl_settings;
+        StateTextEdit::new(ui)
+            .description("Allowlist    ")
+            .max_ch(255)
+            .help_msg(CRAWLER_ALLOWLIST)
+            .validations(&[|x| parse_cidr_list(x).is_ok()])
+            .build(ui, &mut settings.allowlist);
+        StateTextEdit::new(ui)
+            .description("Denylist     ")
+            .max_ch(255)
+            .help_msg(CRAWLER_DENYLIST)
+            .validations(&[|x| parse_cidr_list(x).is_ok()])
+            .build(ui, &mut settings.denylist);
+        StateTextEdit::new(ui)
+            .description("Spy nodes    ")
+            .max_ch(255)
+            .help_msg(CRAWLER_SPY_NODES)
+            // optional paths, only checked once entered
+            .validations(&[|x| x.is_empty() || Gupax::path_is_file(x)])
+            .build(ui, &mut settings.spy_nodes_path);
+        StateTextEdit::new(ui)
+            .description("Prefix table ")
+            .max_ch(255)
+            .help_msg(CRAWLER_PREFIX_TABLE)
+            .validations(&[|x| x.is_empty() || Gupax::path_is_file(x)])
+            .build(ui, &mut settings.prefix_table_path);
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut settings.diversity, "One backup node per network")
+                .on_hover_text(CRAWLER_DIVERSITY);
+            ui.separator();
+            ui.checkbox(&mut settings.require_full_rpc, "Require full RPC")
+                .on_hover_text(CRAWLER_FULL_RPC);
+        });
+    }
     fn slider_latency(&mut self, ui: &mut Ui) {
         ui.horizontal(|ui|{
         let text_height = height_txt_before_button(ui, &TextStyle::Button);
@@ -1,9 +1,12 @@
 use crate::app::BackupNodes;
 use crate::app::panels::middle::common::console::{console, input_args_field, start_options_field};
 use crate::app::submenu_enum::SubmenuP2pool;
+use crate::disk::state::P2poolChain;
 use crate::disk::state::{P2pool, StartOptionsMode, State};
+use crate::helper::chain_advisor::{ChainAdvisor, ChainStats};
 use crate::helper::crawler::Crawler;
-use crate::helper::p2pool::PubP2poolApi;
+use crate::helper::p2pool::{ImgP2pool, PubP2poolApi};
+use advisor::RunningChain;
 use egui::Image;
 // Gupax
 //
@@ -31,6 +34,7 @@ use super::common::header_tab::header_tab;
 use super::common::list_poolnode::PoolNode;
 
 mod advanced;
+mod advisor;
 mod crawler;
 mod simple;
 
@@ -52,6 +56,10 @@ impl P2pool {
         local_node_zmq_port: u16,
         local_node_rpc_port: u16,
         crawler: &Arc<Mutex<Crawler>>,
+        chain_advisor: &Arc<Mutex<ChainAdvisor>>,
+        hashrate: u64,
+        p2pool_alive: bool,
+        p2pool_img: &Arc<Mutex<ImgP2pool>>,
     ) {
         //---------------------------------------------------------------------------------------------------- [Simple] Console
         // debug!("P2Pool Tab | Rendering [Console]");
@@ -109,11 +117,42 @@ impl P2pool {
                 &mut self.address,
                 ui,
                 P2POOL_ADDRESS,
+                ProcessName::P2pool,
+                crawler.lock().unwrap().network,
             );
+            let current = self.current_chain();
+            let running = if p2pool_alive {
+                p2pool_img
+                    .lock()
+                    .unwrap()
+                    .chain
+                    .parse::<P2poolChain>()
+                    .ok()
+                    .and_then(|chain| {
+                        let api = api.lock().unwrap();
+                        (api.p2pool_difficulty_u64 > 0).then(|| RunningChain {
+                            stats: ChainStats {
+                                window_length_blocks: api
+                                    .window_length_blocks
+                                    .unwrap_or(chain.pplns_window_blocks()),
+                                chain,
+                                difficulty: api.p2pool_difficulty_u64,
+                            },
+                            monero_difficulty: api.monero_difficulty_u64,
+                            block_reward: api.reward,
+                        })
+                    })
+            } else {
+                None
+            };
             match self.submenu {
-                SubmenuP2pool::Simple => self.simple(ui, crawler, backup_nodes),
+                SubmenuP2pool::Simple => {
+                    self.simple(ui, crawler, backup_nodes);
+                    self.advisor(ui, chain_advisor, hashrate, running, current);
+                }
                 SubmenuP2pool::Advanced => {
                     self.advanced(ui, node_vec);
+                    self.advisor(ui, chain_advisor, hashrate, running, current);
                 }
                 SubmenuP2pool::Crawler => self.crawler(ui, crawler, ping, api, backup_nodes),
             }
@@ -0,0 +1,219 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, Mutex};
+
+use egui::{Button, DragValue, RichText, ScrollArea, TextStyle};
+use log::debug;
+
+use crate::{
+    disk::{
+        state::{P2poolChain, Status},
+        status::Hash,
+    },
+    helper::p2pool::{ImgP2pool, PubP2poolApi},
+    utils::{
+        constants::*,
+        earnings::{DAY, Earnings, EarningsInput, MONTH, Profit, WEEK},
+        human::{HumanNumber, HumanTime},
+    },
+};
+
+impl Status {
+    pub(super) fn calculator(
+        &mut self,
+        ui: &mut egui::Ui,
+        p2pool_alive: bool,
+        p2pool_api: &Arc<Mutex<PubP2poolApi>>,
+        p2pool_img: &Arc<Mutex<ImgP2pool>>,
+    ) {
+        debug!("Status Tab | Rendering [Calculator]");
+        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+        ui.style_mut().override_text_style = Some(TextStyle::Body);
+        let size_text = ui.text_style_height(&TextStyle::Body);
+        let height = (ui.style().spacing.button_padding.y * 2.0) + size_text;
+        // The sidechain difficulty and the window are only known for the chain of the running P2Pool.
+        let running_chain =
+            p2pool_alive && p2pool_img.lock().unwrap().chain == self.calculator.chain.to_string();
+        let api = p2pool_api.lock().unwrap();
+        let hashrate = if self.manual_hash {
+            Hash::convert_to_hash(self.hashrate, self.hash_metric) as u64
+        } else {
+            api.hashrate_1h
+        };
+        let (sidechain_difficulty, window_length_blocks) = if running_chain {
+            (
+                api.p2pool_difficulty_u64,
+                api.window_length_blocks
+                    .unwrap_or(self.calculator.chain.pplns_window_blocks()),
+            )
+        } else {
+            (
+                self.calculator.sidechain_difficulty,
+                self.calculator.chain.pplns_window_blocks(),
+            )
+        };
+        let earnings = Earnings::new(&EarningsInput {
+            hashrate,
+            monero_difficulty: api.monero_difficulty_u64,
+            block_reward: api.reward,
+            chain: self.calculator.chain.clone(),
+            sidechain_difficulty,
+            window_length_blocks,
+        });
+        drop(api);
+        ScrollArea::vertical().show(ui, |ui| {
+            self.hashrate_input(ui, height);
+            // Chain
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    let width = ui.available_width() / 10.0;
+                    for chain in [P2poolChain::Main, P2poolChain::Mini, P2poolChain::Nano] {
+                        if ui
+                            .add_sized(
+                                [width, height],
+                                Button::selectable(
+                                    self.calculator.chain == chain,
+                                    format!("P2Pool {chain}"),
+                                ),
+                            )
+                            .on_hover_text(STATUS_CALCULATOR_CHAIN)
+                            .clicked()
+                        {
+                            self.calculator.chain = chain;
+                        }
+                        ui.separator();
+                    }
+                    if running_chain {
+                        ui.label(format!(
+                            "Sidechain difficulty: {} | PPLNS window: {window_length_blocks} blocks",
+                            HumanNumber::from_u64(sidechain_difficulty)
+                        ))
+                        .on_hover_text(STATUS_CALCULATOR_DIFFICULTY_RUNNING);
+                    } else {
+                        ui.label("Sidechain difficulty:");
+                        ui.add(DragValue::new(&mut self.calculator.sidechain_difficulty))
+                            .on_hover_text(STATUS_CALCULATOR_DIFFICULTY);
+                    }
+                });
+            });
+            // Electricity
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    ui.label("Power:");
+                    ui.add(
+                        DragValue::new(&mut self.calculator.power)
+                            .range(0.0..=100_000.0)
+                            .suffix(" W"),
+                    )
+                    .on_hover_text(STATUS_CALCULATOR_POWER);
+                    ui.separator();
+                    ui.label("Price per kWh:");
+                    ui.add(
+                        DragValue::new(&mut self.calculator.kwh_price)
+                            .range(0.0..=100.0)
+                            .speed(0.01),
+                    )
+                    .on_hover_text(STATUS_CALCULATOR_KWH_PRICE);
+                    ui.separator();
+                    ui.label("Price per XMR:");
+                    ui.add(
+                        DragValue::new(&mut self.calculator.xmr_price)
+                            .range(0.0..=1_000_000.0)
+                            .speed(1.0),
+                    )
+                    .on_hover_text(STATUS_CALCULATOR_XMR_PRICE);
+                });
+            });
+            ui.add_space(height / 2.0);
+            ui.add_enabled_ui(p2pool_alive, |ui| {
+                if !p2pool_alive {
+                    ui.label(STATUS_CALCULATOR_P2POOL_OFFLINE);
+                }
+                ui.columns_const(|[col1, col2, col3, col4]| {
+                    for (col, name, seconds) in [
+                        (col1, "Day", DAY),
+                        (col2, "Week", WEEK),
+                        (col3, "Month", MONTH),
+                    ] {
+                        col.group(|ui| {
+                            ui.vertical_centered(|ui| {
+                                ui.label(RichText::new(format!("Per {name}")).underline())
+                                    .on_hover_text(STATUS_CALCULATOR_XMR);
+                                ui.label(format!(
+                                    "{} XMR",
+                                    HumanNumber::from_f64_12_point(earnings.xmr(seconds))
+                                ));
+                                let profit = Profit::new(
+                                    &earnings,
+                                    seconds,
+                                    self.calculator.xmr_price,
+                                    self.calculator.power,
+                                    self.calculator.kwh_price,
+                                );
+                                ui.label(RichText::new("Income").underline());
+                                ui.label(format!("{:.2}", profit.income));
+                                ui.label(RichText::new("Electricity").underline());
+                                ui.label(format!("{:.2}", profit.electricity));
+                                ui.label(RichText::new("Net Profit").underline())
+                                    .on_hover_text(STATUS_CALCULATOR_NET);
+                                let net = format!("{:.2}", profit.net());
+                                if profit.net() < 0.0 {
+                                    ui.label(RichText::new(net).color(RED));
+                                } else {
+                                    ui.label(net);
+                                }
+                            });
+                        });
+                    }
+                    col4.group(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(RichText::new("Shares in Window").underline())
+                                .on_hover_text(STATUS_CALCULATOR_WINDOW_SHARES);
+                            ui.label(format!("{:.3}", earnings.window_shares));
+                            ui.label(RichText::new("Chance to be Paid").underline())
+                                .on_hover_text(STATUS_CALCULATOR_WINDOW_CHANCE);
+                            ui.label(HumanNumber::from_f64_to_percent_6_point(
+                                earnings.window_chance * 100.0,
+                            ).as_str());
+                            ui.label(RichText::new("Payout Mean").underline())
+                                .on_hover_text(STATUS_CALCULATOR_PAYOUT_MEAN);
+                            ui.label(
+                                earnings
+                                    .payout_mean
+                                    .map(|s| HumanTime::from_u64(s as u64).display(false))
+                                    .unwrap_or_else(|| "???".to_string()),
+                            );
+                            ui.label(RichText::new("Average Payout").underline())
+                                .on_hover_text(STATUS_CALCULATOR_PAYOUT);
+                            ui.label(
+                                earnings
+                                    .payout()
+                                    .map(|xmr| {
+                                        format!("{} XMR", HumanNumber::from_f64_12_point(xmr))
+                                    })
+                                    .unwrap_or_else(|| "???".to_string()),
+                            );
+                        });
+                    });
+                });
+            });
+        });
+    }
+}
@@ -15,13 +15,20 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use crate::utils::monero_address::Network;
 use crate::{
     app::{Benchmark, eframe_impl::ProcessStatesGui, submenu_enum::SubmenuStatus},
-    disk::{gupax_p2pool_api::GupaxP2poolApi, state::Status},
+    disk::{
+        gupax_p2pool_api::GupaxP2poolApi,
+        payout_export::PayoutExport,
+        state::{P2poolChain, Status},
+    },
     helper::{
         ProcessName, ProcessState,
         node::PubNodeApi,
+        observer::Observer,
         p2pool::{ImgP2pool, PubP2poolApi},
+        payout_scanner::PayoutScanner,
         sys_info::Sys,
         xrig::{
             xmrig::{ImgXmrig, PubXmrigApi},
@@ -30,9 +37,14 @@ use crate::{
         xvb::PubXvbApi,
     },
 };
-use std::sync::{Arc, Mutex};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
 mod benchmarks;
+mod calculator;
+mod observer;
 mod p2pool;
 mod processes;
 
@@ -54,6 +66,14 @@ impl Status {
         max_threads: u16,
         gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
         benchmarks: &[Benchmark],
+        payout_scanner: &Arc<Mutex<PayoutScanner>>,
+        payout_export: &Arc<Mutex<PayoutExport>>,
+        observer: &Arc<Mutex<Observer>>,
+        observer_url: &str,
+        chain: &P2poolChain,
+        p2pool_address: &str,
+        network: Network,
+        node_rpc: SocketAddr,
         _ctx: &egui::Context,
         ui: &mut egui::Ui,
     ) {
@@ -80,6 +100,23 @@ impl Status {
                 gupax_p2pool_api,
                 states.find(ProcessName::P2pool).state == ProcessState::Alive,
                 p2pool_api,
+                p2pool::expected_local_workers(states, xmrig_api, xmrig_proxy_api),
+                payout_scanner,
+                payout_export,
+                observer,
+                observer_url,
+                chain,
+                p2pool_address,
+                network,
+                node_rpc,
+            );
+        //---------------------------------------------------------------------------------------------------- [Calculator]
+        } else if self.submenu == SubmenuStatus::Calculator {
+            self.calculator(
+                ui,
+                states.find(ProcessName::P2pool).state == ProcessState::Alive,
+                p2pool_api,
+                p2pool_img,
             );
         //---------------------------------------------------------------------------------------------------- [Benchmarks]
         } else if self.submenu == SubmenuStatus::Benchmarks {
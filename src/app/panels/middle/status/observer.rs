@@ -0,0 +1,204 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use egui::{Button, RichText};
+use egui_extras::{Column, TableBuilder};
+use log::debug;
+use readable::num::Unsigned;
+
+use crate::{
+    disk::gupax_p2pool_api::GupaxP2poolApi,
+    helper::{ProcessName, observer::Observer, payout_scanner::PayoutScanner},
+    utils::{
+        constants::*,
+        earnings::DAY,
+        human::HumanTime,
+        monero_address::{Network, check_address},
+        xmr::AtomicUnit,
+    },
+};
+
+// Found blocks shown in the table, the observer gives more for the stats.
+const FOUND_BLOCKS_ROWS: usize = 10;
+
+// Blocks found by the pool, shares of every rig of the address and payouts known by the observer.
+// The payouts missing from the log are only added once verified on chain with the view key.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn observer(
+    ui: &mut egui::Ui,
+    observer: &Arc<Mutex<Observer>>,
+    gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+    payout_scanner: &Arc<Mutex<PayoutScanner>>,
+    url: &str,
+    address: &str,
+    network: Network,
+    node_rpc: SocketAddr,
+    height: f32,
+) {
+    debug!("Status Tab | Rendering [P2Pool Observer]");
+    let mut fetch = false;
+    let mut verify = None;
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("P2Pool Observer").underline())
+                .on_hover_text(STATUS_SUBMENU_OBSERVER);
+        });
+        let lock = observer.lock().unwrap();
+        ui.horizontal(|ui| {
+            fetch = ui
+                .add_enabled(
+                    !lock.fetching && !address.is_empty(),
+                    Button::new("Fetch").min_size([0.0, height].into()),
+                )
+                .on_hover_text(STATUS_SUBMENU_OBSERVER_FETCH)
+                .clicked();
+            ui.label(lock.msg.as_str());
+        });
+        if lock.found_blocks.is_empty() && lock.shares.is_empty() && lock.payouts.is_empty() {
+            return;
+        }
+        let now = chrono::Local::now().timestamp();
+        let ago = |timestamp: i64| {
+            format!(
+                "{} ago",
+                HumanTime::from_u64(now.saturating_sub(timestamp).max(0) as u64).display(false)
+            )
+        };
+        ui.columns_const(|[col1, col2, col3]| {
+            col1.vertical_centered(|ui| {
+                ui.label(RichText::new("Last Block Found").underline())
+                    .on_hover_text(STATUS_SUBMENU_OBSERVER_BLOCKS);
+                ui.label(
+                    lock.found_blocks
+                        .first()
+                        .map(|b| ago(b.main_block.timestamp))
+                        .unwrap_or_else(|| "???".to_string()),
+                );
+            });
+            col2.vertical_centered(|ui| {
+                ui.label(RichText::new("Your Shares in 24h").underline())
+                    .on_hover_text(STATUS_SUBMENU_OBSERVER_SHARES);
+                let uncles = lock.shares.iter().filter(|s| s.uncle_of.is_some()).count();
+                ui.label(format!(
+                    "{} ({uncles} uncles in the last {})",
+                    lock.shares_since(now - DAY as i64),
+                    lock.shares.len()
+                ));
+                ui.label(RichText::new("Your Last Share").underline());
+                ui.label(
+                    lock.shares
+                        .first()
+                        .map(|s| ago(s.timestamp))
+                        .unwrap_or_else(|| "???".to_string()),
+                );
+            });
+            col3.vertical_centered(|ui| {
+                ui.label(RichText::new("Payouts").underline())
+                    .on_hover_text(STATUS_SUBMENU_OBSERVER_PAYOUTS);
+                let Some(report) = &lock.report else {
+                    ui.label("No payout found");
+                    return;
+                };
+                ui.label(format!(
+                    "{} in the log, {} missing",
+                    report.verified.len(),
+                    report.missing.len()
+                ));
+                if !report.missing.is_empty() {
+                    let scanner = payout_scanner.lock().unwrap();
+                    if ui
+                        .add_enabled(
+                            scanner.view_key.is_some() && !scanner.scanning,
+                            Button::new("Verify the missing payouts on chain"),
+                        )
+                        .on_hover_text(STATUS_SUBMENU_OBSERVER_VERIFY)
+                        .on_disabled_hover_text(STATUS_SUBMENU_OBSERVER_VERIFY_LOCKED)
+                        .clicked()
+                    {
+                        verify = Some(report.missing.iter().map(|p| p.height).collect());
+                    }
+                }
+                if !report.unconfirmed.is_empty() {
+                    let blocks: Vec<String> =
+                        report.unconfirmed.iter().map(|b| b.to_string()).collect();
+                    ui.label(
+                        RichText::new(format!("Unknown to the observer: {}", blocks.join(", ")))
+                            .color(ORANGE),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_UNCONFIRMED);
+                }
+            });
+        });
+        if lock.found_blocks.is_empty() {
+            return;
+        }
+        let width = ui.available_width() / 4.0 - SPACE;
+        let rows = lock.found_blocks.len().min(FOUND_BLOCKS_ROWS);
+        TableBuilder::new(ui)
+            .vscroll(false)
+            .columns(Column::exact(width), 4)
+            .header(height, |mut header| {
+                for title in ["Monero Height", "Sidechain Height", "Found", "Reward"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(height, rows, |mut row| {
+                    let block = &lock.found_blocks[row.index()];
+                    row.col(|ui| {
+                        ui.label(Unsigned::from(block.main_block.height).as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(Unsigned::from(block.side_height).as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(ago(block.main_block.timestamp));
+                    });
+                    row.col(|ui| {
+                        ui.label(format!(
+                            "{} XMR",
+                            AtomicUnit::from_u64(block.main_block.reward).human_number_12_point()
+                        ));
+                    });
+                });
+            });
+    });
+    if fetch {
+        Observer::start(
+            observer,
+            gupax_p2pool_api,
+            url.to_string(),
+            address.to_string(),
+        );
+    }
+    if let Some(heights) = verify {
+        match check_address(address, ProcessName::P2pool, network) {
+            Ok(address) => {
+                PayoutScanner::verify(payout_scanner, gupax_p2pool_api, address, node_rpc, heights)
+            }
+            Err(e) => payout_scanner.lock().unwrap().msg = format!("P2Pool address: {e}"),
+        }
+    }
+}
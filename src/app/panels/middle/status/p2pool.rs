@@ -15,29 +15,59 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::sync::{Arc, Mutex};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
 
-use egui::{Button, Label, RichText, ScrollArea, Separator, Slider, TextStyle};
+use egui::{
+    Button, Label, ProgressBar, RichText, ScrollArea, Separator, Slider, TextEdit, TextStyle,
+};
+use egui_extras::{Column, TableBuilder};
 use readable::num::Unsigned;
 use strum::{EnumCount, IntoEnumIterator};
 
 use crate::{
+    app::eframe_impl::ProcessStatesGui,
     disk::{
         gupax_p2pool_api::GupaxP2poolApi,
-        state::Status,
+        payout_export::{ExportFormat, PayoutExport},
+        state::{P2poolChain, Status},
         status::{Hash, PayoutView},
     },
-    helper::p2pool::PubP2poolApi,
-    utils::constants::*,
+    helper::{
+        ProcessName,
+        observer::Observer,
+        p2pool::PubP2poolApi,
+        payout_scanner::PayoutScanner,
+        xrig::{xmrig::PubXmrigApi, xmrig_proxy::PubXmrigProxyApi},
+        xvb::nodes::Pool,
+    },
+    utils::{
+        constants::*,
+        human::HumanTime,
+        monero_address::{Network, check_address},
+        view_key::ViewKey,
+    },
 };
 
 impl Status {
+    #[allow(clippy::too_many_arguments)]
     pub fn p2pool(
         &mut self,
         ui: &mut egui::Ui,
         gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
         p2pool_alive: bool,
         p2pool_api: &Arc<Mutex<PubP2poolApi>>,
+        expected_local_workers: usize,
+        payout_scanner: &Arc<Mutex<PayoutScanner>>,
+        payout_export: &Arc<Mutex<PayoutExport>>,
+        observer: &Arc<Mutex<Observer>>,
+        observer_url: &str,
+        chain: &P2poolChain,
+        address: &str,
+        network: Network,
+        node_rpc: SocketAddr,
     ) {
         let api = gupax_p2pool_api.lock().unwrap();
         // let height = size.y;
@@ -116,80 +146,64 @@ impl Status {
                             };
                         });
                 });
-            });
-            // });
-            drop(api);
-            // Payout/Share Calculator
-            // let button = (width / 20.0) - (SPACE * 1.666);
-            ui.group(|ui| {
-                ui.set_width(ui.available_width());
+                // Export
                 ui.horizontal(|ui| {
-                    // ui.set_min_width(width - SPACE);
-                    let width = ui.available_width() / 10.0;
-                    if ui
-                        .add_sized(
-                            [width, height],
-                            Button::selectable(!self.manual_hash, "Automatic"),
-                        )
-                        .on_hover_text(STATUS_SUBMENU_AUTOMATIC)
-                        .clicked()
-                    {
-                        self.manual_hash = false;
-                    }
-                    ui.separator();
-                    if ui
-                        .add_sized(
-                            [width, height],
-                            Button::selectable(self.manual_hash, "Manual"),
-                        )
-                        .on_hover_text(STATUS_SUBMENU_MANUAL)
-                        .clicked()
-                    {
-                        self.manual_hash = true;
-                    }
-                    ui.separator();
-                    ui.add_enabled_ui(self.manual_hash, |ui| {
-                        if ui
-                            .selectable_label(self.hash_metric == Hash::Hash, "Hash")
-                            .on_hover_text(STATUS_SUBMENU_HASH)
-                            .clicked()
-                        {
-                            self.hash_metric = Hash::Hash;
-                        }
-                        ui.separator();
-                        if ui
-                            .selectable_label(self.hash_metric == Hash::Kilo, "Kilo")
-                            .on_hover_text(STATUS_SUBMENU_KILO)
-                            .clicked()
-                        {
-                            self.hash_metric = Hash::Kilo;
-                        }
-                        ui.separator();
-                        if ui
-                            .selectable_label(self.hash_metric == Hash::Mega, "Mega")
-                            .on_hover_text(STATUS_SUBMENU_MEGA)
-                            .clicked()
-                        {
-                            self.hash_metric = Hash::Mega;
-                        }
-                        ui.separator();
-                        if ui
-                            .selectable_label(self.hash_metric == Hash::Giga, "Giga")
-                            .on_hover_text(STATUS_SUBMENU_GIGA)
-                            .clicked()
-                        {
-                            self.hash_metric = Hash::Giga;
+                    let thread = payout_export.lock().unwrap().thread;
+                    ui.add_enabled_ui(!thread, |ui| {
+                        for format in [ExportFormat::Csv, ExportFormat::Json] {
+                            if ui
+                                .add_sized(
+                                    [size_text * 5.0, height],
+                                    Button::new(format!("Export {format}")),
+                                )
+                                .on_hover_text(STATUS_SUBMENU_EXPORT)
+                                .clicked()
+                            {
+                                payout_export.lock().unwrap().msg.clear();
+                                PayoutExport::spawn_thread(
+                                    payout_export,
+                                    api.log.clone(),
+                                    format,
+                                    self.price_source.clone(),
+                                );
+                            }
                         }
-                        ui.separator();
-                        ui.spacing_mut().slider_width = (ui.available_width() / 1.2).max(0.0);
-                        ui.add_sized(
-                            [0.0, height],
-                            Slider::new(&mut self.hashrate, 1.0..=1_000.0)
-                                .suffix(format!(" {}", self.hash_metric)),
-                        );
                     });
-                })
+                    ui.add_sized(
+                        [ui.available_width() / 2.0, height],
+                        TextEdit::singleline(&mut self.price_source)
+                            .hint_text("Prices: CSV file or URL (optional)"),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_PRICE_SOURCE);
+                    ui.label(payout_export.lock().unwrap().msg.as_str());
+                });
             });
+            // });
+            drop(api);
+            // Payout verification with the view key
+            self.payout_verification(
+                ui,
+                gupax_p2pool_api,
+                payout_scanner,
+                address,
+                network,
+                node_rpc,
+                height,
+            );
+            // Blocks, shares and payouts seen by the observer
+            super::observer::observer(
+                ui,
+                observer,
+                gupax_p2pool_api,
+                payout_scanner,
+                observer_url,
+                address,
+                network,
+                node_rpc,
+                height,
+            );
+            // Payout/Share Calculator
+            self.hashrate_input(ui, height);
             // Actual stats
             ui.add_space(height / 2.0);
             ui.add_enabled_ui(p2pool_alive, |ui| {
@@ -310,6 +324,526 @@ impl Status {
                 });
                 drop(api);
             });
+            // Workers connected to the stratum
+            ui.add_space(height / 2.0);
+            ui.add_enabled_ui(p2pool_alive, |ui| {
+                workers(ui, p2pool_api, expected_local_workers, height);
+            });
+            // Our shares in the PPLNS window
+            ui.add_space(height / 2.0);
+            ui.add_enabled_ui(p2pool_alive, |ui| {
+                pplns_window(ui, p2pool_api, height);
+            });
+            // Shares found by the local P2Pool
+            ui.add_space(height / 2.0);
+            shares(ui, gupax_p2pool_api, p2pool_api, chain, network, height);
         });
     }
 }
+
+impl Status {
+    // Choice between the hashrate of P2Pool and a manual one for the calculations.
+    pub(super) fn hashrate_input(&mut self, ui: &mut egui::Ui, height: f32) {
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                // ui.set_min_width(width - SPACE);
+                let width = ui.available_width() / 10.0;
+                if ui
+                    .add_sized(
+                        [width, height],
+                        Button::selectable(!self.manual_hash, "Automatic"),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_AUTOMATIC)
+                    .clicked()
+                {
+                    self.manual_hash = false;
+                }
+                ui.separator();
+                if ui
+                    .add_sized(
+                        [width, height],
+                        Button::selectable(self.manual_hash, "Manual"),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_MANUAL)
+                    .clicked()
+                {
+                    self.manual_hash = true;
+                }
+                ui.separator();
+                ui.add_enabled_ui(self.manual_hash, |ui| {
+                    if ui
+                        .selectable_label(self.hash_metric == Hash::Hash, "Hash")
+                        .on_hover_text(STATUS_SUBMENU_HASH)
+                        .clicked()
+                    {
+                        self.hash_metric = Hash::Hash;
+                    }
+                    ui.separator();
+                    if ui
+                        .selectable_label(self.hash_metric == Hash::Kilo, "Kilo")
+                        .on_hover_text(STATUS_SUBMENU_KILO)
+                        .clicked()
+                    {
+                        self.hash_metric = Hash::Kilo;
+                    }
+                    ui.separator();
+                    if ui
+                        .selectable_label(self.hash_metric == Hash::Mega, "Mega")
+                        .on_hover_text(STATUS_SUBMENU_MEGA)
+                        .clicked()
+                    {
+                        self.hash_metric = Hash::Mega;
+                    }
+                    ui.separator();
+                    if ui
+                        .selectable_label(self.hash_metric == Hash::Giga, "Giga")
+                        .on_hover_text(STATUS_SUBMENU_GIGA)
+                        .clicked()
+                    {
+                        self.hash_metric = Hash::Giga;
+                    }
+                    ui.separator();
+                    ui.spacing_mut().slider_width = (ui.available_width() / 1.2).max(0.0);
+                    ui.add_sized(
+                        [0.0, height],
+                        Slider::new(&mut self.hashrate, 1.0..=1_000.0)
+                            .suffix(format!(" {}", self.hash_metric)),
+                    );
+                });
+            })
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn payout_verification(
+        &mut self,
+        ui: &mut egui::Ui,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        payout_scanner: &Arc<Mutex<PayoutScanner>>,
+        address: &str,
+        network: Network,
+        node_rpc: SocketAddr,
+        height: f32,
+    ) {
+        let mut scan = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.vertical_centered(|ui| {
+                ui.label(RichText::new("Payout Verification").underline())
+                    .on_hover_text(STATUS_SUBMENU_SCAN);
+            });
+            let mut scanner = payout_scanner.lock().unwrap();
+            let width = ui.available_width() / 4.0;
+            if scanner.view_key.is_some() {
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(!scanner.scanning, |ui| {
+                        scan = ui
+                            .add_sized([width / 2.0, height], Button::new("Scan"))
+                            .on_hover_text(STATUS_SUBMENU_SCAN)
+                            .clicked();
+                        if ui
+                            .add_sized([width / 2.0, height], Button::new("Lock"))
+                            .on_hover_text(STATUS_SUBMENU_LOCK)
+                            .clicked()
+                        {
+                            scanner.forget_key();
+                        }
+                    });
+                    ui.label(scanner.msg.as_str());
+                });
+                if scanner.scanning {
+                    ui.add(ProgressBar::new(scanner.prog / 100.0));
+                }
+                if let Some(report) = &scanner.report
+                    && !report.unconfirmed.is_empty()
+                {
+                    let blocks: Vec<String> =
+                        report.unconfirmed.iter().map(|b| b.to_string()).collect();
+                    ui.label(
+                        RichText::new(format!(
+                            "Payouts not found on chain in blocks: {}",
+                            blocks.join(", ")
+                        ))
+                        .color(ORANGE),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_UNCONFIRMED);
+                }
+                return;
+            }
+            // the view key is entered once, then only the passphrase is asked.
+            let adding = self.encrypted_view_key.is_empty();
+            ui.horizontal(|ui| {
+                if adding {
+                    ui.add_sized(
+                        [width, height],
+                        TextEdit::singleline(&mut scanner.input_view_key)
+                            .password(true)
+                            .hint_text("Private view key"),
+                    )
+                    .on_hover_text(STATUS_SUBMENU_VIEW_KEY);
+                }
+                ui.add_sized(
+                    [width, height],
+                    TextEdit::singleline(&mut scanner.passphrase)
+                        .password(true)
+                        .hint_text("Passphrase"),
+                )
+                .on_hover_text(STATUS_SUBMENU_PASSPHRASE);
+                if adding {
+                    if ui
+                        .add_enabled(!scanner.passphrase.is_empty(), Button::new("Encrypt"))
+                        .on_hover_text(STATUS_SUBMENU_ENCRYPT)
+                        .clicked()
+                    {
+                        let result = scanner.input_view_key.parse::<ViewKey>().and_then(|key| {
+                            if let Ok(address) =
+                                check_address(address, ProcessName::P2pool, network)
+                            {
+                                key.check(&address)?;
+                            }
+                            Ok(key)
+                        });
+                        match result {
+                            Ok(key) => {
+                                self.encrypted_view_key = key.encrypt(&scanner.passphrase);
+                                scanner.view_key = Some(key);
+                                scanner.msg = "View key encrypted, save to keep it".to_string();
+                            }
+                            Err(e) => scanner.msg = e.to_string(),
+                        }
+                        scanner.wipe_inputs();
+                    }
+                } else {
+                    if ui
+                        .add_enabled(!scanner.passphrase.is_empty(), Button::new("Unlock"))
+                        .on_hover_text(STATUS_SUBMENU_UNLOCK)
+                        .clicked()
+                    {
+                        match ViewKey::decrypt(&self.encrypted_view_key, &scanner.passphrase) {
+                            Ok(key) => {
+                                scanner.view_key = Some(key);
+                                scanner.msg = "Unlocked".to_string();
+                            }
+                            Err(e) => scanner.msg = e.to_string(),
+                        }
+                        scanner.wipe_inputs();
+                    }
+                    if ui
+                        .button("Forget")
+                        .on_hover_text(STATUS_SUBMENU_FORGET)
+                        .clicked()
+                    {
+                        self.encrypted_view_key.clear();
+                        scanner.forget_key();
+                    }
+                }
+                ui.label(scanner.msg.as_str());
+            });
+        });
+        if scan {
+            match check_address(address, ProcessName::P2pool, network) {
+                Ok(address) => {
+                    PayoutScanner::start(payout_scanner, gupax_p2pool_api, address, node_rpc)
+                }
+                Err(e) => payout_scanner.lock().unwrap().msg = format!("P2Pool address: {e}"),
+            }
+        }
+    }
+}
+
+// Number of miners started by Gupax that are currently mining on the local P2Pool stratum.
+pub fn expected_local_workers(
+    states: &ProcessStatesGui,
+    xmrig_api: &Arc<Mutex<PubXmrigApi>>,
+    xmrig_proxy_api: &Arc<Mutex<PubXmrigProxyApi>>,
+) -> usize {
+    let xmrig = states.is_alive(ProcessName::Xmrig)
+        && matches!(xmrig_api.lock().unwrap().pool, Some(Pool::P2pool(_)));
+    let proxy = states.is_alive(ProcessName::XmrigProxy)
+        && matches!(xmrig_proxy_api.lock().unwrap().pool, Some(Pool::P2pool(_)));
+    xmrig as usize + proxy as usize
+}
+
+fn workers(
+    ui: &mut egui::Ui,
+    p2pool_api: &Arc<Mutex<PubP2poolApi>>,
+    expected_local_workers: usize,
+    height: f32,
+) {
+    let api = p2pool_api.lock().unwrap();
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new(format!("Workers: {}", api.workers.len())).underline())
+                .on_hover_text(STATUS_SUBMENU_WORKERS);
+            let missing = api.missing_local_workers(expected_local_workers);
+            if missing > 0 {
+                ui.label(
+                    RichText::new(format!(
+                        "{missing} miner{} started by Gupax {} not connected to the local P2Pool stratum",
+                        if missing > 1 { "s" } else { "" },
+                        if missing > 1 { "are" } else { "is" }
+                    ))
+                    .color(ORANGE),
+                )
+                .on_hover_text(STATUS_SUBMENU_WORKERS_MISSING);
+            }
+        });
+        if api.workers.is_empty() {
+            return;
+        }
+        let width = ui.available_width() / 5.0 - SPACE;
+        TableBuilder::new(ui)
+            .vscroll(false)
+            .columns(Column::exact(width), 5)
+            .header(height, |mut header| {
+                for title in ["IP", "Name", "Hashrate", "Difficulty", "Uptime"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(height, api.workers.len(), |mut row| {
+                    let worker = &api.workers[row.index()];
+                    row.col(|ui| {
+                        ui.label(worker.ip.as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(worker.name.as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("{} H/s", Unsigned::from(worker.hashrate)));
+                    });
+                    row.col(|ui| {
+                        ui.label(Unsigned::from(worker.difficulty).as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(HumanTime::from_u64(worker.uptime).display(false));
+                    });
+                });
+            });
+    });
+}
+
+fn shares(
+    ui: &mut egui::Ui,
+    gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+    p2pool_api: &Arc<Mutex<PubP2poolApi>>,
+    chain: &P2poolChain,
+    network: Network,
+    height: f32,
+) {
+    let share_mean = p2pool_api.lock().unwrap().p2pool_share_mean.as_secs();
+    let history = gupax_p2pool_api.lock().unwrap().shares.of(chain, network);
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new(format!("Shares Found: {}", history.len())).underline())
+                .on_hover_text(STATUS_SUBMENU_SHARES);
+        });
+        if history.is_empty() {
+            return;
+        }
+        let unknown = || "???".to_string();
+        let time = |secs: Option<u64>| {
+            secs.map(|s| HumanTime::from_u64(s).display(false))
+                .unwrap_or_else(unknown)
+        };
+        ui.columns_const(|[col1, col2, col3]| {
+            col1.vertical_centered(|ui| {
+                ui.label(RichText::new("Since Last Share").underline())
+                    .on_hover_text(STATUS_SUBMENU_SINCE_LAST_SHARE);
+                let since_last = history.since_last(chrono::Local::now().timestamp());
+                // a share is overdue after three times the mean, it only happens 5% of the time
+                let overdue = share_mean > 0 && since_last.is_some_and(|s| s > share_mean * 3);
+                let text = RichText::new(time(since_last));
+                ui.label(if overdue { text.color(ORANGE) } else { text });
+                ui.label(RichText::new("Your P2Pool Share Mean").underline())
+                    .on_hover_text(STATUS_SUBMENU_P2POOL_SHARE_MEAN);
+                ui.label(time((share_mean > 0).then_some(share_mean)));
+            });
+            col2.vertical_centered(|ui| {
+                ui.label(RichText::new("Mean Interval").underline())
+                    .on_hover_text(STATUS_SUBMENU_SHARE_INTERVAL);
+                ui.label(time(history.mean_interval()));
+                ui.label(RichText::new("Median Interval").underline())
+                    .on_hover_text(STATUS_SUBMENU_SHARE_INTERVAL);
+                ui.label(time(history.median_interval()));
+            });
+            col3.vertical_centered(|ui| {
+                ui.label(RichText::new("Average Effort").underline())
+                    .on_hover_text(STATUS_SUBMENU_SHARE_EFFORT);
+                ui.label(
+                    history
+                        .average_effort()
+                        .map(|e| format!("{e:.2}%"))
+                        .unwrap_or_else(unknown),
+                );
+                ui.label(RichText::new("Last Share Effort").underline())
+                    .on_hover_text(STATUS_SUBMENU_SHARE_EFFORT);
+                ui.label(
+                    history
+                        .last()
+                        .map(|s| format!("{:.2}% at height {}", s.effort, s.sidechain_height))
+                        .unwrap_or_else(unknown),
+                );
+            });
+        });
+        ui.add_space(height / 2.0);
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("Luck Distribution").underline())
+                .on_hover_text(STATUS_SUBMENU_LUCK);
+        });
+        let bins = history.luck_distribution();
+        let max = bins
+            .iter()
+            .map(|b| (b.shares as f64).max(b.expected))
+            .fold(1.0, f64::max);
+        let width = ui.available_width() / 4.0 - SPACE;
+        TableBuilder::new(ui)
+            .vscroll(false)
+            .column(Column::exact(width))
+            .column(Column::exact(width / 2.0))
+            .column(Column::exact(width / 2.0))
+            .column(Column::remainder())
+            .header(height, |mut header| {
+                for title in ["Effort", "Shares", "Expected", ""] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(height, bins.len(), |mut row| {
+                    let bin = &bins[row.index()];
+                    row.col(|ui| {
+                        ui.label(match bin.to {
+                            Some(to) => format!("{:.0}% - {to:.0}%", bin.from),
+                            None => format!("{:.0}% +", bin.from),
+                        });
+                    });
+                    row.col(|ui| {
+                        ui.label(bin.shares.to_string());
+                    });
+                    row.col(|ui| {
+                        ui.label(format!("{:.1}", bin.expected));
+                    });
+                    row.col(|ui| {
+                        ui.add(
+                            ProgressBar::new((bin.shares as f64 / max) as f32)
+                                .desired_height(height / 2.0),
+                        );
+                    });
+                });
+            });
+    });
+}
+
+fn pplns_window(ui: &mut egui::Ui, p2pool_api: &Arc<Mutex<PubP2poolApi>>, height: f32) {
+    let api = p2pool_api.lock().unwrap();
+    let window = &api.pplns_window;
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "PPLNS Window: {} shares in {} blocks",
+                    api.sidechain_shares, window.length
+                ))
+                .underline(),
+            )
+            .on_hover_text(STATUS_SUBMENU_PPLNS_WINDOW);
+            match window.last_expiry() {
+                Some(expiry) => {
+                    let text = RichText::new(format!(
+                        "Your last share leaves the window in {}",
+                        HumanTime::from_u64(expiry).display(false)
+                    ));
+                    // a new share is not expected before the last one leaves
+                    let share_mean = api.p2pool_share_mean.as_secs();
+                    ui.label(if share_mean > expiry {
+                        text.color(ORANGE)
+                    } else {
+                        text
+                    })
+                    .on_hover_text(STATUS_SUBMENU_PPLNS_EXPIRY);
+                    ui.label(format!(
+                        "Payout if a Monero block is found now: {} XMR",
+                        window
+                            .projected_payout(api.reward, api.p2pool_difficulty_u64)
+                            .human_number_12_point()
+                    ))
+                    .on_hover_text(STATUS_SUBMENU_PPLNS_PAYOUT);
+                }
+                None => {
+                    ui.label(RichText::new("You have no share in the PPLNS window").color(ORANGE));
+                }
+            }
+            let unknown = (api.sidechain_shares as usize).saturating_sub(window.shares.len());
+            if unknown > 0 {
+                ui.label(format!(
+                    "{unknown} share{} found before Gupax recorded them not shown",
+                    if unknown > 1 { "s" } else { "" }
+                ));
+            }
+        });
+        if window.length == 0 {
+            return;
+        }
+        // The window as a bar, the left side is the oldest block, about to leave the window.
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), height),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+        for share in &window.shares {
+            let x = rect.left() + rect.width() * share.blocks_left as f32 / window.length as f32;
+            painter.line_segment(
+                [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                egui::Stroke::new(2.0, GREEN),
+            );
+        }
+        ui.horizontal(|ui| {
+            ui.label("Leaving");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label("Newest");
+            });
+        });
+        if window.shares.is_empty() {
+            return;
+        }
+        let width = ui.available_width() / 3.0 - SPACE;
+        TableBuilder::new(ui)
+            .vscroll(false)
+            .columns(Column::exact(width), 3)
+            .header(height, |mut header| {
+                for title in ["Sidechain Height", "Difficulty", "Leaves the Window In"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(height, window.shares.len(), |mut row| {
+                    // most recent first
+                    let share = &window.shares[window.shares.len() - 1 - row.index()];
+                    row.col(|ui| {
+                        ui.label(Unsigned::from(share.sidechain_height).as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(Unsigned::from(share.difficulty).as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(format!(
+                            "{} ({} blocks)",
+                            HumanTime::from_u64(share.expires_in).display(false),
+                            share.blocks_left
+                        ));
+                    });
+                });
+            });
+    });
+}
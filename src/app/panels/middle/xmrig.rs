@@ -27,6 +27,7 @@ use crate::helper::xrig::xmrig::PubXmrigApi;
 use crate::helper::{Process, ProcessName};
 use crate::miscs::height_txt_before_button;
 use crate::regex::REGEXES;
+use crate::utils::monero_address::Network;
 use egui::{Checkbox, Image, Ui, vec2};
 use log::*;
 
@@ -47,6 +48,7 @@ impl Xmrig {
         _ctx: &egui::Context,
         ui: &mut egui::Ui,
         p2pool_stratum_port: u16,
+        network: Network,
     ) {
         let logo = Some(Image::from_bytes("bytes:/xmrig.png", BYTES_XMRIG));
         header_tab(
@@ -88,7 +90,13 @@ impl Xmrig {
                 );
                 ui.add_enabled_ui(self.arguments.is_empty(), |ui| {
                     debug!("XMRig Tab | Rendering [Address]");
-                    monero_address_field(&mut self.address, ui, XMRIG_ADDRESS);
+                    monero_address_field(
+                        &mut self.address,
+                        ui,
+                        XMRIG_ADDRESS,
+                        ProcessName::Xmrig,
+                        network,
+                    );
                 });
             }
             if self.simple {
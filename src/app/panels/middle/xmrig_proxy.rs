@@ -30,14 +30,15 @@ use crate::helper::{Helper, Process, ProcessName};
 use crate::miscs::height_txt_before_button;
 use crate::regex::REGEXES;
 use crate::utils::constants::IP_NOT_FOUND;
+use crate::utils::monero_address::Network;
 use crate::{
     SPACE, START_OPTIONS_HOVER, XMRIG_API_IP, XMRIG_API_PORT, XMRIG_IP, XMRIG_KEEPALIVE,
-    XMRIG_NAME, XMRIG_PORT, XMRIG_PROXY_INPUT, XMRIG_PROXY_REDIRECT, XMRIG_PROXY_URL, XMRIG_RIG,
-    XMRIG_TLS,
+    XMRIG_NAME, XMRIG_PORT, XMRIG_PROXY_ADDRESS, XMRIG_PROXY_INPUT, XMRIG_PROXY_REDIRECT,
+    XMRIG_PROXY_URL, XMRIG_RIG, XMRIG_TLS,
 };
 
 use super::common::list_poolnode::PoolNode;
-use super::common::state_edit_field::StateTextEdit;
+use super::common::state_edit_field::{StateTextEdit, monero_address_field};
 use super::{HELP_STRATUM_IP, HELP_STRATUM_PORT, XMRIG_API_TOKEN};
 
 impl XmrigProxy {
@@ -55,6 +56,7 @@ impl XmrigProxy {
         public_ip: &Arc<Mutex<Option<Ipv4Addr>>>,
         port_reachable: &Arc<Mutex<bool>>,
         helper: &Arc<Mutex<Helper>>,
+        network: Network,
     ) {
         header_tab(
             ui,
@@ -99,6 +101,14 @@ impl XmrigProxy {
                 if !self.arguments.is_empty() {
                     ui.disable();
                 }
+                debug!("XMRig-Proxy Tab | Rendering [Address]");
+                monero_address_field(
+                    &mut self.address,
+                    ui,
+                    XMRIG_PROXY_ADDRESS,
+                    ProcessName::XmrigProxy,
+                    network,
+                );
                 ui.add_space(SPACE);
                 // ui.style_mut().spacing.icon_width_inner = width / 45.0;
                 // ui.style_mut().spacing.icon_width = width / 35.0;
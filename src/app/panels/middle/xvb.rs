@@ -15,34 +15,54 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
-use egui::{Align, Image, Label, RichText, ScrollArea, TextStyle, Ui};
+use chrono::{DateTime, Local};
+use egui::{Align, Image, RichText, ScrollArea, TextStyle, Ui};
+use egui_extras::{Column, TableBuilder};
 use log::debug;
 use readable::num::Float;
 use readable::up::Uptime;
-use strum::EnumCount;
+use strum::{EnumCount, IntoEnumIterator};
 
 use crate::app::panels::middle::common::console::console;
 use crate::app::panels::middle::common::header_tab::header_tab;
-use crate::app::panels::middle::common::toggle::toggle_ui_compact;
-use crate::disk::state::{ManualDonationLevel, ManualDonationMetric, XvbMode};
-use crate::helper::ProcessName;
+use crate::disk::state::{
+    ManualDonationLevel, ManualDonationMetric, XvbAccount, XvbCoordination, XvbGoal, XvbMode,
+    XvbPoolEndpoint, XvbSplit,
+};
+use crate::disk::xvb_history::XvbHistory;
 use crate::helper::xrig::xmrig::PubXmrigApi;
 use crate::helper::xrig::xmrig_proxy::PubXmrigProxyApi;
 use crate::helper::xvb::PubXvbApi;
-use crate::helper::xvb::priv_stats::RuntimeMode;
+use crate::helper::xvb::algorithm::{Decision, Stats};
+use crate::helper::xvb::priv_stats::{RuntimeGoal, RuntimeMode};
+use crate::helper::xvb::registration::{RegistrationStatus, XvbRegistration};
+use crate::helper::xvb::siblings::instance_name;
+use crate::helper::xvb::what_if::{WhatIf, WhatIfOutcome};
+use crate::helper::{Helper, ProcessName};
 use crate::miscs::height_txt_before_button;
 use crate::utils::constants::{
-    ORANGE, XVB_DONATED_1H_FIELD, XVB_DONATED_24H_FIELD, XVB_DONATION_LEVEL_DONOR_HELP,
+    GREEN, ORANGE, XVB_DONATED_1H_FIELD, XVB_DONATED_24H_FIELD, XVB_DONATION_LEVEL_DONOR_HELP,
     XVB_DONATION_LEVEL_MEGA_DONOR_HELP, XVB_DONATION_LEVEL_VIP_DONOR_HELP,
     XVB_DONATION_LEVEL_WHALE_DONOR_HELP, XVB_FAILURE_FIELD, XVB_HERO_SELECT, XVB_MANUAL_POOL,
     XVB_MANUAL_SLIDER_MANUAL_P2POOL_HELP, XVB_MANUAL_SLIDER_MANUAL_XVB_HELP,
     XVB_MODE_MANUAL_DONATION_LEVEL_HELP, XVB_MODE_MANUAL_P2POOL_HELP, XVB_MODE_MANUAL_XVB_HELP,
     XVB_ROUND_TYPE_FIELD, XVB_URL_RULES, XVB_WINNER_FIELD,
 };
-use crate::utils::regex::Regexes;
-use crate::{XVB_MINING_ON_FIELD, XVB_P2POOL_BUFFER, XVB_SIDECHAIN};
+use crate::utils::constants::{
+    RED, XVB_ACCOUNTS, XVB_COORDINATION, XVB_GOAL_CAP, XVB_GOAL_DAILY_PERCENT_HELP,
+    XVB_GOAL_ROUND_HELP, XVB_MODE_GOAL_HELP, XVB_REGISTRATION, XVB_REGISTRATION_ENDPOINT,
+    XVB_REGISTRATION_VERIFY, XVB_SIBLING_NAME, XVB_SIBLINGS,
+};
+use crate::utils::constants::{
+    XVB_DECISIONS, XVB_HISTORY, XVB_HISTORY_DONATED, XVB_HISTORY_ROLL, XVB_HISTORY_WINNING,
+    XVB_MODE_PREDICTIVE_HELP, XVB_POOLS, XVB_SPLIT_THREADS_HELP, XVB_SPLIT_TIME_HELP, XVB_WHAT_IF,
+};
+use crate::utils::human::HumanTime;
+use crate::utils::monero_address::{Network, check_address};
+use crate::{XVB_MINING_ON_FIELD, XVB_P2POOL_BUFFER, XVB_SIDECHAIN, XVB_TIME_ALGO};
 use crate::{
     constants::{BYTES_XVB, SPACE},
     utils::constants::XVB_URL,
@@ -59,6 +79,10 @@ impl crate::disk::state::Xvb {
         api: &Arc<Mutex<PubXvbApi>>,
         gui_api_xmrig: &Arc<Mutex<PubXmrigApi>>,
         gui_api_xp: &Arc<Mutex<PubXmrigProxyApi>>,
+        xvb_history: &Arc<Mutex<XvbHistory>>,
+        xvb_registration: &Arc<Mutex<XvbRegistration>>,
+        what_if: &mut Option<(WhatIf, WhatIfOutcome)>,
+        goal_projection: &mut Option<(WhatIf, WhatIfOutcome)>,
         is_alive: bool,
     ) {
         // logo and website link
@@ -129,6 +153,12 @@ impl crate::disk::state::Xvb {
                                 ui.selectable_value(&mut self.mode, XvbMode::ManualDonationLevel,
                                      XvbMode::ManualDonationLevel.to_string())
                                 .on_hover_text(XVB_MODE_MANUAL_DONATION_LEVEL_HELP);
+                                ui.selectable_value(&mut self.mode, XvbMode::Predictive,
+                                     XvbMode::Predictive.to_string())
+                                .on_hover_text(XVB_MODE_PREDICTIVE_HELP);
+                                ui.selectable_value(&mut self.mode, XvbMode::Goal,
+                                     XvbMode::Goal.to_string())
+                                .on_hover_text(XVB_MODE_GOAL_HELP);
                         });
                         if self.mode == XvbMode::ManualXvb || self.mode == XvbMode::ManualP2pool {
 
@@ -211,6 +241,12 @@ impl crate::disk::state::Xvb {
 
                             api.lock().unwrap().stats_priv.runtime_manual_donation_level = self.manual_donation_level.clone().into();
                             });
+            ui.add_space(SPACE);
+                        }
+
+                        if self.mode == XvbMode::Goal {
+                            ui.add_space(SPACE);
+                            goal(ui, self, &api.lock().unwrap().decisions, goal_projection);
             ui.add_space(SPACE);
                         }
                     });
@@ -232,8 +268,9 @@ impl crate::disk::state::Xvb {
             // Set runtime_mode & runtime_manual_amount
             api.lock().unwrap().stats_priv.runtime_mode = self.mode.clone().into();
             api.lock().unwrap().stats_priv.runtime_manual_amount = self.manual_amount_raw;
+            api.lock().unwrap().stats_priv.runtime_goal = RuntimeGoal::from(&*self);
          ui.add_space(SPACE);
-        let p2pool_buffer_enabled = matches!(self.mode, XvbMode::Auto | XvbMode::Hero);
+        let p2pool_buffer_enabled = matches!(self.mode, XvbMode::Auto | XvbMode::Hero | XvbMode::Predictive | XvbMode::Goal);
 
 
          ui.horizontal(|ui|{
@@ -259,25 +296,63 @@ impl crate::disk::state::Xvb {
         // Allow user to choose XvB pool manually
         // checkbox to enable
         ui.checkbox(&mut self.manual_pool_enabled, "Manual selection of the XvB pool").on_hover_text(XVB_MANUAL_POOL);
-        // slider for EU or NA
-            ui.horizontal(|ui|{
-        ui.add_enabled_ui(self.manual_pool_enabled, |ui|{
-                ui.style_mut().override_text_style = Some(TextStyle::Heading);
-                ui.add_sized([0.0, text_height], Label::new(" [ NA"));
-                toggle_ui_compact(&mut self.manual_pool_eu, ui);
-                ui.add_sized([0.0, text_height], Label::new("EU ]"));
+        // choice from the list of pools
+        ui.add_enabled_ui(self.manual_pool_enabled, |ui| {
+            egui::ComboBox::from_id_salt("xvb_manual_pool")
+                .selected_text(&self.manual_pool)
+                .show_ui(ui, |ui| {
+                    for pool in &self.pools {
+                        ui.selectable_value(&mut self.manual_pool, pool.label.clone(), &pool.label);
+                    }
+                });
+        });
+        pools(ui, &mut self.pools);
+        accounts(ui, &mut self.accounts);
+        ui.horizontal(|ui| {
+            ui.label("Siblings");
+            ui.text_edit_singleline(&mut self.siblings).on_hover_text(XVB_SIBLINGS);
+            ui.label("Name");
+            let named = !self.siblings.trim().is_empty() || self.coordination == XvbCoordination::Follower;
+            ui.add_enabled(named, egui::TextEdit::singleline(&mut self.sibling_name).hint_text(instance_name(""))).on_hover_text(XVB_SIBLING_NAME);
+        });
+        // coordination of the algorithm with the other instances on the LAN
+        ui.horizontal(|ui| {
+            ui.label("Coordination");
+            for coordination in XvbCoordination::iter() {
+                ui.selectable_value(&mut self.coordination, coordination, coordination.to_string()).on_hover_text(XVB_COORDINATION);
+            }
+            ui.add_enabled_ui(self.coordination != XvbCoordination::Alone, |ui| {
+                ui.label("Address");
+                ui.text_edit_singleline(&mut self.coordination_address).on_hover_text(XVB_COORDINATION);
+                ui.label("Key");
+                ui.add(egui::TextEdit::singleline(&mut self.coordination_key).password(true)).on_hover_text(XVB_COORDINATION);
             });
         });
+        // split the hashrate by time or by threads
+        ui.horizontal(|ui| {
+            ui.label("Split the hashrate by");
+            for split in XvbSplit::iter() {
+                let help = match split {
+                    XvbSplit::Time => XVB_SPLIT_TIME_HELP,
+                    XvbSplit::Threads => XVB_SPLIT_THREADS_HELP,
+                };
+                if ui.selectable_value(&mut self.split, split, split.to_string()).on_hover_text(help).clicked() {
+                    api.lock().unwrap().split = self.split;
+                }
+            }
+        });
 
         }
 
         // need to warn the user if no address is set in p2pool tab
-        if !Regexes::addr_ok(address) {
+        if check_address(address, ProcessName::Xvb, Network::Mainnet).is_err() {
             debug!("XvB Tab | Rendering warning text");
                 ui.horizontal_wrapped(|ui|{
             ui.label(RichText::new("You don't have any payout address set in the P2pool Tab ! XvB process needs one to function properly.")
                     .color(ORANGE));
                 });
+        } else {
+            registration(ui, self, address, xvb_registration);
         }
             // private stats
             ui.add_space(SPACE);
@@ -334,11 +409,13 @@ if priv_stats.win_current {
                                 ui.add_space(SPACE);
                                     ui.label(XVB_MINING_ON_FIELD)
                                         .on_hover_text_at_pointer(&priv_stats.msg_indicator);
-                                    ui.label(
-                                        current_node
-                                            .as_ref()
-                                            .map_or("No where".to_string(), |n| n.to_string()),
-                                    )
+                                    let mut mining_on = current_node
+                                        .as_ref()
+                                        .map_or("No where".to_string(), |n| n.to_string());
+                                    if let Some(pool) = &api.worker_pool {
+                                        mining_on.push_str(&format!(" + {pool} ({} threads)", api.threads_xvb));
+                                    }
+                                    ui.label(mining_on)
                                     .on_hover_text_at_pointer(&priv_stats.msg_indicator);
                                     ui.label(Uptime::from(priv_stats.time_switch_pool).to_string())
                                         .on_hover_text_at_pointer(&priv_stats.msg_indicator)
@@ -351,6 +428,12 @@ if priv_stats.win_current {
                     })
                 });
                     // currently mining on
+            ui.add_space(SPACE);
+            history(ui, &xvb_history.lock().unwrap());
+            if !self.simple {
+                ui.add_space(SPACE);
+                decisions(ui, &api.lock().unwrap().decisions, what_if, self);
+            }
                 });
     }
 }
@@ -372,3 +455,552 @@ fn stat_box(ui: &mut Ui, title: &str, value: &str, column_height: f32) {
         });
     });
 }
+
+// Hours and raffles shown in the tables, newest first.
+const HISTORY_HOURS_ROWS: usize = 24;
+const HISTORY_RAFFLES_ROWS: usize = 10;
+
+fn date(timestamp: i64, format: &str) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.with_timezone(&Local).format(format).to_string())
+        .unwrap_or_else(|| "???".to_string())
+}
+
+fn history(ui: &mut Ui, history: &XvbHistory) {
+    debug!("XvB Tab | Rendering [History]");
+    let height = ui.text_style_height(&TextStyle::Body) * 1.5;
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("History").underline())
+                .on_hover_text(XVB_HISTORY);
+        });
+        if history.hours.is_empty() && history.raffles.is_empty() {
+            ui.label("Nothing recorded yet, the XvB process records every hour it is running.");
+            return;
+        }
+        let totals = history.totals();
+        let rounds: Vec<String> = totals
+            .rounds
+            .iter()
+            .filter(|(_, hours)| *hours > 0)
+            .map(|(round, hours)| format!("{round}: {hours}h"))
+            .collect();
+        ui.label(format!(
+            "{} hours recorded | Donated {} | In a round: {} | {} raffles seen, {} won",
+            totals.hours,
+            HumanTime::from_u64(totals.donated).display(false),
+            if rounds.is_empty() {
+                "never".to_string()
+            } else {
+                rounds.join(", ")
+            },
+            history.raffles.len(),
+            totals.raffles_won
+        ));
+        if !history.hours.is_empty() {
+            ui.add_space(SPACE);
+            let width = ui.available_width() / 6.0 - SPACE;
+            let hours: Vec<_> = history
+                .hours
+                .iter()
+                .rev()
+                .take(HISTORY_HOURS_ROWS)
+                .collect();
+            TableBuilder::new(ui)
+                .id_salt("xvb_history_hours")
+                .vscroll(false)
+                .columns(Column::exact(width), 6)
+                .header(height, |mut header| {
+                    for (title, hover) in [
+                        ("Hour", ""),
+                        ("Round", ""),
+                        (XVB_DONATED_1H_FIELD, ""),
+                        (XVB_DONATED_24H_FIELD, ""),
+                        ("Donated", XVB_HISTORY_DONATED),
+                        ("Winning", XVB_HISTORY_WINNING),
+                    ] {
+                        header.col(|ui| {
+                            let label = ui.strong(title);
+                            if !hover.is_empty() {
+                                label.on_hover_text(hover);
+                            }
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(height, hours.len(), |mut row| {
+                        let hour = hours[row.index()];
+                        row.col(|ui| {
+                            ui.label(date(hour.timestamp, "%Y-%m-%d %H:00"));
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                hour.round
+                                    .as_ref()
+                                    .map_or("None".to_string(), |r| r.to_string()),
+                            );
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{} kH/s", Float::from_3(hour.donor_1hr_avg as f64)));
+                        });
+                        row.col(|ui| {
+                            ui.label(format!(
+                                "{} kH/s",
+                                Float::from_3(hour.donor_24hr_avg as f64)
+                            ));
+                        });
+                        row.col(|ui| {
+                            ui.label(HumanTime::from_u64(hour.donated).display(false));
+                        });
+                        row.col(|ui| {
+                            ui.label(if hour.win_current { "Yes" } else { "No" });
+                        });
+                    });
+                });
+        }
+        if !history.raffles.is_empty() {
+            ui.add_space(SPACE);
+            let width = ui.available_width() / 6.0 - SPACE;
+            let raffles: Vec<_> = history
+                .raffles
+                .iter()
+                .rev()
+                .take(HISTORY_RAFFLES_ROWS)
+                .collect();
+            TableBuilder::new(ui)
+                .id_salt("xvb_history_raffles")
+                .vscroll(false)
+                .columns(Column::exact(width), 6)
+                .header(height, |mut header| {
+                    for (title, hover) in [
+                        ("Seen", ""),
+                        ("Monero Height", ""),
+                        ("Round", ""),
+                        ("Winner", ""),
+                        ("Roll", XVB_HISTORY_ROLL),
+                        ("Reward", ""),
+                    ] {
+                        header.col(|ui| {
+                            let label = ui.strong(title);
+                            if !hover.is_empty() {
+                                label.on_hover_text(hover);
+                            }
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(height, raffles.len(), |mut row| {
+                        let raffle = raffles[row.index()];
+                        row.col(|ui| {
+                            ui.label(date(raffle.timestamp, "%Y-%m-%d %H:%M"));
+                        });
+                        row.col(|ui| {
+                            ui.label(raffle.block_height.to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(raffle.round_type.to_string());
+                        });
+                        row.col(|ui| {
+                            if raffle.won {
+                                ui.label(RichText::new("You").color(GREEN));
+                            } else {
+                                ui.label(&raffle.winner);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{} / {}", raffle.roll_winner, raffle.roll_round));
+                        });
+                        row.col(|ui| {
+                            ui.label(format!("{} XMR", raffle.block_reward));
+                        });
+                    });
+                });
+        }
+    });
+}
+
+// Last decisions of the algorithm with the stats they were made with, and the what-if.
+fn decisions(
+    ui: &mut Ui,
+    decisions: &VecDeque<Decision>,
+    what_if: &mut Option<(WhatIf, WhatIfOutcome)>,
+    state: &crate::disk::state::Xvb,
+) {
+    debug!("XvB Tab | Rendering [Decisions]");
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("Decisions").underline())
+                .on_hover_text(XVB_DECISIONS);
+        });
+        let Some(last) = decisions.back() else {
+            ui.label("No decision yet, the algorithm decides every minute while XvB is running.");
+            return;
+        };
+        for decision in decisions.iter().rev() {
+            egui::CollapsingHeader::new(format!(
+                "{} | {}",
+                date(decision.timestamp, "%H:%M:%S"),
+                decision.outcome
+            ))
+            .id_salt(("xvb_decision", decision.timestamp))
+            .show(ui, |ui| {
+                stats_grid(ui, &decision.stats, decision.timestamp);
+            });
+        }
+        ui.add_space(SPACE);
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("What if").underline())
+                .on_hover_text(XVB_WHAT_IF);
+        });
+        // the inputs are kept when a new decision is made, the outcome is computed again.
+        let (mut inputs, outdated) = match what_if {
+            Some((inputs, _)) => (inputs.clone(), inputs.timestamp != last.timestamp),
+            None => (
+                WhatIf::new(last, state.mode.clone(), state.split, state.into()),
+                true,
+            ),
+        };
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut inputs.hashrate)
+                    .range(0.0..=f32::MAX)
+                    .speed(100.0)
+                    .suffix(" H/s"),
+            );
+            ui.add(
+                egui::Slider::new(&mut inputs.p2pool_buffer, -100..=100).text("% P2Pool Buffer"),
+            );
+            egui::ComboBox::from_id_salt("xvb_what_if_mode")
+                .selected_text(inputs.mode.to_string())
+                .show_ui(ui, |ui| {
+                    for mode in XvbMode::iter() {
+                        let text = mode.to_string();
+                        ui.selectable_value(&mut inputs.mode, mode, text);
+                    }
+                });
+            for split in XvbSplit::iter() {
+                ui.selectable_value(&mut inputs.split, split, format!("By {split}"));
+            }
+            if ui.button("Reset").clicked() {
+                inputs = WhatIf::new(last, state.mode.clone(), state.split, state.into());
+            }
+        });
+        inputs.timestamp = last.timestamp;
+        if outdated
+            || what_if
+                .as_ref()
+                .is_none_or(|(current, _)| *current != inputs)
+        {
+            let outcome = inputs.outcome(last);
+            *what_if = Some((inputs, outcome));
+        }
+        let Some((_, outcome)) = what_if else {
+            return;
+        };
+        let split = match outcome.threads_xvb {
+            Some(threads) => format!(
+                "{threads} threads on XvB, {} on P2Pool",
+                outcome.stats.threads - threads
+            ),
+            None => format!(
+                "{}s on XvB, {}s on P2Pool every cycle",
+                outcome.stats.needed_time_xvb as f32 / 1000.0,
+                (XVB_TIME_ALGO - outcome.stats.needed_time_xvb) as f32 / 1000.0
+            ),
+        };
+        ui.label(format!(
+            "{split} | {:.0} H/s sent to XvB | Round: {}",
+            outcome.sent,
+            outcome
+                .round
+                .as_ref()
+                .map_or("None, no share".to_string(), |r| r.to_string())
+        ));
+        stats_grid(ui, &outcome.stats, 0);
+    });
+}
+// Constraints of the goal mode, with the round they would reach with the stats of the last decision.
+fn goal(
+    ui: &mut Ui,
+    state: &mut crate::disk::state::Xvb,
+    decisions: &VecDeque<Decision>,
+    projection: &mut Option<(WhatIf, WhatIfOutcome)>,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Goal");
+        for goal in XvbGoal::iter() {
+            let help = match goal {
+                XvbGoal::Round => XVB_GOAL_ROUND_HELP,
+                XvbGoal::DailyPercent => XVB_GOAL_DAILY_PERCENT_HELP,
+            };
+            ui.selectable_value(&mut state.goal, goal, goal.to_string())
+                .on_hover_text(help);
+        }
+    });
+    ui.horizontal(|ui| match state.goal {
+        XvbGoal::Round => {
+            for level in [
+                ManualDonationLevel::Donor,
+                ManualDonationLevel::DonorVIP,
+                ManualDonationLevel::DonorWhale,
+                ManualDonationLevel::DonorMega,
+            ] {
+                let text = level.to_string();
+                ui.radio_value(&mut state.goal_round, level, text)
+                    .on_hover_text(XVB_GOAL_ROUND_HELP);
+            }
+        }
+        XvbGoal::DailyPercent => {
+            ui.add(egui::Slider::new(&mut state.goal_percent, 0.0..=100.0).text("% per day"))
+                .on_hover_text(XVB_GOAL_DAILY_PERCENT_HELP);
+        }
+    });
+    ui.add(egui::Slider::new(&mut state.goal_cap, 0.0..=100.0).text("% cap"))
+        .on_hover_text(XVB_GOAL_CAP);
+    let Some(last) = decisions.back() else {
+        ui.label("The projection needs a decision of the algorithm, it decides every minute while XvB is running.");
+        return;
+    };
+    // computed again only when the goal or the decision change, the stats are logged.
+    let inputs = WhatIf::new(last, XvbMode::Goal, XvbSplit::Time, (&*state).into());
+    if projection
+        .as_ref()
+        .is_none_or(|(current, _)| *current != inputs)
+    {
+        let outcome = inputs.outcome(last);
+        *projection = Some((inputs, outcome));
+    }
+    let Some((inputs, outcome)) = projection else {
+        return;
+    };
+    let stats = &outcome.stats;
+    ui.label(format!(
+        "Projection: {:.0} H/s sent to XvB, at most {:.0} H/s | Round: {}",
+        outcome.sent,
+        stats.goal_max_hashrate(),
+        outcome
+            .round
+            .as_ref()
+            .map_or("None, no share".to_string(), |r| r.to_string())
+    ));
+    let reachable = match inputs.goal.goal {
+        XvbGoal::Round => {
+            stats.target_donation_hashrate + stats.siblings_hashrate
+                >= inputs.goal.round.get_hashrate()
+        }
+        XvbGoal::DailyPercent => {
+            stats.goal_max_hashrate() >= stats.hashrate_xmrig * inputs.goal.percent / 100.0
+        }
+    };
+    if !reachable {
+        ui.label(
+            RichText::new("The goal can not be reached under the cap without losing the share, the mode donates as much as they allow.")
+                .color(ORANGE),
+        );
+    }
+}
+
+// Verification and registration of the XvB user, the token given by XvB is stored in the accounts.
+fn registration(
+    ui: &mut Ui,
+    state: &mut crate::disk::state::Xvb,
+    p2pool_address: &str,
+    registration: &Arc<Mutex<XvbRegistration>>,
+) {
+    let Some(account) = state.accounts(p2pool_address).into_iter().next() else {
+        return;
+    };
+    let mut lock = registration.lock().unwrap();
+    if let Some(token) = lock.token.take() {
+        match state
+            .accounts
+            .iter_mut()
+            .find(|a| a.address == lock.address)
+        {
+            Some(a) => a.token = token,
+            // the P2Pool address is used without any account, it only needs one for a token.
+            None if !token.is_empty() => state.accounts.push(XvbAccount {
+                address: lock.address.clone(),
+                token,
+            }),
+            None => {}
+        }
+    }
+    // the status of an other address is outdated
+    let status = if lock.address == account.address {
+        lock.status.clone()
+    } else {
+        RegistrationStatus::Unknown
+    };
+    let busy = lock.busy;
+    drop(lock);
+    let color = match status {
+        RegistrationStatus::Registered => GREEN,
+        RegistrationStatus::NotRegistered => ORANGE,
+        _ if status.is_error() => RED,
+        _ => ui.visuals().text_color(),
+    };
+    let valid = check_address(&account.address, ProcessName::Xvb, Network::Mainnet).is_ok();
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "XvB registration of {}:",
+            Helper::head_tail_of_monero_address(&account.address)
+        ));
+        ui.label(RichText::new(status.to_string()).color(color));
+        ui.add_enabled_ui(valid && !busy, |ui| {
+            if ui
+                .button("Verify")
+                .on_hover_text(XVB_REGISTRATION_VERIFY)
+                .clicked()
+            {
+                XvbRegistration::start(registration, account.clone(), None);
+            }
+            if ui
+                .add_enabled(
+                    status != RegistrationStatus::Registered
+                        && !state.registration_endpoint.is_empty(),
+                    egui::Button::new("Register"),
+                )
+                .on_hover_text(XVB_REGISTRATION)
+                .clicked()
+            {
+                XvbRegistration::start(
+                    registration,
+                    account,
+                    Some(state.registration_endpoint.clone()),
+                );
+            }
+        });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Registration endpoint:");
+        ui.text_edit_singleline(&mut state.registration_endpoint)
+            .on_hover_text(XVB_REGISTRATION_ENDPOINT);
+    });
+}
+
+// List of the XvB pools, pinged to choose the fastest.
+fn pools(ui: &mut Ui, pools: &mut Vec<XvbPoolEndpoint>) {
+    egui::CollapsingHeader::new("XvB pools")
+        .id_salt("xvb_pools")
+        .show(ui, |ui| {
+            ui.label(XVB_POOLS);
+            let mut remove = None;
+            let pools_len = pools.len();
+            egui::Grid::new("xvb_pools_grid")
+                .num_columns(5)
+                .show(ui, |ui| {
+                    ui.label("Region");
+                    ui.label("Host");
+                    ui.label("Port");
+                    ui.label("TLS");
+                    ui.end_row();
+                    for (i, pool) in pools.iter_mut().enumerate() {
+                        ui.text_edit_singleline(&mut pool.label);
+                        ui.text_edit_singleline(&mut pool.host);
+                        ui.add(egui::DragValue::new(&mut pool.port));
+                        ui.checkbox(&mut pool.tls, "");
+                        // at least one pool is needed
+                        if ui
+                            .add_enabled(pools_len > 1, egui::Button::new("Remove"))
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(i) = remove {
+                pools.remove(i);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("Add").clicked() {
+                    pools.push(XvbPoolEndpoint {
+                        label: format!("Pool {}", pools.len() + 1),
+                        host: String::new(),
+                        ..XvbPoolEndpoint::default()
+                    });
+                }
+                if ui.button("Reset").clicked() {
+                    *pools = crate::disk::state::Xvb::default().pools;
+                }
+            });
+        });
+}
+
+// Addresses registered on XvB, with their token.
+fn accounts(ui: &mut Ui, accounts: &mut Vec<XvbAccount>) {
+    egui::CollapsingHeader::new("XvB accounts")
+        .id_salt("xvb_accounts")
+        .show(ui, |ui| {
+            ui.label(XVB_ACCOUNTS);
+            let mut remove = None;
+            egui::Grid::new("xvb_accounts_grid")
+                .num_columns(3)
+                .show(ui, |ui| {
+                    ui.label("Address");
+                    ui.label("Token");
+                    ui.end_row();
+                    for (i, account) in accounts.iter_mut().enumerate() {
+                        let valid =
+                            check_address(&account.address, ProcessName::Xvb, Network::Mainnet)
+                                .is_ok();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut account.address)
+                                .text_color_opt((!valid).then_some(RED)),
+                        );
+                        ui.add(egui::TextEdit::singleline(&mut account.token).password(true));
+                        if ui.button("Remove").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(i) = remove {
+                accounts.remove(i);
+            }
+            if ui.button("Add").clicked() {
+                accounts.push(XvbAccount::default());
+            }
+        });
+}
+
+fn stats_grid(ui: &mut Ui, stats: &Stats, id: i64) {
+    egui::Grid::new(("xvb_stats", id))
+        .striped(true)
+        .show(ui, |ui| {
+            let expiry = stats.share_expiry.map_or("unknown".to_string(), |secs| {
+                HumanTime::from_u64(secs).display(false)
+            });
+            for (name, value) in [
+                ("Mode", format!("{:?}", stats.runtime_mode)),
+                (
+                    "Shares",
+                    format!("{} (last one expires in {expiry})", stats.share),
+                ),
+                (
+                    "HR",
+                    format!(
+                        "{:.0} H/s ({} threads)",
+                        stats.hashrate_xmrig, stats.threads
+                    ),
+                ),
+                (
+                    "XvB averages",
+                    format!(
+                        "1h: {:.0} H/s | 24h: {:.0} H/s",
+                        stats.xvb_1h_avg, stats.xvb_24h_avg
+                    ),
+                ),
+            ]
+            .into_iter()
+            .chain(stats.formulas())
+            {
+                ui.label(name);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+}
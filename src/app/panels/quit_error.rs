@@ -186,7 +186,8 @@ impl crate::app::App {
                                 Helper::start_node(
                                 &self.helper,
                                 &self.state.node,
-                                &self.state.gupax.absolute_node_path);
+                                &self.state.gupax.absolute_node_path,
+                                self.state.gupax.network);
                         }
                         // If [Esc] was pressed, assume [No]
                         if key.is_esc()
@@ -16,7 +16,9 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::app::Tab;
-use egui::{Button, TextStyle};
+use crate::utils::constants::{GUPAX_NOT_MAINNET, ORANGE};
+use crate::utils::monero_address::Network;
+use egui::{Button, RichText, TextStyle};
 use egui::{ScrollArea, Separator, TopBottomPanel, Ui};
 use log::debug;
 
@@ -25,6 +27,19 @@ impl crate::app::App {
         debug!("App | Rendering TOP tabs");
         let tabs = Tab::from_show_processes(&self.state.gupax.show_processes);
         TopBottomPanel::top("top").show(ctx, |ui| {
+            // coins of the other networks have no value, it must never be missed.
+            if self.state.gupax.network != Network::Mainnet {
+                ui.vertical_centered(|ui| {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} | {GUPAX_NOT_MAINNET}",
+                            self.state.gupax.network.to_string().to_uppercase()
+                        ))
+                        .color(ORANGE)
+                        .strong(),
+                    );
+                });
+            }
             // low spacing to shrink and be able to show all tabs on one line on 640x480
             ui.style_mut().spacing.item_spacing.x = 4.0;
             // spacing of separator, will reduce width size of the button. Low value so that tabs can be selected easily.
@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use strum::{EnumIter, IntoEnumIterator};
 
 use crate::utils::constants::{
-    P2POOL_ADVANCED, P2POOL_CRAWLER, P2POOL_SIMPLE, STATUS_SUBMENU_HASHRATE, STATUS_SUBMENU_P2POOL,
-    STATUS_SUBMENU_PROCESSES,
+    P2POOL_ADVANCED, P2POOL_CRAWLER, P2POOL_SIMPLE, STATUS_SUBMENU_CALCULATOR,
+    STATUS_SUBMENU_HASHRATE, STATUS_SUBMENU_P2POOL, STATUS_SUBMENU_PROCESSES,
 };
 
 /// A submenu
@@ -19,6 +19,7 @@ pub enum SubmenuStatus {
     #[default]
     Processes,
     P2pool,
+    Calculator,
     Benchmarks,
 }
 
@@ -35,6 +36,7 @@ impl Submenu for SubmenuStatus {
         match self {
             Self::Processes => STATUS_SUBMENU_PROCESSES,
             Self::P2pool => STATUS_SUBMENU_P2POOL,
+            Self::Calculator => STATUS_SUBMENU_CALCULATOR,
             Self::Benchmarks => STATUS_SUBMENU_HASHRATE,
         }
     }
@@ -1,3 +1,5 @@
+use chrono::NaiveDate;
+use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
 use clap::crate_authors;
@@ -10,8 +12,10 @@ use log::warn;
 use std::process::exit;
 
 use crate::app::App;
+use crate::disk::payout_export::ExportFormat;
 use crate::miscs::print_disk_file;
 use crate::miscs::print_gupax_p2pool_api;
+use crate::miscs::print_payout_export;
 use crate::resets::reset;
 use crate::resets::reset_gupax_p2pool_api;
 use crate::resets::reset_nodes;
@@ -40,8 +44,10 @@ pub enum GupaxData {
     State,
     #[command(about = "Print the manual node list")]
     Nodes,
-    #[command(about = "Print the P2Pool payout log, payout count, and total XMR mined")]
-    Payouts,
+    #[command(
+        about = "Print the P2Pool payout log, payout count, and total XMR mined. Export the payouts with --format"
+    )]
+    Payouts(PayoutsArgs),
     #[command(about = "Reset all Gupaxstate (your settings)")]
     ResetState,
     #[command(about = "Reset the manual node list in the [P2Pool] tab")]
@@ -58,6 +64,38 @@ pub enum GupaxData {
     )]
     Nostartup,
 }
+#[derive(Args)]
+pub struct PayoutsArgs {
+    #[arg(long, value_enum, help = "Export the payouts in this format")]
+    pub format: Option<ExportFormat>,
+    #[arg(long, help = "Export the payouts from this date included (YYYY-MM-DD)")]
+    pub from: Option<NaiveDate>,
+    #[arg(
+        long,
+        help = "Export the payouts until this date included (YYYY-MM-DD)"
+    )]
+    pub to: Option<NaiveDate>,
+    #[arg(
+        long,
+        help = "Add the fiat value of the payouts, from a CSV file of \"YYYY-MM-DD,price\" lines or an URL where {date} is replaced"
+    )]
+    pub prices: Option<String>,
+}
+
+impl PayoutsArgs {
+    // Any export option makes it an export, CSV by default.
+    pub fn export_format(&self) -> Option<ExportFormat> {
+        if self.format.is_none()
+            && self.from.is_none()
+            && self.to.is_none()
+            && self.prices.is_none()
+        {
+            return None;
+        }
+        Some(self.format.unwrap_or_default())
+    }
+}
+
 // #[cold]
 // #[inline(never)]
 pub fn parse_args<S: Into<String>>(mut app: App, args: &Cli, panic: S) -> App {
@@ -81,9 +119,14 @@ pub fn parse_args<S: Into<String>>(mut app: App, args: &Cli, panic: S) -> App {
                 print_disk_file(&app.node_path);
                 exit(0);
             }
-            GupaxData::Payouts => {
-                debug!("Printing payouts...\n");
-                print_gupax_p2pool_api(&app.gupax_p2pool_api);
+            GupaxData::Payouts(payouts_args) => {
+                if let Some(format) = payouts_args.export_format() {
+                    debug!("Exporting payouts...\n");
+                    print_payout_export(&app.gupax_p2pool_api, format, payouts_args);
+                } else {
+                    debug!("Printing payouts...\n");
+                    print_gupax_p2pool_api(&app.gupax_p2pool_api);
+                }
                 exit(0);
             }
             GupaxData::ResetState => {
@@ -16,17 +16,20 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::app::panels::middle::common::list_poolnode::PoolNode;
+use crate::disk::known_nodes::{self, KnownNodes};
+use crate::utils::monero_address::Network;
 use crate::utils::node_latency::port_ping;
 use derive_more::{Deref, DerefMut};
 use egui::Color32;
 use enclose::enc;
 use log::*;
 use rand::{Rng, rng};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[allow(dead_code)]
 #[derive(Clone, Debug, Deserialize, Serialize, Eq)]
@@ -35,6 +38,9 @@ pub struct RemoteNode {
     pub rpc: u16,
     pub zmq: u16,
     pub ms: u64,
+    // result of the last RPC health check, not saved to disk since it would be outdated at next start.
+    #[serde(skip)]
+    pub health: Option<NodeHealth>,
 }
 
 // we ignore latency to identify nodes
@@ -123,6 +129,20 @@ impl RemoteNode {
         }
     }
 
+    /// Combined score of latency and health, lower is better.
+    /// Returns None if the node was not checked or is not usable by P2Pool.
+    pub fn score(&self, network: Network) -> Option<u64> {
+        let health = self.health.as_ref()?;
+        if !health.is_healthy(network) {
+            return None;
+        }
+        let mut score = self.ms + health.lag * NODE_LAG_PENALTY;
+        if health.restricted {
+            score += NODE_RESTRICTED_PENALTY;
+        }
+        Some(score)
+    }
+
     // TODO if ever wanting to show the country
     // Use a database https://github.com/sapics/ip-location-db to show country of discovered node
     // pub fn country(&self) -> String {
@@ -152,6 +172,12 @@ pub const GREEN_NODE_PING: u64 = 100;
 // yellow is anything in-between green/red
 pub const RED_NODE_PING: u64 = 300;
 pub const TIMEOUT_NODE_PING: u64 = 1000;
+// maximum number of blocks a node can be behind the best height seen before being discarded.
+pub const NODE_MAX_LAG: u64 = 5;
+// penalty in ms added to the score for every block the node is behind the best height seen.
+pub const NODE_LAG_PENALTY: u64 = 100;
+// restricted RPC is enough for P2Pool, but it is the mark of a public node shared with many users.
+pub const NODE_RESTRICTED_PENALTY: u64 = 50;
 
 //---------------------------------------------------------------------------------------------------- `/get_info`
 // A struct repr of the JSON-RPC we're
@@ -159,18 +185,103 @@ pub const TIMEOUT_NODE_PING: u64 = 1000;
 //
 // This struct leaves out most fields on purpose,
 // we only need a few to verify the node is ok.
-#[allow(dead_code)] // allow dead code because Deserialize doesn't use all the fields in this program
-#[derive(Debug, serde::Deserialize)]
-pub struct GetInfo<'a> {
-    pub id: &'a str,
-    pub jsonrpc: &'a str,
-    pub _result: GetInfoResult,
+#[derive(Debug, Deserialize)]
+pub struct GetInfo {
+    pub result: GetInfoResult,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Deserialize)]
 pub struct GetInfoResult {
-    pub _mainnet: bool,
-    pub _synchronized: bool,
+    pub height: u64,
+    pub nettype: String,
+    // older versions of monerod do not include this field
+    #[serde(default)]
+    pub restricted: bool,
+    pub synchronized: bool,
+}
+
+impl GetInfo {
+    // Send a [get_info] JSON-RPC request to the node, returns the data with the round-trip in ms.
+    pub async fn request(client: &Client, ip: IpAddr, port: u16) -> anyhow::Result<(Self, u64)> {
+        let now = Instant::now();
+        let info = client
+            .post(format!("http://{}/json_rpc", SocketAddr::new(ip, port)))
+            .body(r#"{"jsonrpc":"2.0","id":"0","method":"get_info"}"#)
+            .send()
+            .await?
+            .json::<GetInfo>()
+            .await?;
+        Ok((info, now.elapsed().as_millis() as u64))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Health data
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeHealth {
+    pub height: u64,
+    pub synchronized: bool,
+    pub nettype: String,
+    pub restricted: bool,
+    // number of blocks behind the best height seen during the same ping
+    pub lag: u64,
+}
+
+impl From<GetInfoResult> for NodeHealth {
+    fn from(result: GetInfoResult) -> Self {
+        Self {
+            height: result.height,
+            synchronized: result.synchronized,
+            nettype: result.nettype,
+            restricted: result.restricted,
+            lag: 0,
+        }
+    }
+}
+
+impl NodeHealth {
+    // Network of the node, None if monerod returned an unknown nettype.
+    pub fn network(&self) -> Option<Network> {
+        self.nettype.parse().ok()
+    }
+
+    pub fn is_healthy(&self, network: Network) -> bool {
+        self.synchronized && self.network() == Some(network) && self.lag <= NODE_MAX_LAG
+    }
+}
+
+// Check that the ZMQ port accepts connections, then ask the state of the node with [get_info].
+// Returns the node with its latency and health, the lag is only known once compared to other nodes with [set_lag].
+pub async fn check_node(client: &Client, node: &RemoteNode) -> anyhow::Result<RemoteNode> {
+    // P2Pool needs the ZMQ port, a node without it is useless even if the RPC is healthy.
+    let socket_address = SocketAddr::new(node.ip, node.zmq);
+    if let Err(e) = port_ping(socket_address, TIMEOUT_NODE_PING).await {
+        anyhow::bail!("ZMQ port unreachable ... {e}");
+    }
+    match GetInfo::request(client, node.ip, node.rpc).await {
+        Ok((info, ms)) => {
+            let mut node = node.clone();
+            node.ms = ms;
+            node.health = Some(NodeHealth::from(info.result));
+            Ok(node)
+        }
+        Err(e) => anyhow::bail!("RPC [get_info] failed ... {e}"),
+    }
+}
+
+// Compute the lag of every checked node from the best height seen among them.
+pub fn set_lag(nodes: &mut [RemoteNode], network: Network) {
+    let best_height = nodes
+        .iter()
+        .filter_map(|n| n.health.as_ref())
+        .filter(|h| h.synchronized && h.network() == Some(network))
+        .map(|h| h.height)
+        .max()
+        .unwrap_or_default();
+    for node in nodes.iter_mut() {
+        if let Some(health) = node.health.as_mut() {
+            health.lag = best_height.saturating_sub(health.height);
+        }
+    }
 }
 
 //---------------------------------------------------------------------------------------------------- Ping data
@@ -182,10 +293,13 @@ pub struct Ping {
     pub prog: f32,
     pub pinged: bool,
     pub auto_selected: bool,
+    pub known_nodes: Arc<Mutex<KnownNodes>>,
+    // network of the nodes to ping, kept in sync with the state by the GUI
+    pub network: Network,
 }
 
 impl Ping {
-    pub fn new(nodes: RemoteNodes) -> Self {
+    pub fn new(nodes: RemoteNodes, known_nodes: Arc<Mutex<KnownNodes>>) -> Self {
         Self {
             nodes,
             pinging: false,
@@ -193,6 +307,8 @@ impl Ping {
             prog: 0.0,
             pinged: false,
             auto_selected: true,
+            known_nodes,
+            network: Network::default(),
         }
     }
 
@@ -220,21 +336,27 @@ impl Ping {
                 }
             }
             info!("Ping ... Took [{}] seconds...", now.elapsed().as_secs_f32());
+            let known_nodes = ping.lock().unwrap().known_nodes.clone();
+            if let Err(e) = known_nodes.lock().unwrap().save() {
+                error!("Ping ... Saving known nodes failed ... {e}");
+            }
             ping.lock().unwrap().pinging = false;
         });
     }
 
-    // This is for pinging the remote nodes to
-    // find the fastest/slowest one for the user.
+    // This is for checking the health of the remote nodes
+    // to find the best/worst one for the user.
     // The process:
+    //   - Check that the ZMQ port accepts connections
     //   - Send [get_info] JSON-RPC request over HTTP to all IPs
     //   - Measure each request in milliseconds
-    //   - Timeout on requests over 5 seconds
-    //   - Add data to appropriate struct
-    //   - Sorting fastest to lowest is automatic (fastest nodes return ... the fastest)
+    //   - Timeout on requests over [TIMEOUT_NODE_PING] ms
+    //   - Compare the height of each node with the best height seen
+    //   - Discard nodes that are not synchronized, on another network or lagging behind
+    //   - Sort by score, combining latency with the health and reputation of the node
+    //   - Record the results in the known nodes
     //
-    // This used to be done 3x linearly but after testing, sending a single
-    // JSON-RPC call to all IPs asynchronously resulted in the same data.
+    // Nodes known to fail most of the time are not checked again until their cooldown is over.
     #[cold]
     #[inline(never)]
     #[tokio::main]
@@ -244,28 +366,51 @@ impl Ping {
         ping.lock().unwrap().prog = 0.0;
         let len = ping.lock().unwrap().nodes.len();
         let percent = Arc::new((100.0 / (len as f32)).floor());
+        let client = Client::builder()
+            .timeout(Duration::from_millis(TIMEOUT_NODE_PING))
+            .build()?;
 
         // Handle vector
         let mut handles = Vec::with_capacity(len);
         let mut nodes = ping.lock().unwrap().nodes.clone();
+        let known_nodes = ping.lock().unwrap().known_nodes.clone();
+        let network = ping.lock().unwrap().network;
+        let now = known_nodes::now();
+        nodes.retain(|n| {
+            let bad = known_nodes.lock().unwrap().is_bad(n, now);
+            if bad {
+                info!("Ping | Skipping node known to be bad ... {}", n.ip);
+            }
+            !bad
+        });
         let vec_nodes = Arc::new(Mutex::new(Vec::with_capacity(nodes.len())));
+        let vec_failed = Arc::new(Mutex::new(vec![]));
         for node in nodes.iter() {
-            let handle = tokio::task::spawn(enc!((vec_nodes, node, ping, percent) async move {
-                let socket_address = SocketAddr::new(node.ip, node.zmq);
-
-                if let Ok(ms) = port_ping(socket_address, TIMEOUT_NODE_PING).await {
-                    let info = format!("{ms}ms ... {}", node.ip);
-                    info!("Ping | {ms}ms ... {}", node.ip);
-
-                    let mut ping = ping.lock().unwrap();
-                    ping.msg = info;
-                    ping.prog += *percent;
-                    drop(ping);
-                    let mut node = node.clone();
-                    node.ms = ms;
-                    vec_nodes.lock().unwrap().push(node);
-                }
-            }));
+            let handle = tokio::task::spawn(
+                enc!((vec_nodes, vec_failed, node, ping, percent, client) async move {
+                    match check_node(&client, &node).await {
+                        Ok(node) => {
+                            let msg = format!("{}ms ... {}", node.ms, node.ip);
+                            if let Some(health) = &node.health {
+                                info!(
+                                    "Ping | {msg} ... height: {} synchronized: {} nettype: {} restricted: {}",
+                                    health.height, health.synchronized, health.nettype, health.restricted
+                                );
+                            }
+
+                            let mut ping = ping.lock().unwrap();
+                            ping.msg = msg;
+                            ping.prog += *percent;
+                            drop(ping);
+                            vec_nodes.lock().unwrap().push(node);
+                        }
+                        Err(e) => {
+                            warn!("Ping | {} ... {e}", node.ip);
+                            vec_failed.lock().unwrap().push(node);
+                        }
+                    }
+                }),
+            );
             handles.push(handle);
         }
 
@@ -273,12 +418,23 @@ impl Ping {
             handle.await?;
         }
         nodes = RemoteNodes(vec_nodes.lock().unwrap().to_vec());
-        nodes.sort_by(|a, b| a.ms.cmp(&b.ms));
+        let mut known_nodes = known_nodes.lock().unwrap();
+        let failed = std::mem::take(&mut *vec_failed.lock().unwrap());
+        // the unhealthy nodes answered, their health is recorded with the success.
+        let unhealthy = Self::rank(&mut nodes, &known_nodes, network);
+        let now = known_nodes::now();
+        for node in nodes.iter().chain(unhealthy.iter()) {
+            known_nodes.record_success(node, now);
+        }
+        for node in failed.iter() {
+            known_nodes.record_failure(node, now);
+        }
+        drop(known_nodes);
         let fastest_info;
         if let Some(node) = nodes.first() {
-            fastest_info = format!("Fastest node: {}ms ... {}", node.ms, node.ip);
+            fastest_info = format!("Best node: {}ms ... {}", node.ms, node.ip);
         } else {
-            fastest_info = "Pinged without any nodes".to_string();
+            fastest_info = "Pinged without any healthy nodes".to_string();
         }
 
         let info = "Cleaning up connections".to_string();
@@ -289,6 +445,31 @@ impl Ping {
         drop(ping);
         Ok(fastest_info)
     }
+    // Compute the lag of every node from the best height seen,
+    // remove the unhealthy ones and sort the rest by score, reliable known nodes getting a bonus.
+    // Returns the removed nodes.
+    fn rank(
+        nodes: &mut RemoteNodes,
+        known_nodes: &KnownNodes,
+        network: Network,
+    ) -> Vec<RemoteNode> {
+        set_lag(nodes, network);
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) =
+            nodes.drain(..).partition(|n| n.score(network).is_some());
+        for node in unhealthy.iter() {
+            warn!(
+                "Ping | Discarding unhealthy node ... {} ... {:?}",
+                node.ip, node.health
+            );
+        }
+        nodes.extend(healthy);
+        nodes.sort_by_key(|n| {
+            n.score(network)
+                .unwrap_or_default()
+                .saturating_sub(known_nodes.bonus(n))
+        });
+        unhealthy
+    }
     // This returns relative to the ping.
     pub fn get_last_from_ping(&self, current: &RemoteNode) -> RemoteNode {
         let mut found = false;
@@ -319,3 +500,161 @@ impl Ping {
         current.clone()
     }
 }
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::http_mock::mock_http;
+    use std::net::{Ipv4Addr, TcpListener};
+
+    // Start a fake monerod answering every JSON-RPC request with the given [get_info] result.
+    // The ZMQ port only needs to accept connections.
+    fn mock_node(result: &str) -> RemoteNode {
+        let body = format!(r#"{{"id":"0","jsonrpc":"2.0","result":{result}}}"#);
+        let rpc = mock_http(move |request| {
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.path, "/json_rpc");
+            assert!(request.body.contains("get_info"));
+            ("200 OK", body.clone())
+        });
+        let zmq = TcpListener::bind("127.0.0.1:0").unwrap();
+        let node = RemoteNode {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            rpc: rpc.port(),
+            zmq: zmq.local_addr().unwrap().port(),
+            ms: 0,
+            health: None,
+        };
+        std::thread::spawn(move || for _stream in zmq.incoming() {});
+        node
+    }
+
+    #[test]
+    fn ping_rank_nodes_by_health() {
+        let best = mock_node(
+            r#"{"height":3000000,"nettype":"mainnet","restricted":false,"synchronized":true}"#,
+        );
+        let restricted = mock_node(
+            r#"{"height":3000000,"nettype":"mainnet","restricted":true,"synchronized":true}"#,
+        );
+        let lagging = mock_node(
+            r#"{"height":2999998,"nettype":"mainnet","restricted":false,"synchronized":true}"#,
+        );
+        let not_synced = mock_node(
+            r#"{"height":2000000,"nettype":"mainnet","restricted":false,"synchronized":false}"#,
+        );
+        let too_late = mock_node(
+            r#"{"height":2999990,"nettype":"mainnet","restricted":false,"synchronized":true}"#,
+        );
+        let stagenet = mock_node(
+            r#"{"height":3000100,"nettype":"stagenet","restricted":false,"synchronized":true}"#,
+        );
+        let known_nodes = Arc::new(Mutex::new(KnownNodes::default()));
+        let ping = Arc::new(Mutex::new(Ping::new(
+            RemoteNodes(vec![
+                stagenet.clone(),
+                too_late.clone(),
+                not_synced.clone(),
+                lagging.clone(),
+                restricted.clone(),
+                best.clone(),
+            ]),
+            known_nodes.clone(),
+        )));
+        Ping::ping(&ping).unwrap();
+        let nodes = ping.lock().unwrap().nodes.clone();
+        assert_eq!(*nodes, vec![best.clone(), restricted, lagging.clone()]);
+        assert_eq!(nodes[2].health.as_ref().unwrap().lag, 2);
+        // results are recorded in the known nodes
+        let known_nodes = known_nodes.lock().unwrap();
+        assert_eq!(known_nodes.nodes.len(), 6);
+        let best = known_nodes.find(&best).unwrap();
+        assert_eq!((best.successes, best.failures), (1, 0));
+        assert!(best.synchronized);
+        // unhealthy nodes answered, they are not failing
+        let stagenet = known_nodes.find(&stagenet).unwrap();
+        assert_eq!((stagenet.successes, stagenet.failures), (1, 0));
+        assert_eq!(stagenet.network, Network::Stagenet);
+        let not_synced = known_nodes.find(&not_synced).unwrap();
+        assert_eq!((not_synced.successes, not_synced.failures), (1, 0));
+        assert!(!not_synced.synchronized);
+    }
+
+    #[test]
+    fn ping_skip_known_bad_nodes() {
+        let node = mock_node(
+            r#"{"height":3000000,"nettype":"mainnet","restricted":false,"synchronized":true}"#,
+        );
+        let now = known_nodes::now();
+        let mut known_nodes = KnownNodes::default();
+        for _ in 0..known_nodes::KNOWN_NODES_BAD_FAILURES {
+            known_nodes.record_failure(&node, now);
+        }
+        let known_nodes = Arc::new(Mutex::new(known_nodes));
+        let ping = Arc::new(Mutex::new(Ping::new(
+            RemoteNodes(vec![node.clone()]),
+            known_nodes.clone(),
+        )));
+        Ping::ping(&ping).unwrap();
+        assert!(ping.lock().unwrap().nodes.is_empty());
+        // after the cooldown, the node is checked again and recovers
+        known_nodes.lock().unwrap().nodes[0].last_failure =
+            now - known_nodes::KNOWN_NODES_BAD_COOLDOWN_SECS;
+        ping.lock().unwrap().nodes = RemoteNodes(vec![node.clone()]);
+        Ping::ping(&ping).unwrap();
+        assert_eq!(ping.lock().unwrap().nodes.len(), 1);
+        let known_nodes = known_nodes.lock().unwrap();
+        assert!(!known_nodes.is_bad(&node, now));
+        assert_eq!(known_nodes.find(&node).unwrap().successes, 1);
+    }
+
+    #[test]
+    fn ping_discard_unreachable_zmq() {
+        let mut node = mock_node(
+            r#"{"height":3000000,"nettype":"mainnet","restricted":false,"synchronized":true}"#,
+        );
+        // nothing listens on this port once the listener is dropped.
+        node.zmq = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let ping = Arc::new(Mutex::new(Ping::new(
+            RemoteNodes(vec![node]),
+            Arc::new(Mutex::new(KnownNodes::default())),
+        )));
+        assert_eq!(
+            Ping::ping(&ping).unwrap(),
+            "Pinged without any healthy nodes"
+        );
+        assert!(ping.lock().unwrap().nodes.is_empty());
+    }
+
+    #[test]
+    fn score_remote_node() {
+        let mut node = RemoteNode {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            rpc: 18081,
+            zmq: 18083,
+            ms: 40,
+            health: None,
+        };
+        assert_eq!(node.score(Network::Mainnet), None);
+        node.health = Some(NodeHealth {
+            height: 3000000,
+            synchronized: true,
+            nettype: "mainnet".to_string(),
+            restricted: true,
+            lag: 1,
+        });
+        assert_eq!(
+            node.score(Network::Mainnet),
+            Some(40 + NODE_LAG_PENALTY + NODE_RESTRICTED_PENALTY)
+        );
+        // a node of another network is not usable
+        assert_eq!(node.score(Network::Stagenet), None);
+        node.health.as_mut().unwrap().lag = NODE_MAX_LAG + 1;
+        assert_eq!(node.score(Network::Mainnet), None);
+    }
+}
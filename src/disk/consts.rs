@@ -14,6 +14,8 @@ pub const DIRECTORY: &str = "gupax/";
 pub const STATE_TOML: &str = "state.toml";
 pub const NODE_TOML: &str = "node.toml";
 pub const POOL_TOML: &str = "pool.toml";
+pub const KNOWN_NODES_TOML: &str = "known_nodes.toml";
+pub const XVB_HISTORY_TOML: &str = "xvb_history.toml";
 
 // P2Pool API
 // Lives within the Gupax OS data directory.
@@ -28,10 +30,12 @@ pub const GUPAX_P2POOL_API_DIRECTORY: &str = "p2pool/";
 pub const GUPAX_P2POOL_API_LOG: &str = "log";
 pub const GUPAX_P2POOL_API_PAYOUT: &str = "payout";
 pub const GUPAX_P2POOL_API_XMR: &str = "xmr";
-pub const GUPAX_P2POOL_API_FILE_ARRAY: [&str; 3] = [
+pub const GUPAX_P2POOL_API_SHARE: &str = "share";
+pub const GUPAX_P2POOL_API_FILE_ARRAY: [&str; 4] = [
     GUPAX_P2POOL_API_LOG,
     GUPAX_P2POOL_API_PAYOUT,
     GUPAX_P2POOL_API_XMR,
+    GUPAX_P2POOL_API_SHARE,
 ];
 
 #[cfg(target_os = "windows")]
@@ -16,6 +16,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::*;
+use crate::disk::share_history::{Share, ShareHistory};
 //---------------------------------------------------------------------------------------------------- Gupax-P2Pool API
 #[derive(Clone, Debug)]
 pub struct GupaxP2poolApi {
@@ -27,9 +28,11 @@ pub struct GupaxP2poolApi {
     pub payout_low: String, // A pre-allocated/computed [String] of the above Vec from low payout to high
     pub payout_high: String, // Same as above but high -> low
     pub xmr: AtomicUnit,    // XMR stored as atomic units
+    pub shares: ShareHistory, // Shares found by the local P2Pool
     pub path_log: PathBuf,  // Path to [log]
     pub path_payout: PathBuf, // Path to [payout]
     pub path_xmr: PathBuf,  // Path to [xmr]
+    pub path_share: PathBuf, // Path to [share]
 }
 
 impl Default for GupaxP2poolApi {
@@ -50,7 +53,9 @@ impl GupaxP2poolApi {
             payout_low: String::new(),
             payout_high: String::new(),
             xmr: AtomicUnit::new(),
+            shares: ShareHistory::new(),
             path_xmr: PathBuf::new(),
+            path_share: PathBuf::new(),
             path_payout: PathBuf::new(),
             path_log: PathBuf::new(),
         }
@@ -60,13 +65,16 @@ impl GupaxP2poolApi {
         let mut path_log = gupax_p2pool_dir.to_path_buf();
         let mut path_payout = gupax_p2pool_dir.to_path_buf();
         let mut path_xmr = gupax_p2pool_dir.to_path_buf();
+        let mut path_share = gupax_p2pool_dir.to_path_buf();
         path_log.push(GUPAX_P2POOL_API_LOG);
         path_payout.push(GUPAX_P2POOL_API_PAYOUT);
         path_xmr.push(GUPAX_P2POOL_API_XMR);
+        path_share.push(GUPAX_P2POOL_API_SHARE);
         *self = Self {
             path_log,
             path_payout,
             path_xmr,
+            path_share,
             ..std::mem::take(self)
         };
     }
@@ -127,6 +135,8 @@ impl GupaxP2poolApi {
         };
         let payout = HumanNumber::from_u64(payout_u64);
         let log = read_to_string(File::Log, &self.path_log)?;
+        let shares =
+            ShareHistory::from_file_content(&read_to_string(File::Share, &self.path_share)?);
         self.payout_ord.update_from_payout_log(&log);
         self.update_payout_strings();
         *self = Self {
@@ -134,6 +144,7 @@ impl GupaxP2poolApi {
             payout,
             payout_u64,
             xmr,
+            shares,
             ..std::mem::take(self)
         };
         self.update_log_rev();
@@ -211,6 +222,13 @@ impl GupaxP2poolApi {
         self.update_payout_strings();
     }
 
+    // Record a share found by the local P2Pool in memory and on disk.
+    pub fn add_share(&mut self, share: Share) -> Result<(), TomlError> {
+        let line = share.to_string();
+        self.shares.push(share);
+        Self::disk_append(&line, &self.path_share)
+    }
+
     pub fn write_to_all_files(&self, formatted_log_line: &str) -> Result<(), TomlError> {
         Self::disk_overwrite(&self.payout_u64.to_string(), &self.path_payout)?;
         Self::disk_overwrite(&self.xmr.to_string(), &self.path_xmr)?;
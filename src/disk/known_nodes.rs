@@ -0,0 +1,307 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::components::node::{RemoteNode, RemoteNodes};
+use crate::disk::node::Node as NodeString;
+use crate::utils::monero_address::Network;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//---------------------------------------------------------------------------------------------------- Const
+// number of latency measures kept per node
+pub const KNOWN_NODES_LATENCY_HISTORY: usize = 10;
+// a node is known to be bad after this number of failures if it fails more than it succeeds
+pub const KNOWN_NODES_BAD_FAILURES: u32 = 3;
+// a bad node is checked again this long after its last failure
+pub const KNOWN_NODES_BAD_COOLDOWN_SECS: u64 = 60 * 60;
+// nodes not seen for this duration are forgotten
+pub const KNOWN_NODES_EXPIRATION_SECS: u64 = 60 * 60 * 24 * 30;
+// a node must have been working for at least this number of checks to be reused by the crawler
+pub const KNOWN_NODES_RELIABLE_SUCCESSES: u32 = 3;
+// maximum bonus in ms removed from the score of a reliable node
+pub const KNOWN_NODES_MAX_BONUS: u64 = 50;
+// P2Pool uptime with a node after which the node receives the full uptime bonus
+pub const KNOWN_NODES_UPTIME_FULL_BONUS: u64 = 60 * 60 * 24;
+
+//---------------------------------------------------------------------------------------------------- [KnownNodes]
+// Reputation of every remote node crawled or pinged, saved in [known_nodes.toml].
+// It allows the crawler and the pinger to reuse reliable nodes across restarts
+// and to skip nodes known to be bad.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct KnownNodes {
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(default)]
+    pub nodes: Vec<KnownNode>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KnownNode {
+    pub ip: IpAddr,
+    pub rpc: u16,
+    pub zmq: u16,
+    // unix timestamps in seconds, [last_seen] is the last time the node answered
+    pub first_seen: u64,
+    pub last_seen: u64,
+    #[serde(default)]
+    pub last_failure: u64,
+    // latest latency measures in ms, oldest first
+    pub latencies: Vec<u64>,
+    pub successes: u32,
+    pub failures: u32,
+    pub synchronized: bool,
+    // seconds P2Pool stayed connected to this node
+    pub p2pool_uptime: u64,
+    // network returned by the last health check, nodes saved before it was recorded are mainnet nodes.
+    #[serde(default)]
+    pub network: Network,
+}
+
+impl KnownNode {
+    fn new(ip: IpAddr, rpc: u16, zmq: u16, now: u64) -> Self {
+        Self {
+            ip,
+            rpc,
+            zmq,
+            first_seen: now,
+            last_seen: now,
+            last_failure: 0,
+            latencies: vec![],
+            successes: 0,
+            failures: 0,
+            synchronized: false,
+            p2pool_uptime: 0,
+            network: Network::default(),
+        }
+    }
+
+    fn is(&self, ip: IpAddr, rpc: u16, zmq: u16) -> bool {
+        self.ip == ip && self.rpc == rpc && self.zmq == zmq
+    }
+
+    pub fn avg_latency(&self) -> u64 {
+        if self.latencies.is_empty() {
+            return 0;
+        }
+        self.latencies.iter().sum::<u64>() / self.latencies.len() as u64
+    }
+
+    // ratio of successful checks, 0.0 if never checked.
+    pub fn success_rate(&self) -> f64 {
+        let total = self.successes + self.failures;
+        if total == 0 {
+            return 0.0;
+        }
+        self.successes as f64 / total as f64
+    }
+
+    fn fails_mostly(&self) -> bool {
+        self.failures >= KNOWN_NODES_BAD_FAILURES && self.failures > self.successes
+    }
+
+    // A node failing most of the time is skipped until the cooldown after its last failure,
+    // then it is checked again so that it can recover.
+    pub fn is_bad(&self, now: u64) -> bool {
+        self.fails_mostly() && now.saturating_sub(self.last_failure) < KNOWN_NODES_BAD_COOLDOWN_SECS
+    }
+
+    pub fn is_reliable(&self) -> bool {
+        !self.fails_mostly()
+            && self.synchronized
+            && self.successes >= KNOWN_NODES_RELIABLE_SUCCESSES
+    }
+
+    // Bonus in ms given to long-lived reliable nodes, from 0 to [KNOWN_NODES_MAX_BONUS].
+    // Half of it comes from the success rate, the other half from the time P2Pool stayed connected.
+    pub fn bonus(&self) -> u64 {
+        if self.fails_mostly() {
+            return 0;
+        }
+        let uptime = self.p2pool_uptime.min(KNOWN_NODES_UPTIME_FULL_BONUS) as f64
+            / KNOWN_NODES_UPTIME_FULL_BONUS as f64;
+        ((self.success_rate() + uptime) / 2.0 * KNOWN_NODES_MAX_BONUS as f64) as u64
+    }
+
+    pub fn to_remote_node(&self) -> RemoteNode {
+        RemoteNode {
+            ip: self.ip,
+            rpc: self.rpc,
+            zmq: self.zmq,
+            ms: self.avg_latency(),
+            health: None,
+        }
+    }
+}
+
+impl KnownNodes {
+    //---------------------------------------------------------------------------------------------------- Read/Write
+    // Read [known_nodes.toml], starting with an empty database if it does not exist or can not be parsed.
+    // It's only a cache, losing it is not worth stopping Gupax.
+    pub fn get(path: &Path) -> Self {
+        let mut known_nodes = match read_to_string(File::Crawl, &path.to_path_buf()) {
+            Ok(string) => match toml::de::from_str::<Self>(&string) {
+                Ok(known_nodes) => {
+                    info!("KnownNodes | Parse ... OK");
+                    known_nodes
+                }
+                Err(e) => {
+                    warn!("KnownNodes | Parse ... FAIL ... {e}, starting with an empty list");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        };
+        known_nodes.path = path.to_path_buf();
+        known_nodes.prune(now());
+        known_nodes
+    }
+
+    pub fn save(&self) -> Result<(), TomlError> {
+        // not read from the disk, nothing to save (e.g. the default of the crawler)
+        if self.path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let string = match toml::ser::to_string(self) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("KnownNodes | Couldn't serialize: {e}");
+                return Err(TomlError::Serialize(e));
+            }
+        };
+        match fs::write(&self.path, string) {
+            Ok(_) => {
+                info!("KnownNodes | Save ... OK");
+                Ok(())
+            }
+            Err(e) => {
+                error!("KnownNodes | Couldn't overwrite [{}]", self.path.display());
+                Err(TomlError::Io(e))
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------------- Mutate
+    fn entry(&mut self, ip: IpAddr, rpc: u16, zmq: u16, now: u64) -> &mut KnownNode {
+        let index = match self.nodes.iter().position(|n| n.is(ip, rpc, zmq)) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(KnownNode::new(ip, rpc, zmq, now));
+                self.nodes.len() - 1
+            }
+        };
+        &mut self.nodes[index]
+    }
+
+    // The node answered a check with this latency.
+    // The health of the node is only known after a ping, the crawler only knows it is reachable.
+    // The past failures are halved so that the recent results weigh more.
+    pub fn record_success(&mut self, node: &RemoteNode, now: u64) {
+        let known = self.entry(node.ip, node.rpc, node.zmq, now);
+        known.last_seen = now;
+        known.successes += 1;
+        known.failures /= 2;
+        if node.ms != 0 {
+            known.latencies.push(node.ms);
+            if known.latencies.len() > KNOWN_NODES_LATENCY_HISTORY {
+                known.latencies.remove(0);
+            }
+        }
+        if let Some(health) = &node.health {
+            match health.network() {
+                Some(network) => {
+                    known.network = network;
+                    known.synchronized = health.is_healthy(network);
+                }
+                None => known.synchronized = false,
+            }
+        }
+    }
+
+    pub fn record_failure(&mut self, node: &RemoteNode, now: u64) {
+        let known = self.entry(node.ip, node.rpc, node.zmq, now);
+        known.failures += 1;
+        known.last_failure = now;
+    }
+
+    // P2Pool stayed connected to this node for [secs] seconds.
+    // Local nodes are not recorded since they are not part of the remote node list.
+    pub fn record_p2pool_uptime(&mut self, node: &NodeString, secs: u64, now: u64) {
+        let (Ok(ip), Ok(rpc), Ok(zmq)) = (
+            node.ip.parse::<IpAddr>(),
+            node.rpc.parse::<u16>(),
+            node.zmq.parse::<u16>(),
+        ) else {
+            return;
+        };
+        if ip.is_loopback() {
+            return;
+        }
+        let known = self.entry(ip, rpc, zmq, now);
+        known.last_seen = now;
+        known.p2pool_uptime += secs;
+    }
+
+    // Forget nodes that did not answer for a long time.
+    pub fn prune(&mut self, now: u64) {
+        self.nodes
+            .retain(|n| now.saturating_sub(n.last_seen) < KNOWN_NODES_EXPIRATION_SECS);
+    }
+
+    //---------------------------------------------------------------------------------------------------- Query
+    pub fn find(&self, node: &RemoteNode) -> Option<&KnownNode> {
+        self.nodes
+            .iter()
+            .find(|n| n.is(node.ip, node.rpc, node.zmq))
+    }
+
+    pub fn is_bad(&self, node: &RemoteNode, now: u64) -> bool {
+        self.find(node).is_some_and(|n| n.is_bad(now))
+    }
+
+    pub fn bonus(&self, node: &RemoteNode) -> u64 {
+        self.find(node).map_or(0, |n| n.bonus())
+    }
+
+    // Reliable nodes of this network, best first: longest P2Pool uptime, then best success rate, then lowest latency.
+    pub fn reliable_nodes(&self, max: usize, network: Network) -> RemoteNodes {
+        let mut reliable = self
+            .nodes
+            .iter()
+            .filter(|n| n.network == network && n.is_reliable())
+            .collect::<Vec<_>>();
+        reliable.sort_by(|a, b| {
+            b.p2pool_uptime
+                .cmp(&a.p2pool_uptime)
+                .then(crate::miscs::cmp_f64(b.success_rate(), a.success_rate()))
+                .then(a.avg_latency().cmp(&b.avg_latency()))
+        });
+        let mut nodes = RemoteNodes::default();
+        for node in reliable.into_iter().take(max) {
+            nodes.push(node.to_remote_node());
+        }
+        nodes
+    }
+}
+
+// Current unix timestamp in seconds.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -18,6 +18,8 @@
 // This handles reading/writing the disk files:
 //     - [state.toml] -> [App] state
 //     - [nodes.toml] -> [Manual Nodes] list
+//     - [known_nodes.toml] -> [Known Nodes] reputation of crawled/pinged nodes
+//     - [xvb_history.toml] -> [XvB History] rounds and raffles of XvB
 // The TOML format is used. This struct hierarchy
 // directly translates into the TOML parser:
 //   State/
@@ -53,11 +55,15 @@ use self::errors::TomlError;
 pub mod consts;
 pub mod errors;
 pub mod gupax_p2pool_api;
+pub mod known_nodes;
 pub mod node;
+pub mod payout_export;
 pub mod pool;
+pub mod share_history;
 pub mod state;
 pub mod status;
 pub mod tests;
+pub mod xvb_history;
 //---------------------------------------------------------------------------------------------------- General functions for all [File]'s
 // get_file_path()      | Return absolute path to OS data path + filename
 // read_to_string()     | Convert the file at a given path into a [String]
@@ -184,13 +190,15 @@ pub fn into_absolute_path(path: String) -> Result<PathBuf, TomlError> {
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize)]
 pub enum File {
     // State files
-    State, // state.toml        | Gupax state
-    Node,  // node.toml         | P2Pool manual node selector
-    Pool,  // pool.toml         | XMRig manual pool selector
-    Crawl, // found_nodes.toml | Crawler saved results
+    State,      // state.toml        | Gupax state
+    Node,       // node.toml         | P2Pool manual node selector
+    Pool,       // pool.toml         | XMRig manual pool selector
+    Crawl,      // known_nodes.toml | Crawler saved results
+    XvbHistory, // xvb_history.toml | XvB rounds and raffles history
 
     // Gupax-P2Pool API
     Log,    // log    | Raw log lines of P2Pool payouts received
     Payout, // payout | Single [u64] representing total payouts
     Xmr,    // xmr    | Single [u64] representing total XMR mined in atomic units
+    Share,  // share  | Shares found by the local P2Pool, one per line
 }
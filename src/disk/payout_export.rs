@@ -0,0 +1,373 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Export of the payout log to CSV or JSON, for accounting.
+// Every row is a payout: date, block, atomic units, XMR and optionally its fiat value at the date.
+// The prices come from a [PriceSource]:
+//     - a local CSV file of "YYYY-MM-DD,price" lines
+//     - an HTTP endpoint, "{date}" in the URL is replaced by YYYY-MM-DD and it must answer
+//       with the price as a JSON number or as {"price": number}
+
+use super::*;
+use anyhow::Context;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::time::Duration;
+
+//---------------------------------------------------------------------------------------------------- Const
+// A price source can be slow, but the export must end.
+const TIMEOUT_PRICE_REQUEST: u64 = 10_000;
+
+//---------------------------------------------------------------------------------------------------- ExportFormat
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PriceSource
+pub trait PriceSource {
+    // Price of one XMR at the date, None if unknown.
+    fn price(&mut self, date: NaiveDate) -> Option<f64>;
+}
+
+// Local CSV file, one "YYYY-MM-DD,price" per line. Lines that are not prices (header) are skipped.
+#[derive(Debug, Default)]
+pub struct CsvPrices(HashMap<NaiveDate, f64>);
+
+impl CsvPrices {
+    pub fn parse(csv: &str) -> Self {
+        Self(
+            csv.lines()
+                .filter_map(|line| {
+                    let (date, price) = line.split_once(',')?;
+                    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+                    Some((date, price.trim().parse().ok()?))
+                })
+                .collect(),
+        )
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let csv = fs::read_to_string(path)
+            .with_context(|| format!("can not read the prices [{}]", path.display()))?;
+        Ok(Self::parse(&csv))
+    }
+}
+
+impl PriceSource for CsvPrices {
+    fn price(&mut self, date: NaiveDate) -> Option<f64> {
+        self.0.get(&date).copied()
+    }
+}
+
+// HTTP endpoint, every date is only requested once.
+#[derive(Debug)]
+pub struct HttpPrices {
+    url: String,
+    client: Client,
+    cache: HashMap<NaiveDate, Option<f64>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HttpPrice {
+    Number(f64),
+    Object { price: f64 },
+}
+
+impl HttpPrices {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: Client::builder()
+                .timeout(Duration::from_millis(TIMEOUT_PRICE_REQUEST))
+                .build()
+                .unwrap_or_default(),
+            cache: HashMap::new(),
+        }
+    }
+
+    #[tokio::main]
+    async fn request(&self, date: NaiveDate) -> anyhow::Result<f64> {
+        let url = self
+            .url
+            .replace("{date}", &date.format("%Y-%m-%d").to_string());
+        let price = match self.client.get(url).send().await?.json().await? {
+            HttpPrice::Number(price) | HttpPrice::Object { price } => price,
+        };
+        Ok(price)
+    }
+}
+
+impl PriceSource for HttpPrices {
+    fn price(&mut self, date: NaiveDate) -> Option<f64> {
+        if let Some(price) = self.cache.get(&date) {
+            return *price;
+        }
+        let price = match self.request(date) {
+            Ok(price) => Some(price),
+            Err(e) => {
+                warn!("Payout Export | Price of [{date}] ... FAIL ... {e}");
+                None
+            }
+        };
+        self.cache.insert(date, price);
+        price
+    }
+}
+
+// An URL is requested, anything else is a CSV file.
+pub fn price_source(source: &str) -> anyhow::Result<Box<dyn PriceSource>> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Ok(Box::new(HttpPrices::new(source)))
+    } else {
+        Ok(Box::new(CsvPrices::read(Path::new(source))?))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PayoutRow
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PayoutRow {
+    pub date: String,
+    pub block: u64,
+    pub atomic_units: u64,
+    pub xmr: String,
+    pub fiat: Option<f64>,
+}
+
+// Rows of the payouts between [from] and [to] included, oldest first.
+// Payouts with an unknown date or block can not be accounted and are skipped.
+pub fn payout_rows(
+    payouts: &PayoutOrd,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    mut prices: Option<&mut dyn PriceSource>,
+) -> Vec<PayoutRow> {
+    let mut rows: Vec<PayoutRow> = payouts
+        .iter()
+        .filter_map(|(date, atomic_unit, block)| {
+            let day = NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+            if from.is_some_and(|from| day < from) || to.is_some_and(|to| day > to) {
+                return None;
+            }
+            let atomic_units = atomic_unit.to_u64();
+            let fiat = prices.as_mut().and_then(|p| p.price(day)).map(|price| {
+                // rounded to the cent
+                (price * atomic_units as f64 / 1_000_000_000_000.0 * 100.0).round() / 100.0
+            });
+            Some(PayoutRow {
+                date: date.clone(),
+                block: block.to_string().replace(',', "").parse().ok()?,
+                atomic_units,
+                xmr: atomic_unit.human_number_12_point().to_string(),
+                fiat,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| a.date.cmp(&b.date).then(a.block.cmp(&b.block)));
+    rows
+}
+
+pub fn export_rows(rows: &[PayoutRow], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Csv => {
+            let mut csv = String::from("date,block,atomic_units,xmr,fiat\n");
+            for row in rows {
+                let fiat = row.fiat.map(|f| format!("{f:.2}")).unwrap_or_default();
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{fiat}",
+                    row.date, row.block, row.atomic_units, row.xmr
+                );
+            }
+            csv
+        }
+        // serializing plain structs can not fail
+        ExportFormat::Json => serde_json::to_string_pretty(rows).unwrap_or_default(),
+    }
+}
+
+// Export the formatted payout log, the format of [GupaxP2poolApi.log].
+pub fn export_payout_log(
+    log: &str,
+    format: ExportFormat,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    prices: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut payouts = PayoutOrd::new();
+    payouts.update_from_payout_log(log);
+    let mut prices = prices.map(price_source).transpose()?;
+    let rows = match prices.as_mut() {
+        Some(prices) => payout_rows(&payouts, from, to, Some(prices.as_mut())),
+        None => payout_rows(&payouts, from, to, None),
+    };
+    Ok(export_rows(&rows, format))
+}
+
+//---------------------------------------------------------------------------------------------------- PayoutExport
+// State of the export from the GUI, the file dialog and the price requests are in a thread.
+#[derive(Debug, Default)]
+pub struct PayoutExport {
+    pub thread: bool,
+    pub msg: String,
+}
+
+impl PayoutExport {
+    pub fn spawn_thread(
+        export: &Arc<Mutex<Self>>,
+        log: String,
+        format: ExportFormat,
+        prices: String,
+    ) {
+        export.lock().unwrap().thread = true;
+        let export = export.clone();
+        std::thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .set_title("Export the payouts")
+                .set_file_name(format!("gupax_payouts.{}", format.extension()))
+                .save_file();
+            let msg = match path {
+                Some(path) => {
+                    let prices = (!prices.trim().is_empty()).then_some(prices.as_str());
+                    match export_payout_log(&log, format, None, None, prices)
+                        .and_then(|out| Ok(fs::write(&path, out)?))
+                    {
+                        Ok(()) => {
+                            info!("Payout Export | [{}] ... OK", path.display());
+                            format!("Exported to [{}]", path.display())
+                        }
+                        Err(e) => {
+                            warn!("Payout Export | [{}] ... FAIL ... {e}", path.display());
+                            format!("Export failed: {e}")
+                        }
+                    }
+                }
+                None => String::new(),
+            };
+            let mut export = export.lock().unwrap();
+            export.msg = msg;
+            export.thread = false;
+        });
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LOG: &str = r#"2023-01-02 10:00:00.0000 | 0.002000000000 XMR | Block 2,800,100
+2023-01-01 09:00:00.0000 | 0.001000000000 XMR | Block 2,800,000
+2023-01-03 11:00:00.0000 | 0.003000000000 XMR | Block 2,800,200
+"#;
+
+    // Price of a fake currency, one per day since the first of January.
+    struct StubPrices(Vec<NaiveDate>);
+
+    impl PriceSource for StubPrices {
+        fn price(&mut self, date: NaiveDate) -> Option<f64> {
+            self.0.push(date);
+            (date != NaiveDate::from_ymd_opt(2023, 1, 3).unwrap()).then_some(150.0)
+        }
+    }
+
+    fn payouts() -> PayoutOrd {
+        let mut payouts = PayoutOrd::new();
+        payouts.update_from_payout_log(LOG);
+        payouts
+    }
+
+    #[test]
+    fn export_csv() {
+        let rows = payout_rows(&payouts(), None, None, None);
+        assert_eq!(
+            export_rows(&rows, ExportFormat::Csv),
+            "date,block,atomic_units,xmr,fiat
+2023-01-01 09:00:00.0000,2800000,1000000000,0.001000000000,
+2023-01-02 10:00:00.0000,2800100,2000000000,0.002000000000,
+2023-01-03 11:00:00.0000,2800200,3000000000,0.003000000000,
+"
+        );
+    }
+
+    #[test]
+    fn export_json_with_prices_and_dates() {
+        let mut prices = StubPrices(vec![]);
+        let rows = payout_rows(
+            &payouts(),
+            NaiveDate::from_ymd_opt(2023, 1, 2),
+            NaiveDate::from_ymd_opt(2023, 1, 3),
+            Some(&mut prices),
+        );
+        // only the dates of the exported payouts are priced
+        assert_eq!(prices.0.len(), 2);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].block, 2800100);
+        assert_eq!(rows[0].fiat, Some(0.3));
+        assert_eq!(rows[1].fiat, None);
+        let json: serde_json::Value =
+            serde_json::from_str(&export_rows(&rows, ExportFormat::Json)).unwrap();
+        assert_eq!(json[0]["atomic_units"], 2000000000);
+        assert_eq!(json[0]["date"], "2023-01-02 10:00:00.0000");
+        assert_eq!(json[1]["fiat"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn csv_price_source() {
+        let mut prices = CsvPrices::parse("date,price\n2023-01-01,120.5\n2023-01-02, 130\nbad,1\n");
+        assert_eq!(
+            prices.price(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()),
+            Some(120.5)
+        );
+        assert_eq!(
+            prices.price(NaiveDate::from_ymd_opt(2023, 1, 2).unwrap()),
+            Some(130.0)
+        );
+        assert_eq!(
+            prices.price(NaiveDate::from_ymd_opt(2023, 1, 3).unwrap()),
+            None
+        );
+        let rows = payout_rows(&payouts(), None, None, Some(&mut prices));
+        assert_eq!(rows[0].fiat, Some(0.12));
+        assert_eq!(rows[1].fiat, Some(0.26));
+        assert_eq!(rows[2].fiat, None);
+    }
+}
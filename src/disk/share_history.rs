@@ -0,0 +1,508 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// History of the shares found by the local P2Pool, saved in the [share] file of the Gupax-P2Pool API:
+//     [timestamp],[sidechain height],[difficulty],[effort in percent],[chain],[network]
+// Lines saved before the chain and the network were recorded are read as Main on mainnet.
+// The sidechain heights of the chains are unrelated, the history is always read for one chain and network.
+//
+// The effort of a share is the hashes needed to find it divided by its difficulty.
+// Hashes are random, so the efforts follow an exponential distribution of mean 100%:
+// the chance of a share to need between [a] and [b] effort is e^-a - e^-b.
+// Comparing the efforts found with this distribution tells if the shares were lucky.
+//
+// A share is paid when P2Pool finds a Monero block while the share is in the PPLNS window,
+// the last [window length] blocks of the sidechain. The payout of a block is split between
+// the shares of the window, weighted by their difficulty.
+
+use super::*;
+use crate::disk::state::P2poolChain;
+use crate::regex::{P2POOL_REGEX, share_found};
+use crate::utils::monero_address::Network;
+use chrono::{Local, NaiveDateTime, TimeZone};
+
+//---------------------------------------------------------------------------------------------------- Const
+// Width of the effort ranges of the luck distribution, in percent.
+pub const LUCK_BIN_WIDTH: f64 = 25.0;
+// Number of ranges, the last one is open and contains every effort above.
+pub const LUCK_BINS: usize = 8;
+
+//---------------------------------------------------------------------------------------------------- Share
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub timestamp: i64,
+    pub sidechain_height: u64,
+    pub difficulty: u64,
+    pub effort: f64,
+    pub chain: P2poolChain,
+    pub network: Network,
+}
+
+impl Share {
+    // Parse a [SHARE FOUND] line of the P2Pool console, dated by P2Pool in local time.
+    // The console does not tell the chain and the network, they are the ones P2Pool was started with.
+    pub fn from_p2pool_line(line: &str, chain: &P2poolChain, network: Network) -> Option<Self> {
+        let (sidechain_height, difficulty, effort) = share_found(line)?;
+        let timestamp = P2POOL_REGEX
+            .date
+            .find(line)
+            .and_then(|date| {
+                NaiveDateTime::parse_from_str(date.as_str(), "%Y-%m-%d %H:%M:%S%.f").ok()
+            })
+            .and_then(|date| Local.from_local_datetime(&date).earliest())
+            .map_or_else(|| Local::now().timestamp(), |date| date.timestamp());
+        Some(Self {
+            timestamp,
+            sidechain_height,
+            difficulty,
+            effort,
+            chain: chain.clone(),
+            network,
+        })
+    }
+
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut values = line.trim().split(',');
+        let mut share = Self {
+            timestamp: values.next()?.parse().ok()?,
+            sidechain_height: values.next()?.parse().ok()?,
+            difficulty: values.next()?.parse().ok()?,
+            effort: values.next()?.parse().ok()?,
+            chain: P2poolChain::Main,
+            network: Network::Mainnet,
+        };
+        if let Some(chain) = values.next() {
+            share.chain = chain.parse().ok()?;
+            share.network = values.next()?.parse().ok()?;
+        }
+        values.next().is_none().then_some(share)
+    }
+}
+
+impl Display for Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{},{},{},{:.3},{},{}",
+            self.timestamp,
+            self.sidechain_height,
+            self.difficulty,
+            self.effort,
+            self.chain,
+            self.network
+        )
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- LuckBin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LuckBin {
+    // effort range in percent, the last one has no end
+    pub from: f64,
+    pub to: Option<f64>,
+    pub shares: usize,
+    // shares expected in the range out of all the shares found
+    pub expected: f64,
+}
+
+//---------------------------------------------------------------------------------------------------- ShareHistory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShareHistory(Vec<Share>);
+
+impl ShareHistory {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    // Lines that can not be parsed are skipped, the file is only appended by Gupax.
+    pub fn from_file_content(content: &str) -> Self {
+        let mut shares: Vec<Share> = content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| {
+                let share = Share::parse_line(l);
+                if share.is_none() {
+                    warn!("GupaxP2poolApi | [share] invalid line skipped: {l}");
+                }
+                share
+            })
+            .collect();
+        shares.sort_by_key(|s| s.timestamp);
+        Self(shares)
+    }
+
+    // Shares found on this chain and network.
+    pub fn of(&self, chain: &P2poolChain, network: Network) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|s| s.chain == *chain && s.network == network)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    pub fn push(&mut self, share: Share) {
+        self.0.push(share);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Share> {
+        self.0.last()
+    }
+
+    // Seconds between each share and the previous one.
+    pub fn intervals(&self) -> Vec<u64> {
+        self.0
+            .windows(2)
+            .map(|w| w[1].timestamp.saturating_sub(w[0].timestamp).max(0) as u64)
+            .collect()
+    }
+
+    pub fn mean_interval(&self) -> Option<u64> {
+        let intervals = self.intervals();
+        (!intervals.is_empty()).then(|| intervals.iter().sum::<u64>() / intervals.len() as u64)
+    }
+
+    pub fn median_interval(&self) -> Option<u64> {
+        let mut intervals = self.intervals();
+        intervals.sort_unstable();
+        intervals.get(intervals.len() / 2).copied()
+    }
+
+    pub fn since_last(&self, now: i64) -> Option<u64> {
+        self.last()
+            .map(|s| now.saturating_sub(s.timestamp).max(0) as u64)
+    }
+
+    // Average effort in percent, under 100% means the shares were found faster than expected.
+    pub fn average_effort(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.0.iter().map(|s| s.effort).sum::<f64>() / self.len() as f64)
+    }
+
+    // Distribution of the efforts compared with the exponential distribution expected.
+    pub fn luck_distribution(&self) -> Vec<LuckBin> {
+        let total = self.len() as f64;
+        (0..LUCK_BINS)
+            .map(|i| {
+                let from = i as f64 * LUCK_BIN_WIDTH;
+                let to = (i + 1 < LUCK_BINS).then_some(from + LUCK_BIN_WIDTH);
+                let shares = self
+                    .0
+                    .iter()
+                    .filter(|s| s.effort >= from && to.is_none_or(|to| s.effort < to))
+                    .count();
+                let chance = (-from / 100.0).exp() - to.map_or(0.0, |to| (-to / 100.0).exp());
+                LuckBin {
+                    from,
+                    to,
+                    shares,
+                    expected: chance * total,
+                }
+            })
+            .collect()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PplnsWindow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowShare {
+    pub sidechain_height: u64,
+    pub difficulty: u64,
+    // sidechain blocks before the share leaves the window
+    pub blocks_left: u64,
+    // seconds before the share leaves the window
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PplnsWindow {
+    pub sidechain_height: u64,
+    pub length: u64,
+    // oldest share first
+    pub shares: Vec<WindowShare>,
+}
+
+impl PplnsWindow {
+    // Only the shares of [chain] and [network] are in the window,
+    // the heights of the other chains would match shares that are not on this sidechain.
+    pub fn new(
+        history: &ShareHistory,
+        chain: &P2poolChain,
+        network: Network,
+        sidechain_height: u64,
+        length: u64,
+        seconds_per_block: u64,
+    ) -> Self {
+        let mut shares: Vec<WindowShare> = history
+            .0
+            .iter()
+            .filter(|s| s.chain == *chain && s.network == network)
+            .filter(|s| {
+                s.sidechain_height <= sidechain_height
+                    && sidechain_height - s.sidechain_height < length
+            })
+            .map(|s| {
+                let blocks_left = length - (sidechain_height - s.sidechain_height);
+                WindowShare {
+                    sidechain_height: s.sidechain_height,
+                    difficulty: s.difficulty,
+                    blocks_left,
+                    expires_in: blocks_left * seconds_per_block,
+                }
+            })
+            .collect();
+        shares.sort_by_key(|s| s.sidechain_height);
+        Self {
+            sidechain_height,
+            length,
+            shares,
+        }
+    }
+
+    // Seconds before the last share leaves the window, None without share in it.
+    pub fn last_expiry(&self) -> Option<u64> {
+        self.shares.iter().map(|s| s.expires_in).max()
+    }
+
+    // Payout if P2Pool finds a Monero block now, the whole window having about the current difficulty.
+    pub fn projected_payout(&self, reward: AtomicUnit, sidechain_difficulty: u64) -> AtomicUnit {
+        let window_difficulty = self.length as u128 * sidechain_difficulty as u128;
+        if window_difficulty == 0 {
+            return AtomicUnit::new();
+        }
+        let difficulty = self
+            .shares
+            .iter()
+            .map(|s| s.difficulty as u128)
+            .sum::<u128>()
+            .min(window_difficulty);
+        AtomicUnit::from_u64((reward.to_u64() as u128 * difficulty / window_difficulty) as u64)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn share(timestamp: i64, effort: f64) -> Share {
+        Share {
+            timestamp,
+            sidechain_height: 9_000_000 + timestamp as u64,
+            difficulty: 100_000_000,
+            effort,
+            chain: P2poolChain::Mini,
+            network: Network::Mainnet,
+        }
+    }
+
+    #[test]
+    fn parse_share() {
+        let line = "NOTICE  2024-05-01 12:34:56.7890 StratumServer SHARE FOUND: mainchain height 3141592, sidechain height 9876543, diff 123456789, client 127.0.0.1:54321, user gupax, effort 87.654%";
+        let share = Share::from_p2pool_line(line, &P2poolChain::Mini, Network::Stagenet).unwrap();
+        let date =
+            NaiveDateTime::parse_from_str("2024-05-01 12:34:56", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            share.timestamp,
+            Local
+                .from_local_datetime(&date)
+                .earliest()
+                .unwrap()
+                .timestamp()
+        );
+        assert_eq!(share.sidechain_height, 9876543);
+        assert_eq!(share.difficulty, 123456789);
+        assert_eq!(share.effort, 87.654);
+        assert_eq!(share.chain, P2poolChain::Mini);
+        assert_eq!(share.network, Network::Stagenet);
+        // saved and read back
+        assert_eq!(Share::parse_line(&share.to_string()), Some(share));
+        // saved before the chain and the network were recorded
+        let old = Share::parse_line("1,2,3,4.5").unwrap();
+        assert_eq!(old.chain, P2poolChain::Main);
+        assert_eq!(old.network, Network::Mainnet);
+        assert_eq!(Share::parse_line("1,2,3"), None);
+        assert_eq!(Share::parse_line("1,2,3,4,5"), None);
+        assert_eq!(Share::parse_line("1,2,3,4,Nano"), None);
+        assert_eq!(Share::parse_line("1,2,3,4,Nano,mainnet,6"), None);
+        assert_eq!(
+            Share::from_p2pool_line(
+                "NOTICE  2024-05-01 12:34:56.7890 P2Pool You received a payout of 0.1 XMR",
+                &P2poolChain::Main,
+                Network::Mainnet
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn share_intervals() {
+        // written out of order and with a corrupted line
+        let history = ShareHistory::from_file_content(
+            "1000,1,1,50.0\n400,1,1,150.0\nnot a share\n100,1,1,25.5\n\n",
+        );
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.intervals(), vec![300, 600]);
+        assert_eq!(history.mean_interval(), Some(450));
+        assert_eq!(history.median_interval(), Some(600));
+        assert_eq!(history.since_last(1600), Some(600));
+        assert_eq!(history.last().unwrap().timestamp, 1000);
+        let average = history.average_effort().unwrap();
+        assert!((average - 75.166).abs() < 0.001);
+        // only the shares of the chain and network
+        let history = ShareHistory::from_file_content(
+            "100,1,1,25.5\n200,1,1,50.0,Nano,mainnet\n300,1,1,50.0,Nano,stagenet\n",
+        );
+        let nano = history.of(&P2poolChain::Nano, Network::Mainnet);
+        assert_eq!(nano.len(), 1);
+        assert_eq!(nano.last().unwrap().timestamp, 200);
+        assert_eq!(history.of(&P2poolChain::Main, Network::Mainnet).len(), 1);
+        assert!(history.of(&P2poolChain::Mini, Network::Mainnet).is_empty());
+        let empty = ShareHistory::new();
+        assert_eq!(empty.mean_interval(), None);
+        assert_eq!(empty.median_interval(), None);
+        assert_eq!(empty.since_last(1600), None);
+        assert_eq!(empty.average_effort(), None);
+    }
+
+    #[test]
+    fn luck_distribution() {
+        let mut history = ShareHistory::new();
+        for (i, effort) in [10.0, 20.0, 30.0, 99.9, 100.0, 180.0, 175.0, 450.0]
+            .into_iter()
+            .enumerate()
+        {
+            history.push(share(i as i64, effort));
+        }
+        let bins = history.luck_distribution();
+        assert_eq!(bins.len(), LUCK_BINS);
+        assert_eq!(bins[0].from, 0.0);
+        assert_eq!(bins[0].to, Some(25.0));
+        assert_eq!(bins[0].shares, 2);
+        assert_eq!(bins[1].shares, 1);
+        assert_eq!(bins[3].shares, 1);
+        assert_eq!(bins[4].shares, 1);
+        assert_eq!(bins[6].shares, 0);
+        // 175% and above
+        assert_eq!(bins[7].to, None);
+        assert_eq!(bins[7].shares, 3);
+        assert_eq!(bins.iter().map(|b| b.shares).sum::<usize>(), history.len());
+        // the expected shares add up to all the shares
+        let expected: f64 = bins.iter().map(|b| b.expected).sum();
+        assert!((expected - 8.0).abs() < 1e-9);
+        assert!((bins[0].expected - 8.0 * (1.0 - (-0.25f64).exp())).abs() < 1e-9);
+        assert!(bins[0].expected > bins[1].expected);
+    }
+
+    #[test]
+    fn pplns_window() {
+        let history = ShareHistory::from_file_content(
+            "0,9000000,1000000,50.0,Mini,mainnet\n\
+             0,9001000,2000000,50.0,Mini,mainnet\n\
+             0,9002100,1000000,50.0,Mini,mainnet\n\
+             0,9003000,1000000,50.0,Mini,mainnet\n",
+        );
+        let mini = |sidechain_height| {
+            PplnsWindow::new(
+                &history,
+                &P2poolChain::Mini,
+                Network::Mainnet,
+                sidechain_height,
+                2160,
+                10,
+            )
+        };
+        // Mini, 2160 blocks of 10 seconds: the first share is out of the window
+        let window = mini(9002200);
+        assert_eq!(window.shares.len(), 2);
+        assert_eq!(window.shares[0].sidechain_height, 9001000);
+        assert_eq!(window.shares[0].blocks_left, 960);
+        assert_eq!(window.shares[0].expires_in, 9600);
+        assert_eq!(window.shares[1].blocks_left, 2060);
+        assert_eq!(window.last_expiry(), Some(20600));
+        // 3_000_000 of the 2160 × 1_000_000 window difficulty
+        assert_eq!(
+            window.projected_payout(AtomicUnit::from_u64(720_000_000_000), 1_000_000),
+            AtomicUnit::from_u64(1_000_000_000)
+        );
+        assert_eq!(
+            window.projected_payout(AtomicUnit::from_u64(720_000_000_000), 0),
+            AtomicUnit::new()
+        );
+        // a share exactly [length] blocks old just left
+        let window = mini(9003160);
+        assert_eq!(window.shares.len(), 2);
+        assert_eq!(window.shares[0].blocks_left, 1100);
+        assert_eq!(window.shares[1].blocks_left, 2000);
+        // no share
+        let window = mini(9010000);
+        assert!(window.shares.is_empty());
+        assert_eq!(window.last_expiry(), None);
+    }
+
+    #[test]
+    fn pplns_window_mixed_chains() {
+        // shares of Main, Nano and of Mini on stagenet at heights close to the Mini shares
+        let history = ShareHistory::from_file_content(
+            "0,9001000,2000000,50.0,Mini,mainnet\n\
+             0,9001500,3000000,50.0,Main,mainnet\n\
+             0,9002000,4000000,50.0\n\
+             0,9002100,5000000,50.0,Nano,mainnet\n\
+             0,9002150,6000000,50.0,Mini,stagenet\n",
+        );
+        let window = PplnsWindow::new(
+            &history,
+            &P2poolChain::Mini,
+            Network::Mainnet,
+            9002200,
+            2160,
+            10,
+        );
+        assert_eq!(window.shares.len(), 1);
+        assert_eq!(window.shares[0].sidechain_height, 9001000);
+        // the expiry is the one of the Mini share, not of the later shares of the other chains
+        assert_eq!(window.last_expiry(), Some(9600));
+        // the old lines are read as Main on mainnet
+        let window = PplnsWindow::new(
+            &history,
+            &P2poolChain::Main,
+            Network::Mainnet,
+            9002200,
+            2160,
+            10,
+        );
+        assert_eq!(window.shares.len(), 2);
+        let window = PplnsWindow::new(
+            &history,
+            &P2poolChain::Mini,
+            Network::Stagenet,
+            9002200,
+            2160,
+            10,
+        );
+        assert_eq!(window.shares.len(), 1);
+        assert_eq!(window.shares[0].difficulty, 6000000);
+    }
+}
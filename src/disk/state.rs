@@ -1,6 +1,6 @@
 use derive_more::Display;
 use rand::{Rng, distr::Alphanumeric, rng};
-use strum::{EnumCount, EnumIter, IntoEnumIterator};
+use strum::{EnumCount, EnumIter, EnumString, IntoEnumIterator};
 
 use super::*;
 use crate::{
@@ -14,6 +14,7 @@ use crate::{
         Helper, ProcessName, crawler::CrawlerRequirements, node::ImgNode, p2pool::ImgP2pool,
         xrig::xmrig_proxy::ImgProxy,
     },
+    utils::monero_address::Network,
 };
 //---------------------------------------------------------------------------------------------------- [State] Impl
 impl Default for State {
@@ -171,7 +172,7 @@ impl State {
     // leaving behind old keys+values and updating [default] with old valid ones.
     pub fn merge(old: &str) -> Result<Self, TomlError> {
         let default = toml::ser::to_string(&Self::new()).unwrap();
-        let new: Self = match Figment::from(Toml::string(&default))
+        let mut new: Self = match Figment::from(Toml::string(&default))
             .merge(Toml::string(old))
             .extract()
         {
@@ -184,6 +185,18 @@ impl State {
                 return Err(TomlError::Merge(err));
             }
         };
+        // [manual_pool_eu] of older versions is replaced by the label of the pool.
+        if let Ok(old) = toml::de::from_str::<toml::Table>(old)
+            && let Some(xvb) = old.get("xvb").and_then(|x| x.as_table())
+            && !xvb.contains_key("manual_pool")
+            && let Some(eu) = xvb.get("manual_pool_eu").and_then(|v| v.as_bool())
+        {
+            new.xvb.manual_pool = if eu {
+                XvbPoolEndpoint::europe().label
+            } else {
+                XvbPoolEndpoint::north_america().label
+            };
+        }
         Ok(new)
     }
 }
@@ -208,6 +221,24 @@ pub struct Status {
     pub manual_hash: bool,
     pub hashrate: f64,
     pub hash_metric: Hash,
+    // private view key of the P2Pool wallet encrypted with a passphrase, empty if not set
+    pub encrypted_view_key: String,
+    // CSV file or URL giving the prices of the exported payouts, empty to export without fiat value
+    pub price_source: String,
+    pub calculator: Calculator,
+}
+
+// Settings of the earnings calculator of the Status tab.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Calculator {
+    pub chain: P2poolChain,
+    // difficulty of the sidechain, used when P2Pool is not running on the chain
+    pub sidechain_difficulty: u64,
+    // consumption of the rig in watts
+    pub power: f64,
+    // price of a kWh and of a XMR, in the same currency
+    pub kwh_price: f64,
+    pub xmr_price: f64,
 }
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
@@ -230,6 +261,8 @@ pub struct Gupax {
     pub show_processes: Vec<ProcessName>,
     pub notifications: Vec<Notification>,
     pub theme: GupaxTheme,
+    // Monero network of the Node, P2Pool and the remote nodes.
+    pub network: Network,
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -406,8 +439,38 @@ pub struct P2pool {
     pub stratum_port: u16,
     pub selected_node: SelectedPoolNode,
     pub prefer_local_node: bool,
+    pub auto_failover: bool,
     pub console_height: u32,
     pub crawl_settings: CrawlerRequirements,
+    pub observers: Observers,
+}
+
+// Base URL of the P2Pool observer of each chain.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Observers {
+    pub main: String,
+    pub mini: String,
+    pub nano: String,
+}
+
+impl Default for Observers {
+    fn default() -> Self {
+        Self {
+            main: P2POOL_OBSERVER_MAIN.to_string(),
+            mini: P2POOL_OBSERVER_MINI.to_string(),
+            nano: P2POOL_OBSERVER_NANO.to_string(),
+        }
+    }
+}
+
+impl Observers {
+    pub fn url(&self, chain: &P2poolChain) -> &str {
+        match chain {
+            P2poolChain::Main => &self.main,
+            P2poolChain::Mini => &self.mini,
+            P2poolChain::Nano => &self.nano,
+        }
+    }
 }
 
 // compatible for P2Pool and Xmrig/Proxy
@@ -583,9 +646,116 @@ pub struct Xvb {
     pub p2pool_buffer: i8,
     pub use_p2pool_sidechain_hr: bool,
     pub console_height: u32,
-    // user can choose a pool manually
+    // user can choose a pool manually, by its label
     pub manual_pool_enabled: bool,
-    pub manual_pool_eu: bool,
+    pub manual_pool: String,
+    // how the hashrate is split between P2Pool and XvB
+    pub split: XvbSplit,
+    // pools of XvB or compatible with it, the fastest is used
+    pub pools: Vec<XvbPoolEndpoint>,
+    // addresses registered on XvB, the first one is the XvB user. The P2Pool address if empty.
+    pub accounts: Vec<XvbAccount>,
+    // shared file or URL where the instances of Gupax donating to the same registration report their hashrate
+    pub siblings: String,
+    // name of this instance in the reports, the host name if empty
+    pub sibling_name: String,
+    // one instance runs the algorithm for every instance mining to the same P2Pool address
+    pub coordination: XvbCoordination,
+    // address the leader listens on, or address of the leader for a follower
+    pub coordination_address: String,
+    // key shared by the leader and its followers
+    pub coordination_key: String,
+    // what the goal mode donates toward
+    pub goal: XvbGoal,
+    pub goal_round: ManualDonationLevel,
+    // % of the hashrate donated on average over 24h
+    pub goal_percent: f32,
+    // % of the hashrate never exceeded by the goal mode
+    pub goal_cap: f32,
+    // URL the address is sent to by the Register button of the XvB tab
+    pub registration_endpoint: String,
+}
+
+impl Xvb {
+    // Pools to ping, only the one chosen by the user if it still exists.
+    pub fn pools_to_ping(&self) -> Vec<XvbPoolEndpoint> {
+        let manual = self
+            .pools
+            .iter()
+            .filter(|p| p.label == self.manual_pool)
+            .cloned()
+            .collect::<Vec<_>>();
+        if self.manual_pool_enabled && !manual.is_empty() {
+            manual
+        } else {
+            self.pools.clone()
+        }
+    }
+    // Accounts to get the private stats of, the P2Pool address if none is set.
+    pub fn accounts(&self, p2pool_address: &str) -> Vec<XvbAccount> {
+        let accounts = self
+            .accounts
+            .iter()
+            .filter(|a| !a.address.is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        if accounts.is_empty() {
+            vec![XvbAccount {
+                address: p2pool_address.to_string(),
+                token: String::new(),
+            }]
+        } else {
+            accounts
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize, Default)]
+pub struct XvbAccount {
+    pub address: String,
+    // token of the registration, sent with the requests of the private stats if not empty
+    #[serde(default)]
+    pub token: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct XvbPoolEndpoint {
+    // region of the pool, shown to the user
+    pub label: String,
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl Default for XvbPoolEndpoint {
+    fn default() -> Self {
+        Self::europe()
+    }
+}
+
+impl XvbPoolEndpoint {
+    pub fn europe() -> Self {
+        Self {
+            label: "Europe".to_string(),
+            host: XVB_NODE_EU.to_string(),
+            port: XVB_NODE_PORT,
+            tls: true,
+        }
+    }
+
+    pub fn north_america() -> Self {
+        Self {
+            label: "North America".to_string(),
+            host: XVB_NODE_NA.to_string(),
+            port: XVB_NODE_PORT,
+            tls: true,
+        }
+    }
+
+    // "host:port" as written by XMRig
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize, Default, EnumCount, EnumIter)]
@@ -596,6 +766,8 @@ pub enum XvbMode {
     ManualXvb,
     ManualP2pool,
     ManualDonationLevel,
+    Predictive,
+    Goal,
 }
 
 impl Display for XvbMode {
@@ -606,6 +778,71 @@ impl Display for XvbMode {
             Self::ManualXvb => "Manual Xvb",
             Self::ManualP2pool => "Manual P2pool",
             Self::ManualDonationLevel => "Manual Donation Level",
+            Self::Predictive => "Predictive",
+            Self::Goal => "Goal",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize, Default, EnumIter)]
+pub enum XvbGoal {
+    // reach a round, downgraded to the highest round allowed by the cap
+    #[default]
+    Round,
+    // donate a % of the hashrate, averaged over the last 24h
+    DailyPercent,
+}
+
+impl Display for XvbGoal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Round => "Round",
+            Self::DailyPercent => "Daily %",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize, Default, EnumIter)]
+pub enum XvbSplit {
+    // the whole hashrate switches between P2Pool and XvB during the cycle
+    #[default]
+    Time,
+    // a second XMRig mines on XvB with some of the CPU threads
+    Threads,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize, Default, EnumIter)]
+pub enum XvbCoordination {
+    // the algorithm only knows the other instances as external hashrate
+    #[default]
+    Alone,
+    // the algorithm runs with the hashrate of the followers and assigns them what to send to XvB
+    Leader,
+    // reports its hashrate to the leader and sends what it is assigned
+    Follower,
+}
+
+impl Display for XvbCoordination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Alone => "Alone",
+            Self::Leader => "Leader",
+            Self::Follower => "Follower",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+impl Display for XvbSplit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Time => "Time",
+            Self::Threads => "Threads",
         };
 
         write!(f, "{text}")
@@ -686,6 +923,21 @@ impl Default for Status {
             manual_hash: false,
             hashrate: 1.0,
             hash_metric: Hash::default(),
+            encrypted_view_key: String::new(),
+            price_source: String::new(),
+            calculator: Calculator::default(),
+        }
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self {
+            chain: P2poolChain::Mini,
+            sidechain_difficulty: 0,
+            power: 0.0,
+            kwh_price: 0.0,
+            xmr_price: 0.0,
         }
     }
 }
@@ -712,6 +964,7 @@ impl Default for Gupax {
             show_processes: vec![ProcessName::P2pool, ProcessName::Xmrig],
             notifications: Notification::iter().collect(),
             theme: GupaxTheme::default(),
+            network: Network::default(),
         }
     }
 }
@@ -743,19 +996,40 @@ impl Default for P2pool {
                 zmq_rig: "18083".to_string(),
             },
             prefer_local_node: true,
+            auto_failover: false,
             console_height: APP_DEFAULT_CONSOLE_HEIGHT,
             crawl_settings: CrawlerRequirements::default(),
+            observers: Observers::default(),
         }
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize, Display)]
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize, Serialize, Display, EnumString)]
 pub enum P2poolChain {
     Main,
     Mini,
     Nano,
 }
 
+impl P2poolChain {
+    // Target time between two blocks of the sidechain.
+    pub fn seconds_per_block(&self) -> u64 {
+        match self {
+            Self::Main => SECOND_PER_BLOCK_P2POOL_MAIN,
+            Self::Mini => SECOND_PER_BLOCK_P2POOL_MINI,
+            Self::Nano => SECOND_PER_BLOCK_P2POOL_NANO,
+        }
+    }
+    // Length of the PPLNS window when P2Pool did not tell it yet.
+    pub fn pplns_window_blocks(&self) -> u64 {
+        match self {
+            Self::Main => BLOCK_PPLNS_WINDOW_MAIN_MAX,
+            Self::Mini => BLOCK_PPLNS_WINDOW_MINI,
+            Self::Nano => BLOCK_PPLNS_WINDOW_NANO,
+        }
+    }
+}
+
 impl Xmrig {
     fn with_threads(max_threads: u16, current_threads: u16) -> Self {
         let xmrig = Self::default();
@@ -815,7 +1089,20 @@ impl Default for Xvb {
             use_p2pool_sidechain_hr: false,
             console_height: APP_DEFAULT_CONSOLE_HEIGHT,
             manual_pool_enabled: false,
-            manual_pool_eu: true,
+            manual_pool: XvbPoolEndpoint::europe().label,
+            split: Default::default(),
+            pools: vec![XvbPoolEndpoint::europe(), XvbPoolEndpoint::north_america()],
+            accounts: Vec::new(),
+            siblings: String::new(),
+            sibling_name: String::new(),
+            coordination: Default::default(),
+            coordination_address: format!("127.0.0.1:{XVB_COORDINATION_PORT}"),
+            coordination_key: String::new(),
+            goal: Default::default(),
+            goal_round: Default::default(),
+            goal_percent: 10.0,
+            goal_cap: 100.0,
+            registration_endpoint: XVB_URL_REGISTER.to_string(),
         }
     }
 }
@@ -835,16 +1122,18 @@ impl Node {
     pub const fn process_name() -> ProcessName {
         ProcessName::Node
     }
-    pub fn start_options(&self, mode: StartOptionsMode) -> String {
-        Helper::build_node_args(self, mode).join(" ")
+    pub fn start_options(&self, mode: StartOptionsMode, network: Network) -> String {
+        Helper::build_node_args(self, mode, network).join(" ")
     }
     /// Return rpc port, zmq port from state
-    pub fn ports(&self) -> (u16, u16) {
-        let mut zmq_port = NODE_ZMQ_PORT_DEFAULT;
-        let mut rpc_port = NODE_RPC_PORT_DEFAULT;
+    pub fn ports(&self, network: Network) -> (u16, u16) {
+        // default ports of monerod on this network
+        let zmq_default = network.port(NODE_ZMQ_PORT_DEFAULT);
+        let rpc_default = network.port(NODE_RPC_PORT_DEFAULT);
+        let mut zmq_port = zmq_default;
+        let mut rpc_port = rpc_default;
         if self.simple {
-            zmq_port = NODE_ZMQ_PORT_DEFAULT;
-            rpc_port = NODE_RPC_PORT_DEFAULT;
+            // monerod uses the default ports
         } else if !self.arguments.is_empty() {
             // This parses the input and attempts to fill out
             // the [ImgXmrig]... This is pretty bad code...
@@ -852,38 +1141,38 @@ impl Node {
             for arg in self.arguments.split_whitespace() {
                 match last {
                     "--zmq-pub" => {
-                        zmq_port = last
+                        zmq_port = arg
                             .split(":")
                             .last()
-                            .unwrap_or(&NODE_ZMQ_PORT_DEFAULT.to_string())
+                            .unwrap_or(&zmq_default.to_string())
                             .parse()
-                            .unwrap_or(NODE_ZMQ_PORT_DEFAULT);
+                            .unwrap_or(zmq_default);
                     }
-                    "--rpc-bind-port" => zmq_port = last.parse().unwrap_or(NODE_RPC_PORT_DEFAULT),
+                    "--rpc-bind-port" => rpc_port = arg.parse().unwrap_or(rpc_default),
                     _ => (),
                 }
                 last = arg;
             }
         } else {
             zmq_port = if self.api_port.is_empty() {
-                NODE_ZMQ_PORT_DEFAULT
+                zmq_default
             } else {
-                self.zmq_port.parse().unwrap_or(NODE_ZMQ_PORT_DEFAULT)
+                self.zmq_port.parse().unwrap_or(zmq_default)
             };
             rpc_port = if self.api_port.is_empty() {
-                NODE_RPC_PORT_DEFAULT
+                rpc_default
             } else {
-                self.api_port.parse().unwrap_or(NODE_RPC_PORT_DEFAULT)
+                self.api_port.parse().unwrap_or(rpc_default)
             };
         }
         (rpc_port, zmq_port)
     }
     /// get the ports that the node process is currently using or that it will use if started with current settings
-    pub fn current_ports(&self, alive: bool, img_node: &ImgNode) -> (u16, u16) {
+    pub fn current_ports(&self, alive: bool, img_node: &ImgNode, network: Network) -> (u16, u16) {
         if alive {
             (img_node.rpc_port, img_node.zmq_port)
         } else {
-            self.ports()
+            self.ports(network)
         }
     }
 }
@@ -910,6 +1199,15 @@ impl P2pool {
         )
         .join(" ")
     }
+    /// get the chain that the p2pool process would use if it were started with the current settings
+    pub fn current_chain(&self) -> P2poolChain {
+        // the simple mode always starts P2Pool Nano
+        if self.submenu == SubmenuP2pool::Advanced {
+            self.chain.clone()
+        } else {
+            P2poolChain::Nano
+        }
+    }
     /// get the port that the p2pool process would use for stratum if it were using the current settings
     pub fn stratum_port(&self) -> u16 {
         // If in the crawler tab, it means the use does not use advanced tab because he won't set a custom node.
@@ -56,6 +56,7 @@ mod test {
             show_processes = ["Node", "P2pool", "Xmrig", "XmrigProxy", "Xvb"]
             notifications = ["Payout", "FirstP2poolShare", "FailedService", "DisconnectedMiner"]
             theme = "Dark"
+            network = "Mainnet"
 
 			[gupax.auto]
             update = false
@@ -72,6 +73,15 @@ mod test {
 			manual_hash = false
 			hashrate = 1241.23
 			hash_metric = "Hash"
+			encrypted_view_key = ""
+			price_source = ""
+
+			[status.calculator]
+			chain = "Mini"
+			sidechain_difficulty = 0
+			power = 65.0
+			kwh_price = 0.25
+			xmr_price = 150.0
 			
             [p2pool]
             submenu = "Simple"
@@ -90,6 +100,7 @@ mod test {
             zmq = "18083"
             stratum_port = 3333
             prefer_local_node = true
+            auto_failover = true
             console_height = 360
 
             [p2pool.crawl_settings]
@@ -101,6 +112,10 @@ mod test {
             zmq_ports = [18083, 18084]
             timeout = 10
 
+            [p2pool.observers]
+            main = "https://p2pool.observer"
+            mini = "https://mini.p2pool.observer"
+            nano = "https://nano.p2pool.observer"
 
             [p2pool.selected_remote_node]              
             ip = "37.187.74.171"                       
@@ -185,7 +200,25 @@ mod test {
             use_p2pool_sidechain_hr = false
             console_height = 360
             manual_pool_enabled = false
-            manual_pool_eu = true
+            manual_pool = "Europe"
+            split = "Time"
+            pools = [
+                { label = "Europe", host = "eu.xmrvsbeast.com", port = 4247, tls = true },
+                { label = "North America", host = "na.xmrvsbeast.com", port = 4247, tls = true },
+            ]
+            accounts = [
+                { address = "44hintoFpuo3ugKfcqJvh5BmrsTRpnTasJmetKC4VXCt6QDtbHVuixdTtsm6Ptp7Y8haXnJ6j8Gj2dra8CKy5ewz7Vi9CYW", token = "" },
+            ]
+            siblings = ""
+            sibling_name = ""
+            coordination = "Alone"
+            coordination_address = "127.0.0.1:18095"
+            coordination_key = ""
+            goal = "Round"
+            goal_round = "DonorWhale"
+            goal_percent = 10.0
+            goal_cap = 40.0
+            registration_endpoint = "https://xmrvsbeast.com/cgi-bin/p2pool_register_gupaxx_api.cgi"
 
             [node]
             simple = false
@@ -342,6 +375,29 @@ mod test {
         assert!(merged_state.contains("backup_host = true"));
     }
 
+    #[test]
+    fn read_old_state_manual_pool() {
+        // [state.toml] of a version choosing the XvB pool with [manual_pool_eu]
+        let old = r#"
+            [xvb]
+            simple = false
+            manual_pool_enabled = true
+            manual_pool_eu = false
+        "#;
+        let path =
+            std::env::temp_dir().join(format!("gupax_test_old_state_{}.toml", std::process::id()));
+        std::fs::write(&path, old).unwrap();
+        let state = State::get(&path).unwrap();
+        assert!(!state.xvb.simple);
+        assert!(state.xvb.manual_pool_enabled);
+        assert_eq!(state.xvb.manual_pool, "North America");
+        // the migrated state is saved, it is read the same way the next time
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("manual_pool_eu"));
+        assert_eq!(State::get(&path).unwrap().xvb.manual_pool, "North America");
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn create_and_serde_gupax_p2pool_api() {
         use crate::disk::gupax_p2pool_api::GupaxP2poolApi;
@@ -407,4 +463,106 @@ mod test {
         assert_eq!(Hash::convert(hash, Hash::Giga, Hash::Mega), 1_000.0);
         assert_eq!(Hash::convert(hash, Hash::Giga, Hash::Giga), 1.0);
     }
+    #[test]
+    fn create_and_serde_known_nodes() {
+        use crate::components::node::{NodeHealth, RemoteNode};
+        use crate::disk::known_nodes::{
+            KNOWN_NODES_BAD_COOLDOWN_SECS, KNOWN_NODES_RELIABLE_SUCCESSES, KnownNodes,
+        };
+        use crate::disk::node::Node;
+        use crate::utils::monero_address::Network;
+
+        let node = |ip: &str, ms| RemoteNode {
+            ip: ip.parse().unwrap(),
+            rpc: 18089,
+            zmq: 18084,
+            ms,
+            health: Some(NodeHealth {
+                height: 3000000,
+                synchronized: true,
+                nettype: "mainnet".to_string(),
+                restricted: true,
+                lag: 0,
+            }),
+        };
+        let (fast, stable, bad) = (
+            node("1.1.1.1", 20),
+            node("2.2.2.2", 80),
+            node("3.3.3.3", 10),
+        );
+        let mut known_nodes = KnownNodes::default();
+        for i in 0..KNOWN_NODES_RELIABLE_SUCCESSES as u64 {
+            known_nodes.record_success(&fast, i);
+            known_nodes.record_success(&stable, i);
+            known_nodes.record_failure(&bad, i);
+        }
+        known_nodes.record_p2pool_uptime(
+            &Node {
+                ip: "2.2.2.2".to_string(),
+                rpc: "18089".to_string(),
+                zmq: "18084".to_string(),
+            },
+            3600,
+            10,
+        );
+        // local nodes are not recorded
+        known_nodes.record_p2pool_uptime(
+            &Node {
+                ip: "127.0.0.1".to_string(),
+                rpc: "18081".to_string(),
+                zmq: "18083".to_string(),
+            },
+            3600,
+            10,
+        );
+        assert_eq!(known_nodes.nodes.len(), 3);
+        assert!(known_nodes.is_bad(&bad, 10));
+        assert!(!known_nodes.is_bad(&fast, 10));
+        assert!(known_nodes.bonus(&stable) > known_nodes.bonus(&fast));
+        // the node P2Pool stayed connected to comes first, the bad one is excluded
+        let reliable = known_nodes.reliable_nodes(10, Network::Mainnet);
+        assert_eq!(*reliable, vec![stable.clone(), fast.clone()]);
+        assert_eq!(reliable[0].ms, 80);
+        // nodes of another network are never reused
+        assert!(known_nodes.reliable_nodes(10, Network::Stagenet).is_empty());
+
+        // save and read back
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "gupax_test_known_nodes_{}.toml",
+            std::process::id()
+        ));
+        known_nodes.path = path.clone();
+        known_nodes.save().unwrap();
+        let read = KnownNodes::get(&path);
+        std::fs::remove_file(&path).unwrap();
+        // [get] forgets the nodes not seen for a long time, timestamps used here are from 1970.
+        assert!(read.nodes.is_empty());
+        let read: KnownNodes =
+            toml::de::from_str(&toml::ser::to_string(&known_nodes).unwrap()).unwrap();
+        assert_eq!(read.nodes, known_nodes.nodes);
+        known_nodes.prune(10);
+        assert_eq!(known_nodes.nodes.len(), 3);
+
+        // the bad node is checked again after the cooldown and recovers with a success
+        let later = 2 + KNOWN_NODES_BAD_COOLDOWN_SECS;
+        assert!(!known_nodes.is_bad(&bad, later));
+        known_nodes.record_success(&bad, later);
+        known_nodes.record_failure(&bad, later + 1);
+        assert!(!known_nodes.is_bad(&bad, later + 1));
+    }
+
+    #[test]
+    fn node_ports_from_arguments() {
+        use crate::utils::monero_address::Network;
+        let mut node = crate::disk::state::Node {
+            simple: false,
+            arguments: "--rpc-bind-port 18091 --zmq-pub tcp://127.0.0.1:18093".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(node.ports(Network::Mainnet), (18091, 18093));
+        // the missing one keeps the default port of the network
+        node.arguments = "--rpc-bind-port 38091".to_string();
+        assert_eq!(node.ports(Network::Stagenet), (38091, 38083));
+    }
 }
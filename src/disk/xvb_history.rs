@@ -0,0 +1,321 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::helper::xvb::{priv_stats::XvbPrivStats, public_stats::XvbPubStats, rounds::XvbRound};
+use strum::IntoEnumIterator;
+
+//---------------------------------------------------------------------------------------------------- Const
+const HOUR: i64 = 3600;
+// a month of hours
+pub const XVB_HISTORY_MAX_HOURS: usize = 24 * 30;
+pub const XVB_HISTORY_MAX_RAFFLES: usize = 500;
+
+//---------------------------------------------------------------------------------------------------- [XvbHistory]
+// Participation in the XvB rounds, saved in [xvb_history.toml].
+// The XvB process records every hour the round it detected with the averages of the private API
+// and the time it mined on XvB, and every raffle result given by the public API.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct XvbHistory {
+    #[serde(skip)]
+    pub path: PathBuf,
+    // hour being measured, recorded once it is over
+    #[serde(skip)]
+    pub current: Option<XvbHour>,
+    #[serde(default)]
+    pub hours: Vec<XvbHour>,
+    #[serde(default)]
+    pub raffles: Vec<XvbRaffle>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct XvbHour {
+    // unix timestamp of the start of the hour
+    pub timestamp: i64,
+    // None if there was no share in the PPLNS window
+    pub round: Option<XvbRound>,
+    // kH/s
+    pub donor_1hr_avg: f32,
+    pub donor_24hr_avg: f32,
+    // seconds mined on XvB during the hour
+    pub donated: u64,
+    pub win_current: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct XvbRaffle {
+    // unix timestamp of when Gupax saw the result
+    pub timestamp: i64,
+    pub block_height: u64,
+    pub round_type: XvbRound,
+    // head and tail of the address of the winner
+    pub winner: String,
+    pub roll_winner: u64,
+    pub roll_round: u64,
+    pub block_reward: String,
+    pub won: bool,
+}
+
+// Totals of the recorded hours.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XvbTotals {
+    pub hours: usize,
+    // hours spent in each round, in the order of [XvbRound::iter]
+    pub rounds: Vec<(XvbRound, usize)>,
+    pub donated: u64,
+    pub raffles_won: usize,
+}
+
+impl XvbHistory {
+    pub fn get(path: &Path) -> Self {
+        let mut history = match read_to_string(File::XvbHistory, &path.to_path_buf()) {
+            Ok(string) => match toml::de::from_str::<Self>(&string) {
+                Ok(history) => {
+                    info!("XvbHistory | Parse ... OK");
+                    history
+                }
+                Err(e) => {
+                    warn!("XvbHistory | Parse ... FAIL ... {e}, starting with an empty history");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        };
+        history.path = path.to_path_buf();
+        history
+    }
+
+    pub fn save(&self) -> Result<(), TomlError> {
+        let string = match toml::ser::to_string(self) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("XvbHistory | Couldn't serialize: {e}");
+                return Err(TomlError::Serialize(e));
+            }
+        };
+        match fs::write(&self.path, string) {
+            Ok(_) => {
+                info!("XvbHistory | Save ... OK");
+                Ok(())
+            }
+            Err(e) => {
+                error!("XvbHistory | Couldn't overwrite [{}]", self.path.display());
+                Err(TomlError::Io(e))
+            }
+        }
+    }
+
+    //---------------------------------------------------------------------------------------------------- Mutate
+    // Account the seconds [donated] to XvB in the current hour, with the last private stats.
+    // Returns true if an hour was recorded.
+    pub fn tick(&mut self, now: i64, donated: u64, stats: &XvbPrivStats) -> bool {
+        let start = now - now.rem_euclid(HOUR);
+        let mut recorded = false;
+        if let Some(current) = self.current.take_if(|c| c.timestamp != start) {
+            self.hours.push(current);
+            if self.hours.len() > XVB_HISTORY_MAX_HOURS {
+                self.hours.remove(0);
+            }
+            recorded = true;
+        }
+        let current = self.current.get_or_insert(XvbHour {
+            timestamp: start,
+            round: None,
+            donor_1hr_avg: 0.0,
+            donor_24hr_avg: 0.0,
+            donated: 0,
+            win_current: false,
+        });
+        current.donated += donated;
+        current.round.clone_from(&stats.round_participate);
+        current.donor_1hr_avg = stats.donor_1hr_avg;
+        current.donor_24hr_avg = stats.donor_24hr_avg;
+        current.win_current = stats.win_current;
+        recorded
+    }
+
+    // Record the result of the last raffle if it is a new one.
+    // [addresses] are the addresses of the user, compared to the head and tail of the winner given by XvB.
+    pub fn record_raffle(&mut self, now: i64, stats: &XvbPubStats, addresses: &[String]) -> bool {
+        if stats.block_height == 0
+            || stats.winner.is_empty()
+            || self
+                .raffles
+                .iter()
+                .any(|r| r.block_height == stats.block_height)
+        {
+            return false;
+        }
+        self.raffles.push(XvbRaffle {
+            timestamp: now,
+            block_height: stats.block_height,
+            round_type: stats.round_type.clone(),
+            winner: stats.winner.clone(),
+            roll_winner: stats.roll_winner,
+            roll_round: stats.roll_round,
+            block_reward: stats.block_reward.clone(),
+            won: addresses.iter().any(|a| stats.won_by(a)),
+        });
+        if self.raffles.len() > XVB_HISTORY_MAX_RAFFLES {
+            self.raffles.remove(0);
+        }
+        true
+    }
+
+    //---------------------------------------------------------------------------------------------------- Totals
+    pub fn totals(&self) -> XvbTotals {
+        let rounds = XvbRound::iter()
+            .map(|round| {
+                let hours = self
+                    .hours
+                    .iter()
+                    .filter(|h| h.round.as_ref() == Some(&round))
+                    .count();
+                (round, hours)
+            })
+            .collect();
+        XvbTotals {
+            hours: self.hours.len(),
+            rounds,
+            donated: self.hours.iter().map(|h| h.donated).sum(),
+            raffles_won: self.raffles.iter().filter(|r| r.won).count(),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(round: Option<XvbRound>, donor_1hr_avg: f32) -> XvbPrivStats {
+        XvbPrivStats {
+            round_participate: round,
+            donor_1hr_avg,
+            donor_24hr_avg: 1.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_hours() {
+        let mut history = XvbHistory::default();
+        // 10:59:00, a minute of which 30 seconds on XvB
+        let start = 1_700_000_000 - 1_700_000_000 % HOUR + 10 * HOUR;
+        assert!(!history.tick(start + 59 * 60, 30, &stats(None, 0.0)));
+        assert!(!history.tick(start + 59 * 60 + 30, 0, &stats(None, 0.0)));
+        assert!(history.hours.is_empty());
+        // 11:00:10, the hour of 10h is recorded with its last stats
+        assert!(history.tick(start + HOUR + 10, 10, &stats(Some(XvbRound::Donor), 1.2)));
+        assert_eq!(
+            history.hours,
+            vec![XvbHour {
+                timestamp: start,
+                round: None,
+                donor_1hr_avg: 0.0,
+                donor_24hr_avg: 1.5,
+                donated: 30,
+                win_current: false,
+            }]
+        );
+        let current = history.current.as_ref().unwrap();
+        assert_eq!(current.timestamp, start + HOUR);
+        assert_eq!(current.donated, 10);
+        assert_eq!(current.round, Some(XvbRound::Donor));
+        // a month at most
+        for hour in 0..XVB_HISTORY_MAX_HOURS as i64 + 5 {
+            history.tick(start + (hour + 2) * HOUR, 1, &stats(None, 0.0));
+        }
+        assert_eq!(history.hours.len(), XVB_HISTORY_MAX_HOURS);
+        assert_eq!(history.hours[0].timestamp, start + 6 * HOUR);
+    }
+
+    #[test]
+    fn record_raffles() {
+        let addresses = ["44hintoFpuo3ugKfcqJvh5BmrsTRpnTasJmetKC4VXCt6QDtbHVuixdTtsm6Ptp7Y8haXnJ6j8Gj2dra8CKy5ewz7Vi9CYW".to_string()];
+        let mut history = XvbHistory::default();
+        // winner as given by XvB
+        let mut stats = XvbPubStats {
+            winner: "44hintoF...z7Vi9CYW".to_string(),
+            round_type: XvbRound::DonorVip,
+            block_height: 3_000_000,
+            roll_winner: 12,
+            roll_round: 40,
+            block_reward: "0.6".to_string(),
+            ..Default::default()
+        };
+        assert!(history.record_raffle(1_700_000_000, &stats, &addresses));
+        // the same raffle is given until the next one
+        assert!(!history.record_raffle(1_700_000_060, &stats, &addresses));
+        stats.block_height = 3_000_100;
+        stats.winner = "48WV1ZP3...nzH9iWsL".to_string();
+        assert!(history.record_raffle(1_700_012_000, &stats, &addresses));
+        // no raffle yet
+        assert!(!history.record_raffle(1_700_012_000, &XvbPubStats::default(), &addresses));
+        assert_eq!(history.raffles.len(), 2);
+        assert!(history.raffles[0].won);
+        assert!(!history.raffles[1].won);
+        assert_eq!(history.raffles[0].round_type, XvbRound::DonorVip);
+        // the separator and the spaces are not compared
+        for winner in [
+            " 44hintoF..z7Vi9CYW ",
+            "44hintoF…z7Vi9CYW",
+            "44hintoF ... z7Vi9CYW",
+        ] {
+            stats.winner = winner.to_string();
+            assert!(stats.won_by(&addresses[0]), "{winner}");
+        }
+        for winner in ["", "...", "44hintoF...", "44hintoF...z7Vi9CYX", "44hintoF"] {
+            stats.winner = winner.to_string();
+            assert!(!stats.won_by(&addresses[0]), "{winner}");
+        }
+    }
+
+    #[test]
+    fn history_totals_and_disk() {
+        let dir = std::env::temp_dir().join(format!("gupax_xvb_history_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(XVB_HISTORY_TOML);
+        let mut history = XvbHistory::get(&path);
+        assert!(history.hours.is_empty());
+        let start = 1_700_000_000 - 1_700_000_000 % HOUR;
+        for (hour, round) in [
+            Some(XvbRound::Donor),
+            Some(XvbRound::Donor),
+            Some(XvbRound::Vip),
+            None,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            history.tick(start + hour as i64 * HOUR, 600, &stats(round, 1.0));
+        }
+        history.tick(start + 4 * HOUR, 0, &stats(None, 0.0));
+        let totals = history.totals();
+        assert_eq!(totals.hours, 4);
+        assert_eq!(totals.donated, 2400);
+        assert!(totals.rounds.contains(&(XvbRound::Donor, 2)));
+        assert!(totals.rounds.contains(&(XvbRound::Vip, 1)));
+        assert!(totals.rounds.contains(&(XvbRound::DonorMega, 0)));
+        history.save().unwrap();
+        let from_disk = XvbHistory::get(&path);
+        assert_eq!(from_disk.hours, history.hours);
+        // the hour being measured is not saved
+        assert_eq!(from_disk.current, None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,377 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Advisor of the P2Pool chain fitting the hashrate of the user.
+// The difficulty of every chain is fetched from the observers, the one of the running chain is taken from P2Pool.
+// Each chain is rated by the variation of the earnings it gives to the hashrate (see [utils::earnings]):
+// a small miner on Main waits days for a share, a large miner on Nano waits weeks for a block.
+
+use crate::{
+    disk::state::{Observers, P2poolChain},
+    utils::{
+        earnings::{DAY, Earnings, EarningsInput, MONTH},
+        xmr::AtomicUnit,
+    },
+};
+use enclose::enc;
+use log::{info, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_this_or_that::as_u64;
+use std::{
+    sync::{Arc, Mutex},
+    thread::spawn,
+    time::Duration,
+};
+
+//---------------------------------------------------------------------------------------------------- Const
+const TIMEOUT_OBSERVER: u64 = 10;
+// The current chain is worth a warning when its variation is this many times the one of the recommended chain.
+pub const ADVISOR_WARNING_RATIO: f64 = 1.5;
+
+//---------------------------------------------------------------------------------------------------- `/api/pool_info`
+// Only the difficulties are needed from the observer.
+#[derive(Debug, Deserialize)]
+pub struct PoolInfo {
+    pub sidechain: PoolInfoSidechain,
+    pub mainchain: PoolInfoMainchain,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PoolInfoSidechain {
+    #[serde(deserialize_with = "as_u64")]
+    pub difficulty: u64,
+    #[serde(default)]
+    pub window_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PoolInfoMainchain {
+    #[serde(deserialize_with = "as_u64")]
+    pub difficulty: u64,
+    #[serde(deserialize_with = "as_u64")]
+    pub reward: u64,
+}
+
+impl PoolInfo {
+    pub async fn request(client: &Client, observer: &str) -> anyhow::Result<Self> {
+        Ok(client
+            .get(format!("{}/api/pool_info", observer.trim_end_matches('/')))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Self>()
+            .await?)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Advice
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStats {
+    pub chain: P2poolChain,
+    pub difficulty: u64,
+    pub window_length_blocks: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainAdvice {
+    pub chain: P2poolChain,
+    pub earnings: Earnings,
+    // relative standard deviation of the monthly earnings
+    pub variation: Option<f64>,
+}
+
+// Rate every chain for the hashrate, in the order of [stats].
+pub fn advise(
+    hashrate: u64,
+    monero_difficulty: u64,
+    block_reward: AtomicUnit,
+    stats: &[ChainStats],
+) -> Vec<ChainAdvice> {
+    stats
+        .iter()
+        .map(|stats| {
+            let earnings = Earnings::new(&EarningsInput {
+                hashrate,
+                monero_difficulty,
+                block_reward,
+                chain: stats.chain.clone(),
+                sidechain_difficulty: stats.difficulty,
+                window_length_blocks: stats.window_length_blocks,
+            });
+            ChainAdvice {
+                chain: stats.chain.clone(),
+                earnings,
+                variation: earnings.variation(MONTH),
+            }
+        })
+        .collect()
+}
+
+// The chain giving the steadiest earnings.
+pub fn recommend(advices: &[ChainAdvice]) -> Option<&ChainAdvice> {
+    advices
+        .iter()
+        .filter(|a| a.variation.is_some())
+        .min_by(|a, b| a.variation.unwrap().total_cmp(&b.variation.unwrap()))
+}
+
+// Warning if the chain [current] is unsuitable for the hashrate.
+pub fn warning(current: &P2poolChain, advices: &[ChainAdvice]) -> Option<String> {
+    let best = recommend(advices)?;
+    let current = advices.iter().find(|a| &a.chain == current)?;
+    let (current_variation, best_variation) = (current.variation?, best.variation?);
+    if current_variation < best_variation * ADVISOR_WARNING_RATIO {
+        return None;
+    }
+    let mut warning = format!(
+        "Your monthly earnings on P2Pool {} vary by ±{:.0}%, against ±{:.0}% on P2Pool {}.",
+        current.chain,
+        current_variation * 100.0,
+        best_variation * 100.0,
+        best.chain
+    );
+    if current.earnings.window_shares > best.earnings.window_shares {
+        warning.push_str(&format!(
+            " This chain finds a block every {} days, your hashrate is too large for it.",
+            (current.earnings.block_mean? / DAY).round()
+        ));
+    } else {
+        warning.push_str(&format!(
+            " You expect {:.2} shares in the PPLNS window of this chain, your hashrate is too small for it.",
+            current.earnings.window_shares
+        ));
+    }
+    Some(warning)
+}
+
+//---------------------------------------------------------------------------------------------------- ChainAdvisor
+pub struct ChainAdvisor {
+    pub fetching: bool,
+    pub msg: String,
+    // difficulties fetched from the observers
+    pub stats: Vec<ChainStats>,
+    pub monero_difficulty: u64,
+    pub block_reward: AtomicUnit,
+}
+
+impl Default for ChainAdvisor {
+    fn default() -> Self {
+        Self {
+            fetching: false,
+            msg: "The difficulties of the other chains are not fetched".to_string(),
+            stats: vec![],
+            monero_difficulty: 0,
+            block_reward: AtomicUnit::new(),
+        }
+    }
+}
+
+impl ChainAdvisor {
+    pub fn start(advisor: &Arc<Mutex<Self>>, observers: Observers) {
+        let mut lock = advisor.lock().unwrap();
+        if lock.fetching {
+            return;
+        }
+        lock.fetching = true;
+        lock.msg = "Fetching the difficulties from the observers...".to_string();
+        drop(lock);
+        info!("Spawning chain advisor thread...");
+        spawn(enc!((advisor) move || {
+            Self::fetch(&advisor, &observers);
+            advisor.lock().unwrap().fetching = false;
+        }));
+    }
+
+    #[tokio::main]
+    pub async fn fetch(advisor: &Arc<Mutex<Self>>, observers: &Observers) {
+        let client = match Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_OBSERVER))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                advisor.lock().unwrap().msg = format!("Fetch failed: {e}");
+                return;
+            }
+        };
+        let mut stats = vec![];
+        let mut failed = vec![];
+        let mut mainchain = None;
+        for chain in [P2poolChain::Main, P2poolChain::Mini, P2poolChain::Nano] {
+            let observer = observers.url(&chain);
+            match PoolInfo::request(&client, observer).await {
+                Ok(info) => {
+                    info!("Chain Advisor | P2Pool {chain} from {observer} ... OK");
+                    let window_length_blocks = if info.sidechain.window_size > 0 {
+                        info.sidechain.window_size
+                    } else {
+                        chain.pplns_window_blocks()
+                    };
+                    stats.push(ChainStats {
+                        chain,
+                        difficulty: info.sidechain.difficulty,
+                        window_length_blocks,
+                    });
+                    mainchain.get_or_insert(info.mainchain);
+                }
+                Err(e) => {
+                    warn!("Chain Advisor | P2Pool {chain} from {observer} ... FAIL ... {e}");
+                    failed.push(chain.to_string());
+                }
+            }
+        }
+        let mut lock = advisor.lock().unwrap();
+        lock.msg = if failed.is_empty() {
+            "Difficulties fetched from the observers".to_string()
+        } else {
+            format!("Could not fetch P2Pool {}", failed.join(", "))
+        };
+        lock.stats = stats;
+        if let Some(mainchain) = mainchain {
+            lock.monero_difficulty = mainchain.difficulty;
+            lock.block_reward = AtomicUnit::from_u64(mainchain.reward);
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::http_mock::mock_http;
+
+    fn stats() -> Vec<ChainStats> {
+        vec![
+            ChainStats {
+                chain: P2poolChain::Main,
+                difficulty: 2_000_000_000,
+                window_length_blocks: 2160,
+            },
+            ChainStats {
+                chain: P2poolChain::Mini,
+                difficulty: 30_000_000,
+                window_length_blocks: 2160,
+            },
+            ChainStats {
+                chain: P2poolChain::Nano,
+                difficulty: 3_000_000,
+                window_length_blocks: 2160,
+            },
+        ]
+    }
+
+    fn advices(hashrate: u64) -> Vec<ChainAdvice> {
+        // 3 GH/s network
+        advise(
+            hashrate,
+            360_000_000_000,
+            AtomicUnit::from_u64(600_000_000_000),
+            &stats(),
+        )
+    }
+
+    #[test]
+    fn recommend_chain() {
+        assert_eq!(
+            recommend(&advices(10_000)).unwrap().chain,
+            P2poolChain::Mini
+        );
+        assert_eq!(
+            recommend(&advices(10_000_000)).unwrap().chain,
+            P2poolChain::Main
+        );
+        // the same XMR on every chain
+        let advices = advices(10_000);
+        assert_eq!(advices.len(), 3);
+        assert!(
+            advices
+                .iter()
+                .all(|a| a.earnings.xmr_per_second == advices[0].earnings.xmr_per_second)
+        );
+        // nothing to recommend without the network difficulty
+        assert_eq!(
+            recommend(&advise(10_000, 0, AtomicUnit::new(), &stats())),
+            None
+        );
+    }
+
+    #[test]
+    fn unsuitable_chain() {
+        // a large miner on Nano
+        let msg = warning(&P2poolChain::Nano, &advices(10_000_000)).unwrap();
+        assert!(msg.contains("P2Pool Main"), "{msg}");
+        assert!(msg.contains("too large"), "{msg}");
+        // a small miner on Main
+        let msg = warning(&P2poolChain::Main, &advices(1_000)).unwrap();
+        assert!(msg.contains("P2Pool Mini"), "{msg}");
+        assert!(msg.contains("too small"), "{msg}");
+        // the recommended chain
+        assert_eq!(warning(&P2poolChain::Mini, &advices(10_000)), None);
+        // a chain not fetched
+        assert_eq!(warning(&P2poolChain::Nano, &advices(10_000)[..2]), None);
+    }
+
+    // Start a fake observer answering the pool info of every chain with [difficulty].
+    fn mock_observer(difficulty: u64) -> String {
+        let addr = mock_http(move |request| {
+            if request.method == "GET" && request.path == "/api/pool_info" {
+                (
+                    "200 OK",
+                    format!(
+                        r#"{{"sidechain":{{"id":"00","height":100,"difficulty":{difficulty},"window_size":1080}},"mainchain":{{"height":3000000,"difficulty":360000000000,"reward":600000000000}}}}"#
+                    ),
+                )
+            } else {
+                ("404 Not Found", String::new())
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn fetch_observers() {
+        let observers = Observers {
+            main: mock_observer(2_000_000_000),
+            // trailing slash of a user URL
+            mini: format!("{}/", mock_observer(30_000_000)),
+            // nothing listens there
+            nano: "http://127.0.0.1:1".to_string(),
+        };
+        let advisor = Arc::new(Mutex::new(ChainAdvisor::default()));
+        ChainAdvisor::fetch(&advisor, &observers);
+        let advisor = advisor.lock().unwrap();
+        assert_eq!(
+            advisor.stats,
+            vec![
+                ChainStats {
+                    chain: P2poolChain::Main,
+                    difficulty: 2_000_000_000,
+                    window_length_blocks: 1080,
+                },
+                ChainStats {
+                    chain: P2poolChain::Mini,
+                    difficulty: 30_000_000,
+                    window_length_blocks: 1080,
+                },
+            ]
+        );
+        assert_eq!(advisor.monero_difficulty, 360_000_000_000);
+        assert_eq!(advisor.block_reward, AtomicUnit::from_u64(600_000_000_000));
+        assert_eq!(advisor.msg, "Could not fetch P2Pool Nano");
+    }
+}
@@ -1,9 +1,19 @@
 use crate::{
     app::{BackupNodes, panels::middle::common::list_poolnode::PoolNode},
-    disk::node::Node,
+    disk::{
+        known_nodes::{self, KnownNodes},
+        node::Node,
+    },
     helper::sleep,
+    utils::{
+        ip_rules::{Cidr, PrefixTable, parse_cidr_list, read_cidr_list},
+        monero_address::Network,
+    },
 };
 use std::{
+    collections::HashSet,
+    net::IpAddr,
+    path::Path,
     sync::{
         Arc, Mutex,
         mpsc::{self, Receiver, Sender},
@@ -14,12 +24,13 @@ use std::{
 
 use enclose::enc;
 use futures::StreamExt;
-use log::info;
+use log::{error, info, warn};
 use monero_crawler_lib::{CrawlBuilder, capability_checkers::CapabilitiesChecker};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
-use crate::components::node::{RemoteNode, RemoteNodes};
+use crate::components::node::{GetInfo, RemoteNode, RemoteNodes, TIMEOUT_NODE_PING};
 
 pub struct Crawler {
     pub nodes: RemoteNodes,
@@ -29,6 +40,10 @@ pub struct Crawler {
     pub msg: String,
     pub prog: f32,
     pub handle: Option<Sender<bool>>,
+    // reputation of the nodes, shared with the pinger
+    pub known_nodes: Arc<Mutex<KnownNodes>>,
+    // network of the nodes to find, kept in sync with the state by the GUI
+    pub network: Network,
 }
 
 impl Default for Crawler {
@@ -40,6 +55,8 @@ impl Default for Crawler {
             msg: "Inactive".to_string(),
             prog: 0.0,
             handle: None,
+            known_nodes: Arc::new(Mutex::new(KnownNodes::default())),
+            network: Network::default(),
         }
     }
 }
@@ -57,12 +74,30 @@ pub struct CrawlerRequirements {
     // number of nodes that are not fast but will be saved anyway.
     // It allows the user to stop the crawl and use a medium fast node if the crawl did not find a fast node yet
     pub nb_nodes_medium: u8,
-    // rpc ports to scan until one is working
+    // rpc ports to scan until one is working, shifted for the other networks by [Network::port]
     pub rpc_ports: Vec<u16>,
-    // zmq ports to scan until one is working
+    // zmq ports to scan until one is working, shifted for the other networks by [Network::port]
     pub zmq_ports: Vec<u16>,
     // time in seconds after which the algorithm will stop
     pub timeout: u64,
+    // IP/CIDR entries separated by commas. If not empty, only nodes inside are used as backup.
+    #[serde(default)]
+    pub allowlist: String,
+    // IP/CIDR entries separated by commas, nodes inside are never used.
+    #[serde(default)]
+    pub denylist: String,
+    // path of a file listing spy nodes IP/CIDR, one entry per line.
+    #[serde(default)]
+    pub spy_nodes_path: String,
+    // do not use more than one backup node of the same ASN, or of the same /16 if the ASN is unknown.
+    #[serde(default)]
+    pub diversity: bool,
+    // path of the offline prefix table giving the ASN of the nodes, optional.
+    #[serde(default)]
+    pub prefix_table_path: String,
+    // nodes must have an unrestricted RPC
+    #[serde(default)]
+    pub require_full_rpc: bool,
 }
 
 impl Default for CrawlerRequirements {
@@ -77,13 +112,97 @@ impl Default for CrawlerRequirements {
             rpc_ports: vec![18081, 18089],
             zmq_ports: vec![18083, 18084],
             timeout: 10,
+            allowlist: String::new(),
+            denylist: String::new(),
+            spy_nodes_path: String::new(),
+            diversity: false,
+            prefix_table_path: String::new(),
+            require_full_rpc: false,
         }
     }
 }
 
+//---------------------------------------------------------------------------------------------------- NodeFilter
+// Rules of [CrawlerRequirements] deciding which nodes can be used.
+// Invalid rules are ignored with a warning, the crawler must still be able to find nodes.
+#[derive(Debug, Default)]
+pub struct NodeFilter {
+    allowlist: Vec<Cidr>,
+    // denylist and spy nodes
+    denylist: Vec<Cidr>,
+    diversity: bool,
+    prefix_table: PrefixTable,
+    require_full_rpc: bool,
+}
+
+impl NodeFilter {
+    pub fn new(settings: &CrawlerRequirements) -> Self {
+        let mut filter = NodeFilter {
+            diversity: settings.diversity,
+            require_full_rpc: settings.require_full_rpc,
+            ..Default::default()
+        };
+        match parse_cidr_list(&settings.allowlist) {
+            Ok(list) => filter.allowlist = list,
+            Err(e) => warn!("Crawl | Allowlist is invalid, ignoring it ... {e}"),
+        }
+        match parse_cidr_list(&settings.denylist) {
+            Ok(list) => filter.denylist = list,
+            Err(e) => warn!("Crawl | Denylist is invalid, ignoring it ... {e}"),
+        }
+        if !settings.spy_nodes_path.is_empty() {
+            match read_cidr_list(Path::new(&settings.spy_nodes_path)) {
+                Ok(list) => {
+                    info!("Crawl | Loaded {} spy nodes entries ... OK", list.len());
+                    filter.denylist.extend(list);
+                }
+                Err(e) => warn!("Crawl | Spy nodes list ... FAIL ... {e}"),
+            }
+        }
+        if settings.diversity && !settings.prefix_table_path.is_empty() {
+            match PrefixTable::read(Path::new(&settings.prefix_table_path)) {
+                Ok(table) => filter.prefix_table = table,
+                Err(e) => warn!("Crawl | Prefix table ... FAIL ... {e}, falling back to /16"),
+            }
+        }
+        filter
+    }
+
+    // Allowlist, denylist and spy nodes, checked before connecting to the node.
+    pub fn accepts_ip(&self, ip: IpAddr) -> bool {
+        (self.allowlist.is_empty() || self.allowlist.iter().any(|c| c.contains(ip)))
+            && !self.denylist.iter().any(|c| c.contains(ip))
+    }
+
+    // Rules that can be checked on a single node.
+    // With [require_full_rpc], the health of the node must have been checked.
+    pub fn accepts(&self, node: &RemoteNode) -> bool {
+        if !self.accepts_ip(node.ip) {
+            return false;
+        }
+        if self.require_full_rpc && node.health.as_ref().is_none_or(|h| h.restricted) {
+            return false;
+        }
+        true
+    }
+
+    // Keep the accepted nodes in the same order.
+    // With [diversity], only the first node of every network is kept, so nodes must be sorted best first.
+    pub fn select<'a>(&self, nodes: impl Iterator<Item = &'a RemoteNode>) -> Vec<&'a RemoteNode> {
+        let mut groups = HashSet::new();
+        nodes
+            .filter(|n| self.accepts(n))
+            .filter(|n| !self.diversity || groups.insert(self.prefix_table.network_group(n.ip)))
+            .collect()
+    }
+}
+
 impl Crawler {
-    pub fn new() -> Arc<Mutex<Self>> {
-        Arc::new(Mutex::new(Crawler::default()))
+    pub fn new(known_nodes: Arc<Mutex<KnownNodes>>) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Crawler {
+            known_nodes,
+            ..Default::default()
+        }))
     }
     pub fn start(
         crawler: &Arc<Mutex<Self>>,
@@ -153,15 +272,48 @@ impl Crawler {
         settings: &CrawlerRequirements,
         terminate_rx: Receiver<bool>,
     ) {
-        // reset the peers found
-        crawler.lock().unwrap().nodes = RemoteNodes::default();
+        // reset the peers found, starting from the reliable nodes of previous runs.
+        // They are counted as medium nodes so that the crawl still looks for fresh fast nodes.
+        let known_nodes = crawler.lock().unwrap().known_nodes.clone();
+        let network = crawler.lock().unwrap().network;
+        let client = Client::builder()
+            .timeout(Duration::from_millis(TIMEOUT_NODE_PING))
+            .build()
+            .unwrap_or_default();
+        let reliable_nodes = known_nodes
+            .lock()
+            .unwrap()
+            .reliable_nodes(settings.nb_nodes_medium.into(), network);
+        // the health of the known nodes is not saved, check them again like the crawled nodes.
+        let reliable_nodes = Self::check_known_nodes(&client, reliable_nodes, network).await;
         let mut nb_nodes_fast = 0;
-        let mut nb_nodes_medium = 0;
+        let mut nb_nodes_medium = reliable_nodes.len() as u8;
+        {
+            let mut crawler_lock = crawler.lock().unwrap();
+            crawler_lock.nodes = reliable_nodes;
+            crawler_lock.nodes.sort_by(|a, b| a.ms.cmp(&b.ms));
+            if nb_nodes_medium > 0 {
+                crawler_lock.msg = format!(
+                    "Starting from {} reliable known node{}",
+                    nb_nodes_medium,
+                    if nb_nodes_medium > 1 { "s" } else { "" }
+                );
+            }
+        }
         let percent = 100.0 / (settings.nb_nodes_fast as f32).floor();
 
+        let filter = NodeFilter::new(settings);
         let max_ping = settings.max_ping;
-        let zmq_ports = settings.zmq_ports.clone();
-        let rpc_ports = settings.rpc_ports.clone();
+        let zmq_ports = settings
+            .zmq_ports
+            .iter()
+            .map(|p| network.port(*p))
+            .collect();
+        let rpc_ports = settings
+            .rpc_ports
+            .iter()
+            .map(|p| network.port(*p))
+            .collect();
 
         let crawl = CrawlBuilder::default()
             .capabilities(vec![
@@ -180,17 +332,75 @@ impl Crawler {
 
         // reset backup host
         if let Some(backup_hosts) = &backup_hosts {
-            backup_hosts.lock().unwrap().clear();
+            let mut vec = backup_hosts.lock().unwrap();
+            crawler
+                .lock()
+                .unwrap()
+                .update_backup_hosts(&mut vec, &filter);
         }
         while let Some((peer, rpc_port, zmq_port, ms)) = stream.next().await {
-            let remote_node = RemoteNode {
+            let mut remote_node = RemoteNode {
                 ip: peer.ip(),
                 rpc: rpc_port,
                 zmq: zmq_port,
                 ms: ms as u64,
+                health: None,
             };
+            if known_nodes
+                .lock()
+                .unwrap()
+                .is_bad(&remote_node, known_nodes::now())
+            {
+                info!(
+                    "Crawl | skipping node known to be bad ... {}",
+                    remote_node.ip
+                );
+                continue;
+            }
+            // the rules on the IP first, a denied or spy node must not be contacted.
+            if !filter.accepts_ip(remote_node.ip) {
+                info!(
+                    "Crawl | skipping node excluded by the rules ... {}",
+                    remote_node.ip
+                );
+                continue;
+            }
+            // the crawler only knows the RPC port is open, ask the node its network and if it is restricted.
+            match GetInfo::request(&client, remote_node.ip, remote_node.rpc).await {
+                Ok((info, _)) if info.result.nettype != network.to_string() => {
+                    info!(
+                        "Crawl | skipping node of another network ... {} ... {}",
+                        remote_node.ip, info.result.nettype
+                    );
+                    continue;
+                }
+                Ok((info, _)) => remote_node.health = Some(info.result.into()),
+                Err(e) => {
+                    info!(
+                        "Crawl | skipping node not answering get_info ... {} ... {e}",
+                        remote_node.ip
+                    );
+                    continue;
+                }
+            }
+            if !filter.accepts(&remote_node) {
+                info!(
+                    "Crawl | skipping node excluded by the rules ... {}",
+                    remote_node.ip
+                );
+                continue;
+            }
+            known_nodes
+                .lock()
+                .unwrap()
+                .record_success(&remote_node, known_nodes::now());
             info!("Crawl | found a new compatible p2pool node !");
             let mut crawler_lock = crawler.lock().unwrap();
+            // a known node added at the start is found again, replace it with the fresh data
+            if let Some(index) = crawler_lock.nodes.iter().position(|n| *n == remote_node) {
+                crawler_lock.nodes.remove(index);
+                nb_nodes_medium = nb_nodes_medium.saturating_sub(1);
+            }
             info!(
                 "max_ping_fast is {}, node is {}",
                 settings.max_ping_fast, remote_node.ms
@@ -249,7 +459,7 @@ impl Crawler {
             // We update them in real time, so that if p2pool is waiting for it to start, it can do so.
             if let Some(hosts) = &backup_hosts {
                 let mut vec = hosts.lock().unwrap();
-                crawler_lock.update_backup_hosts(&mut vec);
+                crawler_lock.update_backup_hosts(&mut vec, &filter);
             }
             // stop if the max number of fast nodes is reached
             if nb_nodes_fast == settings.nb_nodes_fast {
@@ -276,16 +486,48 @@ impl Crawler {
             }
         }
 
+        if let Err(e) = known_nodes.lock().unwrap().save() {
+            error!("Crawl | Saving known nodes failed ... {e}");
+        }
         // since the crawling is stopping, we remove the handler that allows to stop it manually
         crawler.lock().unwrap().handle = None;
         // we only put the crawling to false once the crawling is really done, we don't want to have a second crawling happening when the old one is not yet done.
         crawler.lock().unwrap().crawling = false;
         crawler.lock().unwrap().stopping = false;
     }
-    fn update_backup_hosts(&self, backup_hosts: &mut Vec<PoolNode>) {
+    // Ask the known nodes their network and if they are restricted, keeping only the ones answering for this network.
+    async fn check_known_nodes(
+        client: &Client,
+        nodes: RemoteNodes,
+        network: Network,
+    ) -> RemoteNodes {
+        let infos =
+            futures::future::join_all(nodes.iter().map(|n| GetInfo::request(client, n.ip, n.rpc)))
+                .await;
+        let mut checked = RemoteNodes::default();
+        for (mut node, info) in nodes.iter().cloned().zip(infos) {
+            match info {
+                Ok((info, _)) if info.result.nettype == network.to_string() => {
+                    node.health = Some(info.result.into());
+                    checked.push(node);
+                }
+                Ok(_) => info!(
+                    "Crawl | known node is now on another network ... {}",
+                    node.ip
+                ),
+                Err(e) => info!(
+                    "Crawl | known node not answering get_info ... {} ... {e}",
+                    node.ip
+                ),
+            }
+        }
+        checked
+    }
+    fn update_backup_hosts(&self, backup_hosts: &mut Vec<PoolNode>, filter: &NodeFilter) {
         let mut vec = Vec::new();
         // ping will return only valid nodes.
-        for pinged_node in self.nodes.iter() {
+        // Known nodes added at the start of the crawl did not go through the filter yet.
+        for pinged_node in filter.select(self.nodes.iter()) {
             let node = Node {
                 ip: pinged_node.ip.to_string(),
                 rpc: pinged_node.rpc.to_string(),
@@ -44,6 +44,8 @@ use crate::helper::{
     xrig::{xmrig::ImgXmrig, xmrig::PubXmrigApi},
 };
 // use crate::utils::errors::process_running;
+use crate::disk::xvb_history::XvbHistory;
+use crate::helper::xvb::split::XvbWorker;
 use crate::{constants::*, disk::gupax_p2pool_api::GupaxP2poolApi, human::*, macros::*};
 use derive_more::derive::Display;
 use enclose::enc;
@@ -67,11 +69,16 @@ use strum::{EnumCount, EnumIter};
 use sysinfo::{Pid, ProcessRefreshKind, System};
 use xrig::xmrig_proxy::ImgProxy;
 
+use self::node_monitor::NodeMonitor;
 use self::xvb::{PubXvbApi, nodes::Pool};
+pub mod chain_advisor;
 pub mod crawler;
 pub mod node;
+pub mod node_monitor;
 pub mod notification;
+pub mod observer;
 pub mod p2pool;
+pub mod payout_scanner;
 pub mod sys_info;
 pub mod tests;
 pub mod xrig;
@@ -120,6 +127,12 @@ pub struct Helper {
     pub ports_detected_local_node: Arc<Mutex<Option<(u16, u16)>>>,
     pub sys_info: Arc<Mutex<System>>,
     pub notifications_api: Arc<Mutex<NotificationApi>>,
+    // failover state of the P2Pool nodes, kept across P2Pool restarts
+    pub p2pool_node_monitor: Arc<Mutex<NodeMonitor>>,
+    // participation in the XvB rounds, recorded by the XvB process and shown in the XvB tab
+    pub xvb_history: Arc<Mutex<XvbHistory>>,
+    // second XMRig mining on XvB when the hashrate is split by threads
+    pub xvb_worker: Arc<Mutex<XvbWorker>>,
 }
 
 // The communication between the data here and the GUI thread goes as follows:
@@ -442,6 +455,9 @@ impl Helper {
             ports_detected_local_node,
             sys_info,
             notifications_api,
+            p2pool_node_monitor: arc_mut!(NodeMonitor::default()),
+            xvb_history: arc_mut!(XvbHistory::default()),
+            xvb_worker: arc_mut!(XvbWorker::default()),
         }
     }
 
@@ -39,6 +39,7 @@ use crate::{
     },
     macros::sleep,
     utils::constants::{NODE_RPC_PORT_DEFAULT, NODE_ZMQ_PORT_DEFAULT, SOCKET_MONERO_LOCAL_OUTSIDE},
+    utils::monero_address::Network,
 };
 use std::fmt::Write;
 
@@ -77,23 +78,36 @@ impl Helper {
     pub fn build_node_args(
         state: &crate::disk::state::Node,
         mode: StartOptionsMode,
+        network: Network,
     ) -> Vec<String> {
         let mut args = Vec::with_capacity(500);
 
+        // the network is not part of the custom arguments, the user must add it.
+        if mode != StartOptionsMode::Custom
+            && let Some(flag) = network.monerod_flag()
+        {
+            args.push(flag.to_string());
+        }
         // [Simple]
         match mode {
             StartOptionsMode::Simple => {
                 // Build the node argument to be compatible with p2pool, prune by default
                 args.push("--zmq-pub".to_string());
-                args.push("tcp://127.0.0.1:18083".to_string()); // Local P2Pool (the default)
+                args.push(format!(
+                    "tcp://127.0.0.1:{}",
+                    network.port(NODE_ZMQ_PORT_DEFAULT)
+                )); // Local P2Pool (the default)
                 args.push("--out-peers".to_string());
                 args.push("32".to_string());
                 args.push("--in-peers".to_string());
                 args.push("64".to_string()); // Rig name
-                args.push("--add-priority-node".to_string());
-                args.push("p2pmd.xmrvsbeast.com:18080".to_string());
-                args.push("--add-priority-node".to_string());
-                args.push("nodes.hashvault.pro:18080".to_string());
+                // the priority nodes are mainnet nodes
+                if network == Network::Mainnet {
+                    args.push("--add-priority-node".to_string());
+                    args.push("p2pmd.xmrvsbeast.com:18080".to_string());
+                    args.push("--add-priority-node".to_string());
+                    args.push("nodes.hashvault.pro:18080".to_string());
+                }
                 args.push("--disable-dns-checkpoints".to_string());
                 args.push("--enable-dns-blocklist".to_string());
                 args.push("--sync-pruned-blocks".to_string());
@@ -156,7 +170,7 @@ impl Helper {
     #[inline(never)]
     // The "restart frontend" to a "frontend" function.
     // Basically calls to kill the current p2pool, waits a little, then starts the below function in a a new thread, then exit.
-    pub fn restart_node(helper: &Arc<Mutex<Self>>, state: &Node, path: &Path) {
+    pub fn restart_node(helper: &Arc<Mutex<Self>>, state: &Node, path: &Path, network: Network) {
         info!("Node | Attempting to restart...");
         helper.lock().unwrap().node.lock().unwrap().signal = ProcessSignal::Restart;
         helper.lock().unwrap().node.lock().unwrap().state = ProcessState::Middle;
@@ -172,14 +186,14 @@ impl Helper {
             }
             // Ok, process is not alive, start the new one!
             info!("Node | Old process seems dead, starting new one!");
-            Self::start_node(&helper, &state, &path);
+            Self::start_node(&helper, &state, &path, network);
         });
         info!("Node | Restart ... OK");
     }
     #[cold]
     #[inline(never)]
     // The "frontend" function that parses the arguments, and spawns either the [Simple] or [Advanced] Node watchdog thread.
-    pub fn start_node(helper: &Arc<Mutex<Self>>, state: &Node, path: &Path) {
+    pub fn start_node(helper: &Arc<Mutex<Self>>, state: &Node, path: &Path, network: Network) {
         let mut args = vec![];
 
         let ports_detected_local_node = *helper
@@ -211,8 +225,8 @@ impl Helper {
                 StartOptionsMode::Advanced
             };
             // Set the ImgNode with ports that the Node started with
-            *helper.lock().unwrap().img_node.lock().unwrap() = ImgNode::new(state, &mode);
-            args = Self::build_node_args(state, mode);
+            *helper.lock().unwrap().img_node.lock().unwrap() = ImgNode::new(state, &mode, network);
+            args = Self::build_node_args(state, mode, network);
 
             // Print arguments & user settings to console
             crate::disk::print_dash(&format!("Node | Launch arguments: {args:#?}"));
@@ -556,18 +570,22 @@ impl Default for ImgNode {
 }
 
 impl ImgNode {
-    pub fn new(state: &crate::disk::state::Node, mode: &StartOptionsMode) -> ImgNode {
+    pub fn new(
+        state: &crate::disk::state::Node,
+        mode: &StartOptionsMode,
+        network: Network,
+    ) -> ImgNode {
         match mode {
-            StartOptionsMode::Simple => ImgNode::default(),
-            StartOptionsMode::Advanced => {
-                let (rpc_port, zmq_port) = state.ports();
+            StartOptionsMode::Simple | StartOptionsMode::Advanced => {
+                let (rpc_port, zmq_port) = state.ports(network);
                 ImgNode { rpc_port, zmq_port }
             }
             StartOptionsMode::Custom => {
                 // This parses the input and attempts to fill out
                 // the [ImgXmrig]... This is pretty bad code...
                 let mut last = "";
-                let mut img = ImgNode::default();
+                let (rpc_port, zmq_port) = state.ports(network);
+                let mut img = ImgNode { rpc_port, zmq_port };
                 for arg in state.arguments.split_whitespace() {
                     match last {
                         "--zmq-rpc-bind-port" => {
@@ -0,0 +1,285 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Background monitor of the remote nodes used by P2Pool.
+// P2Pool switches by itself between the [--host] it was started with,
+// but if all of them degrade, Gupax must find new nodes and restart P2Pool with them.
+
+use super::Helper;
+use crate::app::BackupNodes;
+use crate::app::panels::middle::common::list_poolnode::PoolNode;
+use crate::components::node::{RemoteNode, TIMEOUT_NODE_PING, check_node, set_lag};
+use crate::disk::known_nodes;
+use crate::disk::state::{Node, P2pool};
+use crate::helper::crawler::Crawler;
+use crate::utils::constants::{
+    P2POOL_MONITOR_COOLDOWN, P2POOL_MONITOR_DEGRADED_CHECKS, P2POOL_MONITOR_INTERVAL,
+};
+use futures::future::join_all;
+use log::*;
+use reqwest::Client;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+//---------------------------------------------------------------------------------------------------- [NodeMonitor]
+// Hysteresis of the failover, to not make P2Pool flap between nodes on a short network issue.
+// A failover is only allowed after [P2POOL_MONITOR_DEGRADED_CHECKS] checks in a row found every node degraded,
+// and not sooner than [P2POOL_MONITOR_COOLDOWN] seconds after the last one.
+#[derive(Debug, Default)]
+pub struct NodeMonitor {
+    degraded_checks: u8,
+    last_failover: Option<Instant>,
+}
+
+impl NodeMonitor {
+    // Returns true if the result of this check must trigger a failover.
+    pub fn update(&mut self, all_degraded: bool, now: Instant) -> bool {
+        if !all_degraded {
+            self.degraded_checks = 0;
+            return false;
+        }
+        self.degraded_checks = self.degraded_checks.saturating_add(1);
+        if self.degraded_checks < P2POOL_MONITOR_DEGRADED_CHECKS {
+            return false;
+        }
+        if self.last_failover.is_some_and(|last| {
+            now.duration_since(last) < Duration::from_secs(P2POOL_MONITOR_COOLDOWN)
+        }) {
+            return false;
+        }
+        self.degraded_checks = 0;
+        self.last_failover = Some(now);
+        true
+    }
+}
+
+// Remote nodes that can be checked, without duplicates.
+// Local nodes are ignored, their state is already watched by the Node process.
+fn remote_nodes<'a>(nodes: impl Iterator<Item = (&'a str, &'a str, &'a str)>) -> Vec<RemoteNode> {
+    let mut remote_nodes: Vec<RemoteNode> = vec![];
+    for (ip, rpc, zmq) in nodes {
+        let (Ok(ip), Ok(rpc), Ok(zmq)) =
+            (ip.parse::<IpAddr>(), rpc.parse::<u16>(), zmq.parse::<u16>())
+        else {
+            continue;
+        };
+        let node = RemoteNode {
+            ip,
+            rpc,
+            zmq,
+            ms: 0,
+            health: None,
+        };
+        if !ip.is_loopback() && !remote_nodes.contains(&node) {
+            remote_nodes.push(node);
+        }
+    }
+    remote_nodes
+}
+
+impl Helper {
+    // Check the current node of P2Pool and its backup nodes every [P2POOL_MONITOR_INTERVAL] seconds.
+    // If they are all degraded long enough, crawl new nodes and restart P2Pool with them.
+    // Stops when P2Pool is stopped or restarted, since a new start spawns a new monitor.
+    #[tokio::main]
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn watch_p2pool_nodes(
+        helper: &Arc<Mutex<Helper>>,
+        state: &P2pool,
+        state_node: &Node,
+        path_p2pool: &Path,
+        backup_hosts: BackupNodes,
+        crawler: &Arc<Mutex<Crawler>>,
+        started_with: Vec<PoolNode>,
+    ) {
+        // let P2Pool connect to its node before the first check
+        sleep(Duration::from_secs(P2POOL_MONITOR_INTERVAL)).await;
+        let process = Arc::clone(&helper.lock().unwrap().p2pool);
+        let gui_api = Arc::clone(&helper.lock().unwrap().gui_api_p2pool);
+        let monitor = Arc::clone(&helper.lock().unwrap().p2pool_node_monitor);
+        let known_nodes = Arc::clone(&crawler.lock().unwrap().known_nodes);
+        let network = crawler.lock().unwrap().network;
+        let start = process.lock().unwrap().start;
+        let client = Client::builder()
+            .timeout(Duration::from_millis(TIMEOUT_NODE_PING))
+            .build()
+            .unwrap_or_default();
+        info!("P2Pool Node Monitor | Watching the nodes...");
+        loop {
+            {
+                let process = process.lock().unwrap();
+                if !process.is_alive() || process.start != start {
+                    break;
+                }
+            }
+            let current_node = gui_api.lock().unwrap().current_node.clone();
+            let nodes = remote_nodes(
+                current_node
+                    .iter()
+                    .map(|n| (n.ip.as_str(), n.rpc.as_str(), n.zmq.as_str()))
+                    .chain(started_with.iter().map(|n| (n.ip(), n.port(), n.custom()))),
+            );
+            if !nodes.is_empty() {
+                let mut checked = vec![];
+                let mut failed = vec![];
+                for (node, result) in nodes
+                    .iter()
+                    .zip(join_all(nodes.iter().map(|n| check_node(&client, n))).await)
+                {
+                    match result {
+                        Ok(node) => checked.push(node),
+                        Err(e) => {
+                            warn!("P2Pool Node Monitor | {} ... {e}", node.ip);
+                            failed.push(node.clone());
+                        }
+                    }
+                }
+                set_lag(&mut checked, network);
+                let healthy = checked
+                    .iter()
+                    .filter(|n| n.health.as_ref().is_some_and(|h| h.is_healthy(network)))
+                    .count();
+                {
+                    let mut known_nodes = known_nodes.lock().unwrap();
+                    let now = known_nodes::now();
+                    for node in checked.iter() {
+                        known_nodes.record_success(node, now);
+                    }
+                    for node in failed.iter() {
+                        known_nodes.record_failure(node, now);
+                    }
+                }
+                debug!(
+                    "P2Pool Node Monitor | {healthy}/{} healthy nodes",
+                    nodes.len()
+                );
+                let failover = monitor.lock().unwrap().update(healthy == 0, Instant::now());
+                if failover
+                    && Self::failover_p2pool(
+                        helper,
+                        state,
+                        state_node,
+                        path_p2pool,
+                        &backup_hosts,
+                        crawler,
+                        &nodes,
+                    )
+                    .await
+                {
+                    break;
+                }
+            }
+            sleep(Duration::from_secs(P2POOL_MONITOR_INTERVAL)).await;
+        }
+        info!("P2Pool Node Monitor | Stopped watching the nodes");
+    }
+
+    // Crawl new nodes and restart P2Pool with the best one that is not degraded.
+    // Returns false if no new node was found, P2Pool keeps running with its current nodes.
+    async fn failover_p2pool(
+        helper: &Arc<Mutex<Helper>>,
+        state: &P2pool,
+        state_node: &Node,
+        path_p2pool: &Path,
+        backup_hosts: &BackupNodes,
+        crawler: &Arc<Mutex<Crawler>>,
+        degraded: &[RemoteNode],
+    ) -> bool {
+        warn!("P2Pool Node Monitor | Every node is degraded, looking for new nodes...");
+        Crawler::start(crawler, &state.crawl_settings, Some(backup_hosts.clone()));
+        while crawler.lock().unwrap().crawling {
+            sleep(Duration::from_secs(1)).await;
+        }
+        // the backup hosts were replaced by the crawler with the new nodes passing the crawl rules, best first.
+        let new_nodes = remote_nodes(
+            backup_hosts
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|n| (n.ip(), n.port(), n.custom())),
+        );
+        let Some(node) = new_nodes.into_iter().find(|n| !degraded.contains(n)) else {
+            warn!("P2Pool Node Monitor | No new node found, keeping the current ones");
+            return false;
+        };
+        info!(
+            "P2Pool Node Monitor | Restarting P2Pool with new node ... {}",
+            node.ip
+        );
+        let mut state = state.clone();
+        state.selected_remote_node = Some(node);
+        Helper::restart_p2pool(
+            helper,
+            &state,
+            state_node,
+            path_p2pool,
+            backup_hosts.clone(),
+            false,
+            crawler,
+        );
+        true
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn node_monitor_hysteresis() {
+        let mut monitor = NodeMonitor::default();
+        let start = Instant::now();
+        // a healthy check resets the count
+        for _ in 1..P2POOL_MONITOR_DEGRADED_CHECKS {
+            assert!(!monitor.update(true, start));
+        }
+        assert!(!monitor.update(false, start));
+        for _ in 1..P2POOL_MONITOR_DEGRADED_CHECKS {
+            assert!(!monitor.update(true, start));
+        }
+        assert!(monitor.update(true, start));
+        // no new failover during the cooldown
+        let later = start + Duration::from_secs(P2POOL_MONITOR_COOLDOWN - 1);
+        for _ in 0..P2POOL_MONITOR_DEGRADED_CHECKS * 2 {
+            assert!(!monitor.update(true, later));
+        }
+        let after_cooldown = start + Duration::from_secs(P2POOL_MONITOR_COOLDOWN);
+        assert!(monitor.update(true, after_cooldown));
+    }
+
+    #[test]
+    fn node_monitor_remote_nodes() {
+        let nodes = remote_nodes(
+            [
+                ("37.187.74.171", "18089", "18084"),
+                ("127.0.0.1", "18081", "18083"),
+                ("node.example.com", "18089", "18084"),
+                ("37.187.74.171", "18089", "18084"),
+                ("1.2.3.4", "18081", "18083"),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            nodes.iter().map(|n| n.ip.to_string()).collect::<Vec<_>>(),
+            ["37.187.74.171", "1.2.3.4"]
+        );
+    }
+}
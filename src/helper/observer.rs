@@ -0,0 +1,311 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Data of a P2Pool observer about the sidechain and the address of the user.
+// The local data-api of P2Pool only knows what the local P2Pool saw since it started,
+// the observer keeps the blocks found by the pool and the shares found by every rig mining to the address.
+// The payouts known by the observer are reconciled with the log like the ones found by the payout scanner.
+
+use crate::{
+    disk::gupax_p2pool_api::GupaxP2poolApi,
+    helper::payout_scanner::{ChainPayout, Reconciliation},
+    utils::xmr::AtomicUnit,
+};
+use enclose::enc;
+use log::{info, warn};
+use reqwest::Client;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_this_or_that::as_u64;
+use std::{
+    sync::{Arc, Mutex},
+    thread::spawn,
+    time::Duration,
+};
+
+//---------------------------------------------------------------------------------------------------- Const
+// Found blocks, shares and payouts asked to the observer.
+pub const OBSERVER_LIMIT: u64 = 50;
+const TIMEOUT_OBSERVER: u64 = 10;
+
+//---------------------------------------------------------------------------------------------------- API
+// Only the fields used are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FoundBlock {
+    pub main_block: MainBlock,
+    pub side_height: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MainBlock {
+    pub height: u64,
+    pub timestamp: i64,
+    #[serde(deserialize_with = "as_u64")]
+    pub reward: u64,
+}
+
+// A share found by the address, on any rig.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SideBlock {
+    pub side_height: u64,
+    pub timestamp: i64,
+    #[serde(deserialize_with = "as_u64")]
+    pub difficulty: u64,
+    // template id of the block including this share as an uncle
+    #[serde(default)]
+    pub uncle_of: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ObserverPayout {
+    pub main_height: u64,
+    pub timestamp: i64,
+    #[serde(deserialize_with = "as_u64")]
+    pub coinbase_reward: u64,
+}
+
+impl From<&ObserverPayout> for ChainPayout {
+    fn from(payout: &ObserverPayout) -> Self {
+        Self {
+            height: payout.main_height,
+            timestamp: payout.timestamp,
+            amount: AtomicUnit::from_u64(payout.coinbase_reward),
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Observer
+#[derive(Debug, Clone, Default)]
+pub struct Observer {
+    pub fetching: bool,
+    pub msg: String,
+    // most recent first, like the observer gives them
+    pub found_blocks: Vec<FoundBlock>,
+    pub shares: Vec<SideBlock>,
+    pub payouts: Vec<ObserverPayout>,
+    pub report: Option<Reconciliation>,
+}
+
+impl Observer {
+    pub fn start(
+        observer: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        url: String,
+        address: String,
+    ) {
+        let mut lock = observer.lock().unwrap();
+        if lock.fetching {
+            return;
+        }
+        lock.fetching = true;
+        lock.msg = format!("Fetching from {url}...");
+        drop(lock);
+        info!("Spawning observer thread...");
+        spawn(enc!((observer, gupax_p2pool_api) move || {
+            match Self::fetch(&observer, &gupax_p2pool_api, &url, &address) {
+                Ok(()) => {
+                    let mut lock = observer.lock().unwrap();
+                    lock.msg = format!(
+                        "{} found blocks, {} shares and {} payouts fetched from {url}",
+                        lock.found_blocks.len(),
+                        lock.shares.len(),
+                        lock.payouts.len()
+                    );
+                }
+                Err(e) => {
+                    warn!("Observer | Fetch from {url} ... FAIL ... {e}");
+                    observer.lock().unwrap().msg = format!("Fetch failed: {e}");
+                }
+            }
+            observer.lock().unwrap().fetching = false;
+        }));
+    }
+
+    #[tokio::main]
+    pub async fn fetch(
+        observer: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        url: &str,
+        address: &str,
+    ) -> anyhow::Result<()> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_OBSERVER))
+            .build()?;
+        let url = url.trim_end_matches('/');
+        let found_blocks: Vec<FoundBlock> = Self::request(
+            &client,
+            &format!("{url}/api/found_blocks?limit={OBSERVER_LIMIT}"),
+        )
+        .await?;
+        let shares: Vec<SideBlock> = Self::request(
+            &client,
+            &format!("{url}/api/shares?miner={address}&limit={OBSERVER_LIMIT}"),
+        )
+        .await?;
+        let payouts: Vec<ObserverPayout> = Self::request(
+            &client,
+            &format!("{url}/api/payouts/{address}?search_limit={OBSERVER_LIMIT}"),
+        )
+        .await?;
+        info!(
+            "Observer | Fetch from {url} ... OK ... {} blocks, {} shares, {} payouts",
+            found_blocks.len(),
+            shares.len(),
+            payouts.len()
+        );
+        let report = Self::reconcile(&gupax_p2pool_api.lock().unwrap(), &payouts);
+        let mut lock = observer.lock().unwrap();
+        lock.found_blocks = found_blocks;
+        lock.shares = shares;
+        lock.payouts = payouts;
+        lock.report = report;
+        Ok(())
+    }
+
+    async fn request<T: DeserializeOwned>(client: &Client, url: &str) -> anyhow::Result<T> {
+        Ok(client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<T>()
+            .await?)
+    }
+
+    // The payouts of the observer cover the blocks from its oldest payout, the log is checked in this range only.
+    pub fn reconcile(
+        gupax_p2pool_api: &GupaxP2poolApi,
+        payouts: &[ObserverPayout],
+    ) -> Option<Reconciliation> {
+        let from = payouts.iter().map(|p| p.main_height).min()?;
+        let to = payouts.iter().map(|p| p.main_height).max()?;
+        let found: Vec<ChainPayout> = payouts.iter().map(ChainPayout::from).collect();
+        Some(Reconciliation::new(
+            &gupax_p2pool_api.payout_ord,
+            &found,
+            from..=to,
+        ))
+    }
+
+    // Shares found by the address since [timestamp], uncles included.
+    pub fn shares_since(&self, timestamp: i64) -> usize {
+        self.shares
+            .iter()
+            .filter(|s| s.timestamp >= timestamp)
+            .count()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::human::HumanNumber;
+    use crate::utils::http_mock::mock_http;
+
+    const ADDRESS: &str = "4A5Dwt2qKwKEQrZfo4aBkSNtvDDAzSFbAJcyFkdW5RwDh9U4WgeZrgKT4hUoE2gv8h6NmsNMTyjsEL8eSLMbABds5rYFWnw";
+    // Answers in the format of the observer, the fields not used are trimmed.
+    const FOUND_BLOCKS: &str = r#"[{"main_block":{"id":"aa","height":3000012,"timestamp":1700000240,"reward":601234567892},"side_height":9000120,"miner":1,"effective_height":9000120,"window_depth":2160},{"main_block":{"id":"bb","height":3000010,"timestamp":1700000000,"reward":601234567897},"side_height":9000100,"miner":2,"effective_height":9000100,"window_depth":2160}]"#;
+    const SHARES: &str = r#"[{"side_height":9000115,"main_height":3000012,"timestamp":1700000200,"difficulty":30000000,"template_id":"cc"},{"side_height":9000090,"main_height":3000009,"timestamp":1699999900,"difficulty":"29000000","template_id":"dd","uncle_of":"ee"}]"#;
+    const PAYOUTS: &str = r#"[{"miner":1,"template_id":"aa","side_height":9000120,"main_id":"aa","main_height":3000012,"timestamp":1700000240,"coinbase_reward":1234567892,"global_output_index":100},{"miner":1,"template_id":"bb","side_height":9000100,"main_id":"bb","main_height":3000010,"timestamp":1700000000,"coinbase_reward":1234567897,"global_output_index":90}]"#;
+
+    // Start a fake observer answering the recorded data for [ADDRESS].
+    fn mock_observer() -> String {
+        let addr = mock_http(|request| {
+            let path = &request.path;
+            let body = if path.starts_with("/api/found_blocks?") {
+                Some(FOUND_BLOCKS)
+            } else if path.starts_with(&format!("/api/shares?miner={ADDRESS}&")) {
+                Some(SHARES)
+            } else if path.starts_with(&format!("/api/payouts/{ADDRESS}?")) {
+                Some(PAYOUTS)
+            } else {
+                None
+            };
+            match body {
+                Some(body) => ("200 OK", body.to_string()),
+                None => ("404 Not Found", String::new()),
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    fn log_payout(api: &mut GupaxP2poolApi, date: &str, amount: u64, block: u64) {
+        let line = GupaxP2poolApi::format_payout(
+            date,
+            &AtomicUnit::from_u64(amount),
+            &HumanNumber::from_u64(block),
+        );
+        api.add_payout(
+            &line,
+            date.to_string(),
+            AtomicUnit::from_u64(amount),
+            HumanNumber::from_u64(block),
+        );
+    }
+
+    #[test]
+    fn fetch_observer() {
+        let mut api = GupaxP2poolApi::new();
+        // seen by Gupax, and a payout the observer does not know inside its range
+        log_payout(&mut api, "2023-11-14 23:13:20.0000", 1234567897, 3000010);
+        log_payout(&mut api, "2023-11-14 23:15:20.0000", 1234567000, 3000011);
+        // older than the payouts of the observer
+        log_payout(&mut api, "2023-11-14 20:00:00.0000", 1234567000, 2999900);
+        let api = Arc::new(Mutex::new(api));
+        let observer = Arc::new(Mutex::new(Observer::default()));
+        // the trailing slash of a user URL is ignored
+        let url = format!("{}/", mock_observer());
+        Observer::fetch(&observer, &api, &url, ADDRESS).unwrap();
+
+        let observer = observer.lock().unwrap();
+        assert_eq!(observer.found_blocks.len(), 2);
+        assert_eq!(
+            observer.found_blocks[0].main_block,
+            MainBlock {
+                height: 3000012,
+                timestamp: 1700000240,
+                reward: 601234567892,
+            }
+        );
+        assert_eq!(observer.shares[1].difficulty, 29000000);
+        assert_eq!(observer.shares[1].uncle_of.as_deref(), Some("ee"));
+        assert_eq!(observer.shares_since(1700000000), 1);
+        let report = observer.report.as_ref().unwrap();
+        assert_eq!((report.from, report.to), (3000010, 3000012));
+        assert_eq!(report.verified.len(), 1);
+        assert_eq!(
+            report.missing,
+            vec![ChainPayout {
+                height: 3000012,
+                timestamp: 1700000240,
+                amount: AtomicUnit::from_u64(1234567892),
+            }]
+        );
+        assert_eq!(report.unconfirmed, vec![3000011]);
+    }
+
+    #[test]
+    fn fetch_observer_failure() {
+        let api = Arc::new(Mutex::new(GupaxP2poolApi::new()));
+        let observer = Arc::new(Mutex::new(Observer::default()));
+        // the address is unknown to the mock
+        assert!(Observer::fetch(&observer, &api, &mock_observer(), "4Unknown").is_err());
+        assert!(observer.lock().unwrap().found_blocks.is_empty());
+        // nothing to reconcile without payouts
+        assert_eq!(Observer::reconcile(&api.lock().unwrap(), &[]), None);
+    }
+}
@@ -21,7 +21,9 @@ use crate::app::BackupNodes;
 use crate::app::panels::middle::common::list_poolnode::PoolNode;
 use crate::app::submenu_enum::SubmenuP2pool;
 use crate::components::node::RemoteNode;
+use crate::disk::known_nodes::{self, KnownNodes};
 use crate::disk::node::Node as NodeString;
+use crate::disk::share_history::{PplnsWindow, Share};
 use crate::disk::state::Node;
 use crate::disk::state::P2pool;
 use crate::disk::state::P2poolChain;
@@ -42,6 +44,7 @@ use crate::regex::contains_yourshare;
 use crate::regex::contains_zmq_failure;
 use crate::regex::estimated_hr;
 use crate::regex::nb_current_shares;
+use crate::utils::monero_address::Network;
 use crate::utils::regex::contains_node;
 use crate::utils::regex::contains_window_nb_blocks;
 use crate::utils::regex::p2pool_monero_node;
@@ -72,6 +75,8 @@ impl Helper {
         reader: Box<dyn std::io::Read + Send>,
         gupax_p2pool_api: Arc<Mutex<GupaxP2poolApi>>,
         gui_api: Arc<Mutex<PubP2poolApi>>,
+        chain: P2poolChain,
+        network: Network,
     ) {
         use std::io::BufRead;
         let mut stdout = std::io::BufReader::new(reader).lines();
@@ -186,6 +191,12 @@ impl Helper {
                     error!("P2Pool PTY GupaxP2poolApi | Write error: {e}");
                 }
             }
+            if let Some(share) = Share::from_p2pool_line(&line, &chain, network) {
+                debug!("P2Pool PTY | Found share, attempting write: {line}");
+                if let Err(e) = gupax_p2pool_api.lock().unwrap().add_share(share) {
+                    error!("P2Pool PTY GupaxP2poolApi | Share write error: {e}");
+                }
+            }
             if let Err(e) = writeln!(output_parse.lock().unwrap(), "{line}") {
                 error!("P2Pool PTY Parse | Output error: {e}");
             }
@@ -306,6 +317,7 @@ impl Helper {
         let (local_node_rpc, local_node_zmq) = state_node.current_ports(
             node_process.lock().unwrap().is_alive(),
             &img_node.lock().unwrap(),
+            crawler.lock().unwrap().network,
         );
         if state.backup_host {
             // we want to add backup host but the crawler is still running and did not add at least the minimum of number of fast node (including medium nodes);
@@ -345,6 +357,18 @@ impl Helper {
         let gui_api = Arc::clone(&helper.lock().unwrap().gui_api_p2pool);
         let pub_api = Arc::clone(&helper.lock().unwrap().pub_api_p2pool);
         let gupax_p2pool_api = Arc::clone(&helper.lock().unwrap().gupax_p2pool_api);
+        let known_nodes = Arc::clone(&crawler.lock().unwrap().known_nodes);
+        // chain really started, the arguments of the advanced tab can override the state.
+        let chain = helper
+            .lock()
+            .unwrap()
+            .img_p2pool
+            .lock()
+            .unwrap()
+            .chain
+            .parse()
+            .unwrap_or(P2poolChain::Main);
+        let network = crawler.lock().unwrap().network;
         let path = path.to_path_buf();
         let node_to_start_with = state
             .selected_remote_node
@@ -369,6 +393,24 @@ impl Helper {
             );
         }
 
+        // thread to switch to new nodes if the current one and the backups are degraded.
+        // Only for remote nodes picked by Gupax, nodes of the advanced tab are chosen by the user.
+        if state.auto_failover && simple && !state.local_node && !override_to_local_node {
+            thread::spawn(
+                enc!((helper, state, state_node, path, backup_hosts, crawler) move || {
+                    Self::watch_p2pool_nodes(
+                        &helper,
+                        &state,
+                        &state_node,
+                        &path,
+                        backup_hosts,
+                        &crawler,
+                        backup_nodes,
+                    );
+                }),
+            );
+        }
+
         thread::spawn(move || {
             Self::spawn_p2pool_watchdog(
                 process,
@@ -382,6 +424,9 @@ impl Helper {
                 api_path_p2p,
                 gupax_p2pool_api,
                 node_to_start_with,
+                known_nodes,
+                chain,
+                network,
             );
         });
     }
@@ -631,6 +676,9 @@ impl Helper {
         api_path_p2p: std::path::PathBuf,
         gupax_p2pool_api: Arc<Mutex<GupaxP2poolApi>>,
         node: RemoteNode,
+        known_nodes: Arc<Mutex<KnownNodes>>,
+        chain: P2poolChain,
+        network: Network,
     ) {
         // 1a. Create PTY
         debug!("P2Pool | Creating PTY...");
@@ -668,15 +716,18 @@ impl Helper {
         debug!("P2Pool | Spawning PTY read thread...");
         let output_parse = Arc::clone(&process.lock().unwrap().output_parse);
         let output_pub = Arc::clone(&process.lock().unwrap().output_pub);
-        let gupax_p2pool_api = Arc::clone(&gupax_p2pool_api);
+        let gupax_p2pool_api_c = Arc::clone(&gupax_p2pool_api);
         let p2pool_api_c = Arc::clone(&gui_api);
+        let chain_c = chain.clone();
         tokio::spawn(async move {
             Self::read_pty_p2pool(
                 output_parse,
                 output_pub,
                 reader,
-                gupax_p2pool_api,
+                gupax_p2pool_api_c,
                 p2pool_api_c,
+                chain_c,
+                network,
             );
         });
         let output_parse = Arc::clone(&process.lock().unwrap().output_parse);
@@ -728,6 +779,7 @@ impl Helper {
         let mut first_loop = true;
         let mut last_p2pool_request = tokio::time::Instant::now();
         let mut last_status_request = tokio::time::Instant::now();
+        let mut last_known_nodes_update = tokio::time::Instant::now();
 
         info!("P2Pool | Entering watchdog mode... woof!");
         loop {
@@ -765,6 +817,27 @@ impl Helper {
                 Self::check_reset_gui_output(&mut lock.output, ProcessName::P2pool);
                 drop(lock);
 
+                // record the time P2Pool stayed connected to its node, so that reliable nodes are preferred later.
+                // The current node is only kept in the GUI API.
+                let elapsed = last_known_nodes_update.elapsed();
+                if elapsed >= Duration::from_secs(P2POOL_KNOWN_NODES_INTERVAL) {
+                    let current_node = gui_api.lock().unwrap().current_node.clone();
+                    if process.lock().unwrap().state == ProcessState::Alive
+                        && let Some(node) = &current_node
+                    {
+                        let mut known_nodes = known_nodes.lock().unwrap();
+                        known_nodes.record_p2pool_uptime(
+                            node,
+                            elapsed.as_secs(),
+                            known_nodes::now(),
+                        );
+                        if let Err(e) = known_nodes.save() {
+                            warn!("P2Pool Watchdog | Saving known nodes failed ... {e}");
+                        }
+                    }
+                    last_known_nodes_update = tokio::time::Instant::now();
+                }
+
                 // Always update from output
                 debug!("P2Pool Watchdog | Starting [update_from_output()]");
                 let mut process_lock = process.lock().unwrap();
@@ -851,6 +924,19 @@ impl Helper {
                     first_loop = false;
                 }
             } // end of scope to drop lock
+            // Our shares in the PPLNS window, the length of the window is given by the status command.
+            let mut gui_api_lock = gui_api.lock().unwrap();
+            gui_api_lock.pplns_window = PplnsWindow::new(
+                &gupax_p2pool_api.lock().unwrap().shares,
+                &chain,
+                network,
+                gui_api_lock.sidechain_height as u64,
+                gui_api_lock
+                    .window_length_blocks
+                    .unwrap_or(chain.pplns_window_blocks()),
+                chain.seconds_per_block(),
+            );
+            drop(gui_api_lock);
             sleep_end_loop(now, ProcessName::P2pool).await;
         }
 
@@ -1004,6 +1090,10 @@ pub struct PubP2poolApi {
     pub prefer_local_node: bool,
     pub current_node: Option<NodeString>,
     pub window_length_blocks: Option<u64>,
+    // Our shares in the PPLNS window
+    pub pplns_window: PplnsWindow,
+    // miners connected to the stratum server
+    pub workers: Vec<P2poolWorker>,
 }
 
 impl Default for PubP2poolApi {
@@ -1062,6 +1152,8 @@ impl PubP2poolApi {
             fails_zmq_since: None,
             current_node: None,
             window_length_blocks: None,
+            pplns_window: PplnsWindow::default(),
+            workers: vec![],
         }
     }
 
@@ -1084,6 +1176,7 @@ impl PubP2poolApi {
             prefer_local_node: std::mem::take(&mut gui_api.prefer_local_node),
             current_node: std::mem::take(&mut gui_api.current_node),
             window_length_blocks: std::mem::take(&mut gui_api.window_length_blocks),
+            pplns_window: std::mem::take(&mut gui_api.pplns_window),
             ..pub_api.clone()
         };
     }
@@ -1199,6 +1292,17 @@ impl PubP2poolApi {
             current_effort: HumanNumber::to_percent(local.current_effort),
             connections: HumanNumber::from_u32(local.connections),
             user_p2pool_hashrate_u64: local.hashrate_1h,
+            workers: local
+                .workers
+                .iter()
+                .filter_map(|w| {
+                    let worker = P2poolWorker::from_str(w);
+                    if worker.is_none() {
+                        warn!("P2Pool Local API | Could not parse worker: {w}");
+                    }
+                    worker
+                })
+                .collect(),
             ..std::mem::take(&mut *public)
         };
     }
@@ -1313,6 +1417,12 @@ impl PubP2poolApi {
         }
     }
 
+    // Number of miners started by Gupax that should be mining on the local stratum but are not connected to it.
+    // Other workers are not counted, they can be rigs of the local network.
+    pub fn missing_local_workers(&self, expected: usize) -> usize {
+        expected.saturating_sub(self.workers.iter().filter(|w| w.is_local()).count())
+    }
+
     #[inline]
     pub fn calculate_dominance(my_hashrate: u64, global_hashrate: u64) -> HumanNumber {
         if global_hashrate == 0 {
@@ -1327,7 +1437,7 @@ impl PubP2poolApi {
 //---------------------------------------------------------------------------------------------------- Private P2Pool "Local" Api
 // This matches directly to P2Pool's [local/stratum] JSON API file (excluding a few stats).
 // P2Pool seems to initialize all stats at 0 (or 0.0), so no [Option] wrapper seems needed.
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(super) struct PrivP2poolLocalApi {
     pub hashrate_15m: u64,
     pub hashrate_1h: u64,
@@ -1336,6 +1446,9 @@ pub(super) struct PrivP2poolLocalApi {
     pub average_effort: f32,
     pub current_effort: f32,
     pub connections: u32, // This is a `uint32_t` in `p2pool`
+    // one entry per miner connected to the stratum, see [P2poolWorker]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workers: Vec<String>,
 }
 
 impl Default for PrivP2poolLocalApi {
@@ -1354,6 +1467,7 @@ impl PrivP2poolLocalApi {
             average_effort: 0.0,
             current_effort: 0.0,
             connections: 0,
+            workers: vec![],
         }
     }
 
@@ -1369,6 +1483,44 @@ impl PrivP2poolLocalApi {
     }
 }
 
+//---------------------------------------------------------------------------------------------------- [P2poolWorker]
+// A miner connected to the stratum server of P2Pool.
+// P2Pool writes them in the [workers] of the stratum API as [ip:port,uptime,difficulty,hashrate,name].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct P2poolWorker {
+    pub ip: String,
+    // seconds since the miner connected
+    pub uptime: u64,
+    pub difficulty: u64,
+    pub hashrate: u64,
+    pub name: String,
+}
+
+impl P2poolWorker {
+    pub fn from_str(s: &str) -> Option<Self> {
+        let mut fields = s.splitn(5, ',');
+        Some(Self {
+            ip: fields.next()?.to_string(),
+            uptime: fields.next()?.parse().ok()?,
+            difficulty: fields.next()?.parse().ok()?,
+            hashrate: fields.next()?.parse().ok()?,
+            name: fields.next().unwrap_or_default().to_string(),
+        })
+    }
+
+    // Miners started by Gupax connect from the same machine.
+    pub fn is_local(&self) -> bool {
+        let ip = self
+            .ip
+            .rsplit_once(':')
+            .map_or(self.ip.as_str(), |(ip, _port)| ip)
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+        ip.parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.to_canonical().is_loopback())
+    }
+}
+
 //---------------------------------------------------------------------------------------------------- Private P2Pool "Network" API
 // This matches P2Pool's [network/stats] JSON API file.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -0,0 +1,758 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Verification of the P2Pool payouts with the private view key of the wallet.
+// P2Pool pays its miners directly in the coinbase transaction of the blocks it finds,
+// so the payouts can be found by scanning the coinbase of the recent blocks with the local monerod RPC.
+// The payouts found are reconciled with the ones logged from the P2Pool console:
+//     - payouts found in both are verified
+//     - payouts found only on chain were missed (Gupax was not running), they are added to the log
+//     - payouts logged but not found on chain are reported
+// The payouts reported by another source, like an observer, are only added to the log once found on chain.
+
+use crate::{
+    components::node::{GetInfo, TIMEOUT_NODE_PING},
+    disk::gupax_p2pool_api::GupaxP2poolApi,
+    human::HumanNumber,
+    utils::{
+        monero_address::{KEY_SIZE, MoneroAddress, read_varint},
+        view_key::{ViewKey, hex_decode, output_key, view_tag},
+    },
+    xmr::{AtomicUnit, PayoutOrd},
+};
+use chrono::{DateTime, Local};
+use enclose::enc;
+use log::{error, info, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use std::{
+    net::SocketAddr,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+    thread::spawn,
+    time::Duration,
+};
+use zeroize::Zeroize;
+
+//---------------------------------------------------------------------------------------------------- Const
+// Blocks scanned on the first scan of a session, a week of blocks.
+pub const PAYOUT_SCAN_DEPTH: u64 = 720 * 7;
+// get_block returns the whole block, a local node can take longer than a ping.
+const TIMEOUT_GET_BLOCK: u64 = TIMEOUT_NODE_PING * 5;
+// Tags of the extra field of a transaction.
+const TX_EXTRA_PUBKEY: u8 = 0x01;
+const TX_EXTRA_NONCE: u8 = 0x02;
+const TX_EXTRA_MERGE_MINING: u8 = 0x03;
+const TX_EXTRA_ADDITIONAL_PUBKEYS: u8 = 0x04;
+
+//---------------------------------------------------------------------------------------------------- `/get_block`
+// The block is given as a JSON string inside the JSON-RPC answer.
+// Only the coinbase transaction is needed.
+#[derive(Debug, Deserialize)]
+pub struct GetBlock {
+    pub result: GetBlockResult,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetBlockResult {
+    pub block_header: BlockHeader,
+    pub json: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockHeader {
+    pub height: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockJson {
+    pub miner_tx: MinerTx,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MinerTx {
+    pub vout: Vec<Output>,
+    pub extra: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Output {
+    pub amount: u64,
+    pub target: OutputTarget,
+}
+
+// Outputs have a view tag since the hard fork v15.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputTarget {
+    Key(String),
+    TaggedKey { key: String, view_tag: String },
+}
+
+impl GetBlock {
+    pub async fn request(client: &Client, rpc: SocketAddr, height: u64) -> anyhow::Result<Self> {
+        Ok(client
+            .post(format!("http://{rpc}/json_rpc"))
+            .body(format!(
+                r#"{{"jsonrpc":"2.0","id":"0","method":"get_block","params":{{"height":{height}}}}}"#
+            ))
+            .send()
+            .await?
+            .json::<GetBlock>()
+            .await?)
+    }
+}
+
+impl MinerTx {
+    // The public key of the transaction is the first [TX_EXTRA_PUBKEY] field of the extra.
+    pub fn public_key(&self) -> Option<[u8; KEY_SIZE]> {
+        let mut extra = self.extra.as_slice();
+        while let Some((&tag, rest)) = extra.split_first() {
+            extra = match tag {
+                TX_EXTRA_PUBKEY => return rest.get(..KEY_SIZE)?.try_into().ok(),
+                TX_EXTRA_NONCE | TX_EXTRA_MERGE_MINING => {
+                    let (len, rest) = read_varint(rest)?;
+                    rest.get(len as usize..)?
+                }
+                TX_EXTRA_ADDITIONAL_PUBKEYS => {
+                    let (count, rest) = read_varint(rest)?;
+                    rest.get(count as usize * KEY_SIZE..)?
+                }
+                // padding or unknown field, the rest can not be parsed
+                _ => return None,
+            };
+        }
+        None
+    }
+
+    // Sum of the outputs paying the address, None if there is none.
+    pub fn payout(&self, address: &MoneroAddress, view_key: &ViewKey) -> Option<AtomicUnit> {
+        let derivation = view_key.derivation(&self.public_key()?)?;
+        let mut payout = None;
+        for (index, output) in self.vout.iter().enumerate() {
+            let index = index as u64;
+            let key = match &output.target {
+                OutputTarget::Key(key) => key,
+                OutputTarget::TaggedKey { key, view_tag: tag } => {
+                    if hex_decode(tag).as_deref() != Some(&[view_tag(&derivation, index)]) {
+                        continue;
+                    }
+                    key
+                }
+            };
+            if hex_decode(key).as_deref()
+                == output_key(&derivation, index, &address.spend_key)
+                    .as_ref()
+                    .map(|k| k.as_slice())
+            {
+                payout = Some(
+                    payout
+                        .unwrap_or(AtomicUnit::new())
+                        .add_self(AtomicUnit::from_u64(output.amount)),
+                );
+            }
+        }
+        payout
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Reconciliation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainPayout {
+    pub height: u64,
+    pub timestamp: i64,
+    pub amount: AtomicUnit,
+}
+
+impl ChainPayout {
+    // Same format as the date of the P2Pool console, blocks only have a precision of a second.
+    pub fn date(&self) -> String {
+        match DateTime::from_timestamp(self.timestamp, 0) {
+            Some(date) => date
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S.0000")
+                .to_string(),
+            None => "????-??-?? ??:??:??.????".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reconciliation {
+    // first and last blocks scanned
+    pub from: u64,
+    pub to: u64,
+    // logged payouts found on chain
+    pub verified: Vec<ChainPayout>,
+    // payouts found on chain missing from the log
+    pub missing: Vec<ChainPayout>,
+    // blocks of the logged payouts inside the scanned blocks but not found on chain
+    pub unconfirmed: Vec<u64>,
+}
+
+impl Reconciliation {
+    pub fn new(payouts: &PayoutOrd, found: &[ChainPayout], range: RangeInclusive<u64>) -> Self {
+        let logged: Vec<u64> = payouts.blocks().collect();
+        let (verified, missing) = found
+            .iter()
+            .cloned()
+            .partition(|p| logged.contains(&p.height));
+        let mut unconfirmed: Vec<u64> = logged
+            .into_iter()
+            .filter(|b| range.contains(b) && !found.iter().any(|p| p.height == *b))
+            .collect();
+        unconfirmed.sort_unstable();
+        unconfirmed.dedup();
+        Self {
+            from: *range.start(),
+            to: *range.end(),
+            verified,
+            missing,
+            unconfirmed,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- PayoutScanner
+pub struct PayoutScanner {
+    pub scanning: bool,
+    pub msg: String,
+    pub prog: f32,
+    // decrypted for the session only, the state keeps the encrypted key
+    pub view_key: Option<ViewKey>,
+    // fields of the GUI, wiped once used
+    pub input_view_key: String,
+    pub passphrase: String,
+    // last block scanned, the next scan continues from there
+    pub last_height: Option<u64>,
+    pub report: Option<Reconciliation>,
+}
+
+impl Default for PayoutScanner {
+    fn default() -> Self {
+        Self {
+            scanning: false,
+            msg: "Locked".to_string(),
+            prog: 0.0,
+            view_key: None,
+            input_view_key: String::new(),
+            passphrase: String::new(),
+            last_height: None,
+            report: None,
+        }
+    }
+}
+
+impl PayoutScanner {
+    // Wipe the fields typed by the user.
+    pub fn wipe_inputs(&mut self) {
+        self.input_view_key.zeroize();
+        self.passphrase.zeroize();
+    }
+
+    // Forget the decrypted key and the results of the session.
+    pub fn forget_key(&mut self) {
+        self.wipe_inputs();
+        *self = Self::default();
+        info!("Payout Scanner | View key wipe ... OK");
+    }
+
+    pub fn start(
+        scanner: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        address: MoneroAddress,
+        rpc: SocketAddr,
+    ) {
+        let mut lock = scanner.lock().unwrap();
+        if lock.scanning {
+            return;
+        }
+        let Some(view_key) = lock.view_key.clone() else {
+            return;
+        };
+        lock.scanning = true;
+        lock.prog = 0.0;
+        lock.msg = format!("Connecting to the local node [{rpc}]...");
+        drop(lock);
+        info!("Spawning payout scanner thread...");
+        spawn(enc!((scanner, gupax_p2pool_api) move || {
+            match Self::scan(&scanner, &gupax_p2pool_api, &address, &view_key, rpc) {
+                Ok(report) => {
+                    let mut lock = scanner.lock().unwrap();
+                    lock.msg = format!(
+                        "Blocks [{}..{}] scanned: {} payouts verified, {} missing payouts added to the log, {} payouts not found",
+                        report.from,
+                        report.to,
+                        report.verified.len(),
+                        report.missing.len(),
+                        report.unconfirmed.len()
+                    );
+                    lock.report = Some(report);
+                }
+                Err(e) => {
+                    warn!("Payout Scanner | Scan ... FAIL ... {e}");
+                    scanner.lock().unwrap().msg = format!("Scan failed: {e}");
+                }
+            }
+            let mut lock = scanner.lock().unwrap();
+            lock.scanning = false;
+            lock.prog = 100.0;
+        }));
+    }
+
+    #[tokio::main]
+    pub async fn scan(
+        scanner: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        address: &MoneroAddress,
+        view_key: &ViewKey,
+        rpc: SocketAddr,
+    ) -> anyhow::Result<Reconciliation> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(TIMEOUT_GET_BLOCK))
+            .build()?;
+        let (info, _) = GetInfo::request(&client, rpc.ip(), rpc.port()).await?;
+        if !info.result.synchronized {
+            anyhow::bail!("the local node is not synchronized");
+        }
+        // the height is the number of blocks, the last block is the one below.
+        let to = info.result.height.saturating_sub(1);
+        let from = match scanner.lock().unwrap().last_height {
+            Some(last) => last + 1,
+            None => to.saturating_sub(PAYOUT_SCAN_DEPTH - 1),
+        };
+        if from > to {
+            anyhow::bail!("no new block since the last scan");
+        }
+        info!("Payout Scanner | Scanning blocks [{from}..{to}]...");
+        let mut found = vec![];
+        // last block scanned, and the error stopping the scan before [to]
+        let mut last = None;
+        let mut error = None;
+        for height in from..=to {
+            match Self::scan_block(&client, rpc, height, address, view_key).await {
+                Ok(payout) => found.extend(payout),
+                Err(e) => {
+                    error = Some(e.context(format!("block {height}")));
+                    break;
+                }
+            }
+            last = Some(height);
+            let mut lock = scanner.lock().unwrap();
+            lock.prog = (height - from + 1) as f32 / (to - from + 1) as f32 * 100.0;
+            lock.msg = format!(
+                "Scanning block {height} ({}/{})",
+                height - from + 1,
+                to - from + 1
+            );
+        }
+        // the blocks scanned before an error are not scanned again, their payouts are kept
+        if let Some(last) = last {
+            let report = Reconciliation::new(
+                &gupax_p2pool_api.lock().unwrap().payout_ord,
+                &found,
+                from..=last,
+            );
+            Self::backfill(gupax_p2pool_api, &report.missing);
+            let mut lock = scanner.lock().unwrap();
+            lock.last_height = Some(last);
+            match error {
+                Some(e) => {
+                    lock.report = Some(report);
+                    Err(e)
+                }
+                None => Ok(report),
+            }
+        } else {
+            Err(error.unwrap_or_else(|| anyhow::anyhow!("no block scanned")))
+        }
+    }
+
+    // Check on chain the payouts of [heights] reported missing by another source, like an observer.
+    // Only the payouts found in the coinbase with the view key are added to the log, with the amount found on chain.
+    pub fn verify(
+        scanner: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        address: MoneroAddress,
+        rpc: SocketAddr,
+        heights: Vec<u64>,
+    ) {
+        let mut lock = scanner.lock().unwrap();
+        if lock.scanning {
+            return;
+        }
+        let Some(view_key) = lock.view_key.clone() else {
+            return;
+        };
+        lock.scanning = true;
+        lock.prog = 0.0;
+        lock.msg = format!("Connecting to the local node [{rpc}]...");
+        drop(lock);
+        info!("Spawning payout verification thread...");
+        spawn(enc!((scanner, gupax_p2pool_api) move || {
+            match Self::verify_heights(&scanner, &gupax_p2pool_api, &address, &view_key, rpc, &heights) {
+                Ok(added) => {
+                    scanner.lock().unwrap().msg = format!(
+                        "{} of the {} reported payouts found on chain and added to the log",
+                        added.len(),
+                        heights.len()
+                    );
+                }
+                Err(e) => {
+                    warn!("Payout Scanner | Verification ... FAIL ... {e}");
+                    scanner.lock().unwrap().msg = format!("Verification failed: {e}");
+                }
+            }
+            let mut lock = scanner.lock().unwrap();
+            lock.scanning = false;
+            lock.prog = 100.0;
+        }));
+    }
+
+    #[tokio::main]
+    pub async fn verify_heights(
+        scanner: &Arc<Mutex<Self>>,
+        gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+        address: &MoneroAddress,
+        view_key: &ViewKey,
+        rpc: SocketAddr,
+        heights: &[u64],
+    ) -> anyhow::Result<Vec<ChainPayout>> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(TIMEOUT_GET_BLOCK))
+            .build()?;
+        let (info, _) = GetInfo::request(&client, rpc.ip(), rpc.port()).await?;
+        if !info.result.synchronized {
+            anyhow::bail!("the local node is not synchronized");
+        }
+        info!(
+            "Payout Scanner | Verifying {} reported payouts...",
+            heights.len()
+        );
+        let mut found = vec![];
+        let mut error = None;
+        for (i, height) in heights.iter().enumerate() {
+            match Self::scan_block(&client, rpc, *height, address, view_key).await {
+                Ok(payout) => found.extend(payout),
+                Err(e) => {
+                    error = Some(e.context(format!("block {height}")));
+                    break;
+                }
+            }
+            let mut lock = scanner.lock().unwrap();
+            lock.prog = (i + 1) as f32 / heights.len() as f32 * 100.0;
+            lock.msg = format!("Verifying block {height} ({}/{})", i + 1, heights.len());
+        }
+        // a payout logged since it was reported is not added twice
+        let logged: Vec<u64> = gupax_p2pool_api
+            .lock()
+            .unwrap()
+            .payout_ord
+            .blocks()
+            .collect();
+        found.retain(|p| !logged.contains(&p.height));
+        // the payouts found before an error are kept
+        Self::backfill(gupax_p2pool_api, &found);
+        match error {
+            Some(e) => Err(e),
+            None => Ok(found),
+        }
+    }
+
+    // Payout to the address in the coinbase of the block.
+    async fn scan_block(
+        client: &Client,
+        rpc: SocketAddr,
+        height: u64,
+        address: &MoneroAddress,
+        view_key: &ViewKey,
+    ) -> anyhow::Result<Option<ChainPayout>> {
+        let block = GetBlock::request(client, rpc, height).await?.result;
+        let json: BlockJson = serde_json::from_str(&block.json)?;
+        Ok(json.miner_tx.payout(address, view_key).map(|amount| {
+            info!("Payout Scanner | Found a payout of {amount} XMR in block {height}");
+            ChainPayout {
+                height: block.block_header.height,
+                timestamp: block.block_header.timestamp,
+                amount,
+            }
+        }))
+    }
+
+    // Add the payouts missed by Gupax to the log, like if they were read from the P2Pool console.
+    // They must have been found on chain.
+    fn backfill(gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>, missing: &[ChainPayout]) {
+        let mut api = gupax_p2pool_api.lock().unwrap();
+        for payout in missing {
+            let date = payout.date();
+            let block = HumanNumber::from_u64(payout.height);
+            let formatted_log_line = GupaxP2poolApi::format_payout(&date, &payout.amount, &block);
+            api.add_payout(&formatted_log_line, date, payout.amount, block);
+            if let Err(e) = api.write_to_all_files(&formatted_log_line) {
+                error!("Payout Scanner | GupaxP2poolApi write error: {e}");
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::http_mock::mock_http;
+    use crate::utils::view_key::test::{ADDRESS, VIEW_KEY};
+
+    // [get_block] answers in the format of monerod, with coinbase transactions made for the test wallet.
+    // The fields not used are trimmed.
+    // Block 3000010 pays the wallet in the output 1 (view tag).
+    // Block 3000011 does not pay the wallet.
+    // Block 3000012 pays the wallet in the output 0 (no view tag).
+    const BLOCK_3000010: &str = r#"{"id":"0","jsonrpc":"2.0","result":{"block_header":{"height":3000010,"timestamp":1700000000,"reward":601234567897},"json":"{\n  \"major_version\": 16, \n  \"minor_version\": 16, \n  \"timestamp\": 1700000000, \n  \"miner_tx\": {\n    \"version\": 2, \n    \"unlock_time\": 3000070, \n    \"vin\": [ {\n        \"gen\": {\n          \"height\": 3000010\n        }\n      }\n    ], \n    \"vout\": [ {\n        \"amount\": 600000000000, \n        \"target\": {\n          \"tagged_key\": {\n            \"key\": \"2d660ee516029a10338f60822d5e6bca1f8ab91db3f2ae140b87254face61b13\", \n            \"view_tag\": \"1d\"\n          }\n        }\n      }, {\n        \"amount\": 1234567897, \n        \"target\": {\n          \"tagged_key\": {\n            \"key\": \"44e60d20df904994e7b38e71cf82859b6daac2462d0bb6b04a67a848b6282171\", \n            \"view_tag\": \"7a\"\n          }\n        }\n      }\n    ], \n    \"extra\": [ 1, 96, 85, 246, 56, 106, 249, 82, 124, 141, 15, 166, 202, 245, 242, 224, 144, 35, 183, 176, 29, 239, 45, 40, 111, 94, 7, 213, 70, 141, 170, 78, 86, 2, 8, 0, 0, 0, 0, 0, 0, 0, 42\n    ], \n    \"rct_signatures\": {\n      \"type\": 0\n    }\n  }, \n  \"tx_hashes\": [ ]\n}","status":"OK","untrusted":false}}"#;
+    const BLOCK_3000011: &str = r#"{"id":"0","jsonrpc":"2.0","result":{"block_header":{"height":3000011,"timestamp":1700000120,"reward":1200000000000},"json":"{\n  \"major_version\": 16, \n  \"minor_version\": 16, \n  \"timestamp\": 1700000120, \n  \"miner_tx\": {\n    \"version\": 2, \n    \"unlock_time\": 3000071, \n    \"vin\": [ {\n        \"gen\": {\n          \"height\": 3000011\n        }\n      }\n    ], \n    \"vout\": [ {\n        \"amount\": 600000000000, \n        \"target\": {\n          \"tagged_key\": {\n            \"key\": \"14d8a97907029187767e8b17194be0ad8dedf078f5b2391e7015310360cab0ca\", \n            \"view_tag\": \"80\"\n          }\n        }\n      }, {\n        \"amount\": 600000000000, \n        \"target\": {\n          \"tagged_key\": {\n            \"key\": \"7a68575115ab7d900618185032d7167e7e457807c12ace022baa485e9bf4e53b\", \n            \"view_tag\": \"73\"\n          }\n        }\n      }\n    ], \n    \"extra\": [ 1, 248, 188, 59, 202, 146, 162, 237, 180, 199, 188, 19, 155, 100, 49, 85, 55, 22, 220, 22, 76, 136, 60, 38, 143, 97, 42, 40, 221, 91, 194, 27, 165, 2, 8, 0, 0, 0, 0, 0, 0, 0, 42\n    ], \n    \"rct_signatures\": {\n      \"type\": 0\n    }\n  }, \n  \"tx_hashes\": [ ]\n}","status":"OK","untrusted":false}}"#;
+    const BLOCK_3000012: &str = r#"{"id":"0","jsonrpc":"2.0","result":{"block_header":{"height":3000012,"timestamp":1700000240,"reward":601234567892},"json":"{\n  \"major_version\": 14, \n  \"minor_version\": 14, \n  \"timestamp\": 1700000240, \n  \"miner_tx\": {\n    \"version\": 2, \n    \"unlock_time\": 3000072, \n    \"vin\": [ {\n        \"gen\": {\n          \"height\": 3000012\n        }\n      }\n    ], \n    \"vout\": [ {\n        \"amount\": 1234567892, \n        \"target\": {\n          \"key\": \"ab4cb874b3d3adcc7a1b0b474a24388e4c6a9ebd5f4c02391beab2e2f76e7d04\"\n        }\n      }, {\n        \"amount\": 600000000000, \n        \"target\": {\n          \"key\": \"9feff45d42a32b538dda544c38d91475bbd9d3ee8d7edb430c5a7cbc80a13552\"\n        }\n      }\n    ], \n    \"extra\": [ 1, 180, 180, 176, 220, 4, 156, 24, 121, 140, 244, 59, 28, 45, 152, 65, 176, 73, 58, 36, 79, 245, 3, 120, 253, 69, 140, 217, 115, 226, 226, 195, 224, 2, 8, 0, 0, 0, 0, 0, 0, 0, 42\n    ], \n    \"rct_signatures\": {\n      \"type\": 0\n    }\n  }, \n  \"tx_hashes\": [ ]\n}","status":"OK","untrusted":false}}"#;
+
+    fn miner_tx(block: &str) -> MinerTx {
+        let block: GetBlock = serde_json::from_str(block).unwrap();
+        serde_json::from_str::<BlockJson>(&block.result.json)
+            .unwrap()
+            .miner_tx
+    }
+
+    #[test]
+    fn coinbase_payout() {
+        let address: MoneroAddress = ADDRESS.parse().unwrap();
+        let view_key: ViewKey = VIEW_KEY.parse().unwrap();
+        assert_eq!(
+            miner_tx(BLOCK_3000010).payout(&address, &view_key),
+            Some(AtomicUnit::from_u64(1234567897))
+        );
+        assert_eq!(miner_tx(BLOCK_3000011).payout(&address, &view_key), None);
+        assert_eq!(
+            miner_tx(BLOCK_3000012).payout(&address, &view_key),
+            Some(AtomicUnit::from_u64(1234567892))
+        );
+        // another wallet does not see the payouts
+        let other: ViewKey = "0100000000000000000000000000000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(miner_tx(BLOCK_3000010).payout(&address, &other), None);
+    }
+
+    #[test]
+    fn tx_extra_public_key() {
+        let mut tx = miner_tx(BLOCK_3000010);
+        let key = tx.public_key().unwrap();
+        assert_eq!(key[..3], [96, 85, 246]);
+        // fields before the public key are skipped
+        let mut extra = vec![TX_EXTRA_NONCE, 2, 7, 7, TX_EXTRA_MERGE_MINING, 1, 9];
+        extra.extend_from_slice(&tx.extra);
+        tx.extra = extra;
+        assert_eq!(tx.public_key(), Some(key));
+        tx.extra = vec![TX_EXTRA_NONCE, 40, 1];
+        assert_eq!(tx.public_key(), None);
+        tx.extra = vec![TX_EXTRA_PUBKEY, 1, 2];
+        assert_eq!(tx.public_key(), None);
+    }
+
+    #[test]
+    fn reconcile_payouts() {
+        let mut log = PayoutOrd::from_vec(vec![]);
+        // verified
+        log.push_raw("2023-11-14 23:13:20.0000", 1234567897, 3000010);
+        // not on chain
+        log.push_raw("2023-11-14 23:15:20.0000", 1, 3000011);
+        // before the scanned blocks
+        log.push_raw("2023-11-01 00:00:00.0000", 1, 2990000);
+        let found = vec![
+            ChainPayout {
+                height: 3000010,
+                timestamp: 1700000000,
+                amount: AtomicUnit::from_u64(1234567897),
+            },
+            ChainPayout {
+                height: 3000012,
+                timestamp: 1700000240,
+                amount: AtomicUnit::from_u64(1234567892),
+            },
+        ];
+        let report = Reconciliation::new(&log, &found, 3000010..=3000012);
+        assert_eq!(report.verified, found[..1]);
+        assert_eq!(report.missing, found[1..]);
+        assert_eq!(report.unconfirmed, vec![3000011]);
+    }
+
+    // Start a fake local monerod at the height 3000013 answering with the recorded blocks.
+    // The block [failing] can not be read.
+    fn mock_node(failing: Option<&'static str>) -> SocketAddr {
+        mock_http(move |request| {
+            let body = if failing.is_some_and(|height| request.body.contains(height)) {
+                return ("500 Internal Server Error", String::new());
+            } else if request.body.contains("get_info") {
+                r#"{"id":"0","jsonrpc":"2.0","result":{"height":3000013,"nettype":"mainnet","restricted":false,"synchronized":true}}"#
+            } else if request.body.contains("3000010") {
+                BLOCK_3000010
+            } else if request.body.contains("3000011") {
+                BLOCK_3000011
+            } else {
+                BLOCK_3000012
+            };
+            ("200 OK", body.to_string())
+        })
+    }
+
+    #[test]
+    fn scan_and_backfill() {
+        let dir = std::env::temp_dir().join(format!("gupax_payout_scan_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        GupaxP2poolApi::create_all_files(&dir).unwrap();
+        let mut api = GupaxP2poolApi::new();
+        api.fill_paths(&dir);
+        api.read_all_files_and_update().unwrap();
+        // the payout of the block 3000010 was seen by Gupax
+        let line = GupaxP2poolApi::format_payout(
+            "2023-11-14 23:13:20.0000",
+            &AtomicUnit::from_u64(1234567897),
+            &HumanNumber::from_u64(3000010),
+        );
+        api.add_payout(
+            &line,
+            "2023-11-14 23:13:20.0000".to_string(),
+            AtomicUnit::from_u64(1234567897),
+            HumanNumber::from_u64(3000010),
+        );
+        api.write_to_all_files(&line).unwrap();
+        let api = Arc::new(Mutex::new(api));
+
+        let scanner = Arc::new(Mutex::new(PayoutScanner {
+            // only the recorded blocks are scanned
+            last_height: Some(3000009),
+            ..Default::default()
+        }));
+        let report = PayoutScanner::scan(
+            &scanner,
+            &api,
+            &ADDRESS.parse().unwrap(),
+            &VIEW_KEY.parse().unwrap(),
+            mock_node(None),
+        )
+        .unwrap();
+        assert_eq!((report.from, report.to), (3000010, 3000012));
+        assert_eq!(report.verified.len(), 1);
+        assert_eq!(report.missing.len(), 1);
+        assert!(report.unconfirmed.is_empty());
+        assert_eq!(scanner.lock().unwrap().last_height, Some(3000012));
+
+        // the missed payout is in the log, on disk too
+        let api = api.lock().unwrap();
+        assert_eq!(api.payout_u64, 2);
+        assert_eq!(api.xmr.to_u64(), 1234567897 + 1234567892);
+        assert!(api.log.contains("0.001234567892 XMR | Block 3,000,012"));
+        let mut from_disk = GupaxP2poolApi::new();
+        from_disk.fill_paths(&dir);
+        from_disk.read_all_files_and_update().unwrap();
+        assert_eq!(from_disk.payout_u64, 2);
+        assert_eq!(
+            from_disk.payout_ord.blocks().collect::<Vec<_>>(),
+            vec![3000010, 3000012]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn scan_stopped_by_an_error() {
+        let dir =
+            std::env::temp_dir().join(format!("gupax_payout_scan_err_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        GupaxP2poolApi::create_all_files(&dir).unwrap();
+        let mut api = GupaxP2poolApi::new();
+        api.fill_paths(&dir);
+        api.read_all_files_and_update().unwrap();
+        let api = Arc::new(Mutex::new(api));
+        let scanner = Arc::new(Mutex::new(PayoutScanner {
+            last_height: Some(3000009),
+            ..Default::default()
+        }));
+        let scan = |node| {
+            PayoutScanner::scan(
+                &scanner,
+                &api,
+                &ADDRESS.parse().unwrap(),
+                &VIEW_KEY.parse().unwrap(),
+                node,
+            )
+        };
+        // the block 3000011 fails, the payout of the block 3000010 is kept
+        let err = scan(mock_node(Some("3000011"))).unwrap_err();
+        assert!(format!("{err:#}").contains("block 3000011"));
+        assert_eq!(scanner.lock().unwrap().last_height, Some(3000010));
+        let report = scanner.lock().unwrap().report.take().unwrap();
+        assert_eq!((report.from, report.to), (3000010, 3000010));
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(api.lock().unwrap().payout_u64, 1);
+        // the next scan continues after it
+        let report = scan(mock_node(None)).unwrap();
+        assert_eq!((report.from, report.to), (3000011, 3000012));
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(api.lock().unwrap().payout_u64, 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn verify_reported_payouts() {
+        let dir = std::env::temp_dir().join(format!("gupax_payout_verify_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        GupaxP2poolApi::create_all_files(&dir).unwrap();
+        let mut api = GupaxP2poolApi::new();
+        api.fill_paths(&dir);
+        api.read_all_files_and_update().unwrap();
+        let api = Arc::new(Mutex::new(api));
+        let scanner = Arc::new(Mutex::new(PayoutScanner::default()));
+        // the block 3000011 does not pay the wallet, whatever the source reported
+        let added = PayoutScanner::verify_heights(
+            &scanner,
+            &api,
+            &ADDRESS.parse().unwrap(),
+            &VIEW_KEY.parse().unwrap(),
+            mock_node(None),
+            &[3000011, 3000012],
+        )
+        .unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].height, 3000012);
+        let lock = api.lock().unwrap();
+        assert_eq!(lock.payout_u64, 1);
+        // the amount found on chain
+        assert_eq!(lock.xmr.to_u64(), 1234567892);
+        drop(lock);
+        // reported again, it is already in the log
+        let added = PayoutScanner::verify_heights(
+            &scanner,
+            &api,
+            &ADDRESS.parse().unwrap(),
+            &VIEW_KEY.parse().unwrap(),
+            mock_node(None),
+            &[3000012],
+        )
+        .unwrap();
+        assert!(added.is_empty());
+        assert_eq!(api.lock().unwrap().payout_u64, 1);
+        // the scan of the scanner does not move
+        assert_eq!(scanner.lock().unwrap().last_height, None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
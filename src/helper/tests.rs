@@ -24,6 +24,7 @@ mod test {
     use crate::helper::xrig::xmrig::ImgXmrig;
     use crate::helper::xrig::xmrig_proxy::{ImgProxy, PubXmrigProxyApi};
     use crate::helper::xvb::algorithm::Algorithm;
+    use crate::helper::xvb::split::XvbWorker;
     use crate::helper::{
         Helper, Process, ProcessName, ProcessState,
         p2pool::{PrivP2poolLocalApi, PrivP2poolNetworkApi},
@@ -198,6 +199,7 @@ Uptime         = 0h 2m 4s
             average_effort: 100.000,
             current_effort: 200.000,
             connections: 1234,
+            workers: vec![],
         };
         let network = PrivP2poolNetworkApi {
             difficulty: 300_000_000_000,
@@ -298,6 +300,7 @@ Uptime         = 0h 2m 4s
             &img_p2pool,
             &proxy_state,
             &p2pool_state,
+            &[],
         );
         println!("{:#?}", process);
         assert!(process.lock().unwrap().state == ProcessState::NotMining);
@@ -317,6 +320,7 @@ Uptime         = 0h 2m 4s
             &img_p2pool,
             &proxy_state,
             &p2pool_state,
+            &[],
         );
         assert!(process.lock().unwrap().state == ProcessState::Alive);
     }
@@ -566,6 +570,7 @@ Uptime         = 0h 2m 4s
         gui_api_xvb.lock().unwrap().stats_priv.runtime_mode = RuntimeMode::ManualXvb;
         gui_api_xvb.lock().unwrap().stats_priv.runtime_manual_amount = 1000.0;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -584,6 +589,7 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 1000.0);
@@ -617,6 +623,7 @@ Uptime         = 0h 2m 4s
         gui_api_xvb.lock().unwrap().stats_priv.runtime_mode = RuntimeMode::ManualP2pool;
         gui_api_xvb.lock().unwrap().stats_priv.runtime_manual_amount = 1000.0;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -635,6 +642,7 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 9000.0);
@@ -673,6 +681,7 @@ Uptime         = 0h 2m 4s
             .stats_priv
             .runtime_manual_donation_level = RuntimeDonationLevel::Donor;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -691,6 +700,7 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 1000.0);
@@ -724,6 +734,7 @@ Uptime         = 0h 2m 4s
         gui_api_xmrig.lock().unwrap().hashrate_raw_15m = 20000.0;
         gui_api_xvb.lock().unwrap().stats_priv.runtime_mode = RuntimeMode::Auto;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -742,6 +753,7 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 10000.0);
@@ -750,6 +762,7 @@ Uptime         = 0h 2m 4s
         gui_api_xmrig.lock().unwrap().hashrate_raw_15m = 10000.0;
         gui_api_xvb.lock().unwrap().stats_priv.runtime_mode = RuntimeMode::Auto;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -768,6 +781,7 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 1000.0);
@@ -802,6 +816,7 @@ Uptime         = 0h 2m 4s
         gui_api_xvb.lock().unwrap().stats_priv.runtime_mode = RuntimeMode::Hero;
         gui_api_xvb.lock().unwrap().use_p2pool_sidechain_hr = true;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -820,12 +835,14 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 18460.7);
 
         gui_api_p2pool.lock().unwrap().sidechain_ehr = 25000.0;
 
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
         let algo = Algorithm::new(
             &client,
             &pub_api,
@@ -844,11 +861,82 @@ Uptime         = 0h 2m 4s
             &xmrig_img,
             &p2pool_img,
             &p2pool_process,
+            &xvb_worker,
         );
 
         assert_eq!(algo.stats.target_donation_hashrate, 20000.0);
     }
 
+    #[test]
+    fn test_share_expiry() {
+        use crate::disk::share_history::{PplnsWindow, ShareHistory};
+        use crate::disk::state::P2poolChain;
+        use crate::utils::monero_address::Network;
+        let client = client();
+        let pub_api = Arc::new(Mutex::new(PubXvbApi::new()));
+        let gui_api_xvb = Arc::new(Mutex::new(PubXvbApi::new()));
+        let gui_api_xmrig = Arc::new(Mutex::new(PubXmrigApi::new()));
+        let gui_api_xp = Arc::new(Mutex::new(PubXmrigProxyApi::new()));
+        let gui_api_p2pool = Arc::new(Mutex::new(PubP2poolApi::new()));
+        let token_xmrig = "12345678";
+        let state_p2pool = P2pool::default();
+        let proxy_img = Arc::new(Mutex::new(ImgProxy::new()));
+        let p2pool_img = Arc::new(Mutex::new(ImgP2pool::new()));
+        let xmrig_img = Arc::new(Mutex::new(ImgXmrig::new()));
+        let p2pool_process = Arc::new(Mutex::new(Process::new(
+            ProcessName::P2pool,
+            String::new(),
+            PathBuf::new(),
+        )));
+        let time_donated = Arc::new(Mutex::new(u64::default()));
+        let xvb_worker = Arc::new(Mutex::new(XvbWorker::default()));
+        gui_api_xmrig.lock().unwrap().hashrate_raw_15m = 10000.0;
+        let algo = |share: u32| {
+            Algorithm::new(
+                &client,
+                &pub_api,
+                &gui_api_xvb,
+                &gui_api_xmrig,
+                &gui_api_xp,
+                &gui_api_p2pool,
+                token_xmrig,
+                &state_p2pool,
+                share,
+                &time_donated,
+                "test_rig",
+                false,
+                5,
+                &proxy_img,
+                &xmrig_img,
+                &p2pool_img,
+                &p2pool_process,
+                &xvb_worker,
+            )
+            .is_share_fulfilled()
+        };
+        // one share recorded in a Nano window of 2160 blocks of 30 seconds
+        let history = ShareHistory::from_file_content("0,1000,1000000,100.0,Nano,mainnet\n");
+        let window = |sidechain_height| {
+            PplnsWindow::new(
+                &history,
+                &P2poolChain::Nano,
+                Network::Mainnet,
+                sidechain_height,
+                2160,
+                30,
+            )
+        };
+        // leaves the window in 30 minutes
+        gui_api_p2pool.lock().unwrap().pplns_window = window(3100);
+        assert!(algo(1));
+        assert!(!algo(0));
+        // leaves the window in 30 seconds, before the end of the algorithm
+        gui_api_p2pool.lock().unwrap().pplns_window = window(3159);
+        assert!(!algo(1));
+        // another share in the window was not recorded, its expiry is unknown
+        assert!(algo(2));
+    }
+
     #[test]
     fn custom_args_p2pool() {
         // check that custom args are parsed correctly.
@@ -875,4 +963,353 @@ Uptime         = 0h 2m 4s
             args
         );
     }
+
+    #[test]
+    fn crawler_node_filter() {
+        use crate::components::node::{NodeHealth, RemoteNode};
+        use crate::helper::crawler::{CrawlerRequirements, NodeFilter};
+
+        let node = |ip: &str, restricted: Option<bool>| RemoteNode {
+            ip: ip.parse().unwrap(),
+            rpc: 18081,
+            zmq: 18083,
+            ms: 10,
+            health: restricted.map(|restricted| NodeHealth {
+                restricted,
+                ..Default::default()
+            }),
+        };
+        let nodes = [
+            node("1.2.3.4", Some(false)),
+            node("1.2.200.1", Some(false)),
+            node("5.6.7.8", Some(true)),
+            node("9.9.9.9", None),
+            node("10.0.0.1", Some(false)),
+        ];
+        let ips = |filter: &NodeFilter| {
+            filter
+                .select(nodes.iter())
+                .iter()
+                .map(|n| n.ip.to_string())
+                .collect::<Vec<_>>()
+        };
+        // no rules
+        let mut settings = CrawlerRequirements::default();
+        assert_eq!(ips(&NodeFilter::new(&settings)).len(), 5);
+        // denylist and spy nodes file
+        let spy_file =
+            std::env::temp_dir().join(format!("gupax_test_spy_nodes_{}.txt", std::process::id()));
+        std::fs::write(&spy_file, "# spy nodes\n10.0.0.0/8\n").unwrap();
+        settings.denylist = "9.9.9.9, 5.6.0.0/16".to_string();
+        settings.spy_nodes_path = spy_file.to_str().unwrap().to_string();
+        assert_eq!(ips(&NodeFilter::new(&settings)), ["1.2.3.4", "1.2.200.1"]);
+        // one node per /16
+        settings.diversity = true;
+        assert_eq!(ips(&NodeFilter::new(&settings)), ["1.2.3.4"]);
+        // the prefix table puts the nodes in different ASN
+        let prefix_table = std::env::temp_dir().join(format!(
+            "gupax_test_prefix_table_{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&prefix_table, "1.2.0.0/24 AS1\n1.2.128.0/17 AS2\n").unwrap();
+        settings.prefix_table_path = prefix_table.to_str().unwrap().to_string();
+        assert_eq!(ips(&NodeFilter::new(&settings)), ["1.2.3.4", "1.2.200.1"]);
+        // allowlist, invalid lists are ignored
+        settings = CrawlerRequirements {
+            allowlist: "5.6.7.8, 9.9.9.9".to_string(),
+            denylist: "not an ip".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(ips(&NodeFilter::new(&settings)), ["5.6.7.8", "9.9.9.9"]);
+        // checked before the node is contacted
+        let filter = NodeFilter::new(&settings);
+        assert!(filter.accepts_ip("9.9.9.9".parse().unwrap()));
+        assert!(!filter.accepts_ip("1.2.3.4".parse().unwrap()));
+        // restricted or unchecked RPC
+        settings = CrawlerRequirements {
+            require_full_rpc: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ips(&NodeFilter::new(&settings)),
+            ["1.2.3.4", "1.2.200.1", "10.0.0.1"]
+        );
+        std::fs::remove_file(&spy_file).unwrap();
+        std::fs::remove_file(&prefix_table).unwrap();
+    }
+
+    #[test]
+    fn update_pub_p2pool_workers_from_stratum() {
+        use crate::helper::PubP2poolApi;
+        let stratum = r#"{"hashrate_15m":1200,"hashrate_1h":1100,"hashrate_24h":1000,"total_hashes":123456,"shares_found":2,"shares_failed":0,"average_effort":80.5,"current_effort":20.1,"connections":3,"incoming_connections":3,"workers":["127.0.0.1:51234,3600,12000,1000,rig1","[::1]:51235,60,8000,200,proxy","192.168.1.20:40000,10,5000,100,laptop,2"]}"#;
+        let local = PrivP2poolLocalApi::from_str(stratum).unwrap();
+        let mut public = PubP2poolApi::new();
+        PubP2poolApi::update_from_local(&mut public, local);
+        assert_eq!(public.workers.len(), 3);
+        assert_eq!(public.workers[0].ip, "127.0.0.1:51234");
+        assert_eq!(public.workers[0].uptime, 3600);
+        assert_eq!(public.workers[0].difficulty, 12000);
+        assert_eq!(public.workers[0].hashrate, 1000);
+        assert_eq!(public.workers[0].name, "rig1");
+        // commas in the name are kept
+        assert_eq!(public.workers[2].name, "laptop,2");
+        assert!(public.workers[0].is_local());
+        assert!(public.workers[1].is_local());
+        assert!(!public.workers[2].is_local());
+        // XMRig and XMRig-Proxy are both connected from this machine
+        assert_eq!(public.missing_local_workers(2), 0);
+        public.workers.remove(0);
+        assert_eq!(public.missing_local_workers(2), 1);
+        // the stratum API of older P2Pool versions has no workers
+        let local = PrivP2poolLocalApi::from_str(r#"{"hashrate_15m":0,"hashrate_1h":0,"hashrate_24h":0,"shares_found":0,"average_effort":0.0,"current_effort":0.0,"connections":0}"#).unwrap();
+        PubP2poolApi::update_from_local(&mut public, local);
+        assert!(public.workers.is_empty());
+        assert_eq!(public.missing_local_workers(1), 1);
+    }
+
+    #[test]
+    fn simulate_auto_mode() {
+        use crate::helper::xvb::rounds::XvbRound;
+        use crate::helper::xvb::simulation::{Simulation, Trace};
+        // 20 kH/s on P2Pool Nano, a share needs about 140 H/s.
+        let simulation = Simulation::new(Trace::constant(20_000.0, 9_000_000), RuntimeMode::Auto);
+        let report = simulation.run(2 * 24 * 3600);
+        assert_eq!(report.cycles, 2 * 24 * 60);
+        assert_eq!(report.rounds.len(), 2 * 24 - 1);
+        // the first share needs some minutes, it is kept afterwards.
+        assert!(report.share_retention() > 0.99, "{report}");
+        // the 24h average is reached during the first day, the second day is spent in the VIP Donor round.
+        assert!(
+            report.rounds[24..]
+                .iter()
+                .all(|r| *r == Some(XvbRound::DonorVip))
+        );
+        // a switch to XvB and back to P2Pool every cycle at most
+        assert!(report.switches <= 2 * report.cycles as u32);
+        // the same inputs give the same decisions
+        assert_eq!(simulation.run(2 * 24 * 3600), report);
+    }
+
+    #[test]
+    fn simulate_hero_mode_with_recorded_trace() {
+        use crate::helper::xvb::simulation::{Simulation, Trace};
+        // the hashrate drops after 6 hours, the difficulty rises after 12 hours.
+        let trace = Trace::from_csv(
+            "# seconds,hashrate,difficulty
+            0,20000,9000000
+            21600,5000,9000000
+            43200,5000,18000000",
+        )
+        .unwrap();
+        assert!(Trace::from_csv("0,20000").is_err());
+        assert!(Trace::from_csv("").is_err());
+        let report = Simulation::new(trace, RuntimeMode::Hero).run(24 * 3600);
+        // the share found before the difficulty doubled expires before the next one is found.
+        assert!(report.share_retention() > 0.98, "{report}");
+        // everything above the minimum to keep a share goes to XvB.
+        assert!(report.donated_ratio() > 0.9, "{report}");
+    }
+
+    #[test]
+    fn simulate_predictive_mode() {
+        use crate::helper::xvb::rounds::XvbRound;
+        use crate::helper::xvb::simulation::{Simulation, Trace};
+        let trace = Trace::constant(20_000.0, 9_000_000);
+        let auto = Simulation::new(trace.clone(), RuntimeMode::Auto).run(2 * 24 * 3600);
+        let predictive = Simulation::new(trace, RuntimeMode::Predictive).run(2 * 24 * 3600);
+        assert!(predictive.share_retention() > 0.99, "{predictive}");
+        // the same round once the 24h average is reached, with far less switches.
+        assert!(
+            predictive.rounds[24..]
+                .iter()
+                .all(|r| *r == Some(XvbRound::DonorVip))
+        );
+        assert!(
+            predictive.switches * 10 < auto.switches,
+            "Auto:\n{auto}Predictive:\n{predictive}"
+        );
+    }
+
+    #[test]
+    fn simulate_thread_split() {
+        use crate::disk::state::XvbSplit;
+        use crate::helper::xvb::rounds::XvbRound;
+        use crate::helper::xvb::simulation::{Simulation, Trace};
+        let mut simulation =
+            Simulation::new(Trace::constant(20_000.0, 9_000_000), RuntimeMode::Auto);
+        simulation.split = XvbSplit::Threads;
+        simulation.threads = 8;
+        let report = simulation.run(2 * 24 * 3600);
+        assert!(report.share_retention() > 0.99, "{report}");
+        // 4 threads of 2.5 kH/s are enough for the VIP Donor round, without switching the pool.
+        assert!(
+            report.rounds[24..]
+                .iter()
+                .all(|r| *r == Some(XvbRound::DonorVip))
+        );
+        assert_eq!(report.switches, 0, "{report}");
+        // a miner that can't be split falls back to the split by time.
+        simulation.threads = 0;
+        assert!(simulation.run(3600).switches > 0);
+    }
+
+    #[test]
+    fn donated_seconds_split_by_threads() {
+        use crate::disk::state::XvbPoolEndpoint;
+        use crate::helper::xvb::{PubXvbApi, donated_seconds, nodes::Pool};
+        use crate::utils::constants::XVB_TIME_ALGO;
+        let mut api = PubXvbApi::new();
+        api.current_pool = Some(Pool::P2pool(3333));
+        assert_eq!(donated_seconds(60, &api, XVB_TIME_ALGO), 0);
+        // 3 threads out of 8 on XvB
+        api.worker_pool = Some(Pool::XvB(XvbPoolEndpoint::europe()));
+        assert_eq!(donated_seconds(80, &api, XVB_TIME_ALGO * 3 / 8), 30);
+        // the whole time of the main miner
+        api.current_pool = Some(Pool::XvB(XvbPoolEndpoint::europe()));
+        api.worker_pool = None;
+        assert_eq!(donated_seconds(80, &api, 0), 80);
+    }
+
+    #[test]
+    fn simulate_pool_failover() {
+        use crate::disk::state::XvbPoolEndpoint;
+        use crate::helper::xvb::nodes::Pool;
+        use crate::helper::xvb::rounds::XvbRound;
+        use crate::helper::xvb::simulation::{Outage, Simulation, Trace};
+        let mut simulation =
+            Simulation::new(Trace::constant(20_000.0, 9_000_000), RuntimeMode::Auto);
+        let healthy = simulation.run(2 * 24 * 3600);
+        // the European pool is down for 6 hours during the second day.
+        simulation.outages = vec![Outage {
+            pool: Pool::XvB(XvbPoolEndpoint::europe()),
+            from: 30 * 3600,
+            to: 36 * 3600,
+        }];
+        let report = simulation.run(2 * 24 * 3600);
+        // the time waiting for the connection is sent again on the North American pool.
+        assert!(
+            report.rounds[24..]
+                .iter()
+                .all(|r| *r == Some(XvbRound::DonorVip))
+        );
+        assert!(
+            report.hashes_xvb > healthy.hashes_xvb * 0.99,
+            "Healthy:\n{healthy}European pool down:\n{report}"
+        );
+        // at most the grace of the connection is lost every cycle.
+        assert!(report.hashes_lost <= 20_000.0 * 10.0 * 6.0 * 60.0);
+        // both pools are down, the hashrate goes back to P2Pool.
+        simulation.outages.push(Outage {
+            pool: Pool::XvB(XvbPoolEndpoint::north_america()),
+            from: 30 * 3600,
+            to: 36 * 3600,
+        });
+        let report = simulation.run(2 * 24 * 3600);
+        assert!(
+            report.share_retention() > 0.99,
+            "Both pools down:\n{report}"
+        );
+        assert!(report.hashes_lost <= 20_000.0 * 20.0 * 6.0 * 60.0);
+    }
+
+    #[test]
+    fn xvb_pools_local_listeners() {
+        use crate::disk::state::{Xvb, XvbPoolEndpoint};
+        use crate::helper::xvb::nodes::Pool;
+        use crate::regex::detect_pool_xmrig;
+        use std::net::TcpListener;
+        // a local listener open during the whole test stands in for the live pool.
+        // The dead pool is in TEST-NET-1 (RFC 5737), never routed, the ping can only time out or fail.
+        let live_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = |label: &str, host: &str, port: u16| XvbPoolEndpoint {
+            label: label.to_string(),
+            host: host.to_string(),
+            port,
+            tls: false,
+        };
+        let live = endpoint(
+            "Live",
+            "127.0.0.1",
+            live_listener.local_addr().unwrap().port(),
+        );
+        let dead = endpoint("Dead", "192.0.2.1", 4247);
+        let mut state_xvb = Xvb {
+            pools: vec![dead.clone(), live.clone()],
+            ..Default::default()
+        };
+        let state_p2pool = P2pool::default();
+        let p2pool_img = Arc::new(Mutex::new(ImgP2pool::new()));
+        let p2pool_port = state_p2pool.current_port(false, &p2pool_img.lock().unwrap());
+        let fastest = |state_xvb: &Xvb| {
+            let pub_api = Arc::new(Mutex::new(PubXvbApi::new()));
+            let process_xvb = Arc::new(Mutex::new(Process::new(
+                ProcessName::Xvb,
+                String::new(),
+                PathBuf::new(),
+            )));
+            let process_p2pool = Arc::new(Mutex::new(Process::new(
+                ProcessName::P2pool,
+                String::new(),
+                PathBuf::new(),
+            )));
+            ping_pools(
+                &pub_api,
+                &process_xvb,
+                &process_p2pool,
+                &p2pool_img,
+                &state_p2pool,
+                state_xvb,
+            );
+            let pool = pub_api.lock().unwrap().stats_priv.pool.clone();
+            let state = process_xvb.lock().unwrap().state;
+            (pool, state)
+        };
+        // the dead pool is skipped
+        let (pool, state) = fastest(&state_xvb);
+        assert_eq!(pool, Pool::XvB(live.clone()));
+        assert_ne!(state, ProcessState::OfflinePoolsAll);
+        // manual selection of the dead pool goes back to P2Pool
+        state_xvb.manual_pool_enabled = true;
+        state_xvb.manual_pool = "Dead".to_string();
+        let (pool, state) = fastest(&state_xvb);
+        assert_eq!(pool, Pool::P2pool(p2pool_port));
+        assert_eq!(state, ProcessState::OfflinePoolsAll);
+        state_xvb.manual_pool = "Live".to_string();
+        assert_eq!(fastest(&state_xvb).0, Pool::XvB(live.clone()));
+        // the failover goes to the other endpoint
+        assert_eq!(
+            Pool::XvB(live.clone()).other_xvb_pool(&state_xvb.pools),
+            Some(Pool::XvB(dead.clone()))
+        );
+        assert_eq!(
+            Pool::XvB(live.clone()).other_xvb_pool(std::slice::from_ref(&live)),
+            None
+        );
+        // the miner output is recognized with the endpoint
+        let line = format!("use pool 127.0.0.1:{}", live.port);
+        assert_eq!(
+            detect_pool_xmrig(&line, 3355, p2pool_port, &state_xvb.pools),
+            Some(Pool::XvB(live))
+        );
+    }
+    #[tokio::main]
+    async fn ping_pools(
+        pub_api: &Arc<Mutex<PubXvbApi>>,
+        process_xvb: &Arc<Mutex<Process>>,
+        process_p2pool: &Arc<Mutex<Process>>,
+        p2pool_img: &Arc<Mutex<ImgP2pool>>,
+        state_p2pool: &P2pool,
+        state_xvb: &crate::disk::state::Xvb,
+    ) {
+        crate::helper::xvb::nodes::Pool::update_fastest_pool(
+            pub_api,
+            &Arc::new(Mutex::new(PubXvbApi::new())),
+            process_xvb,
+            process_p2pool,
+            p2pool_img,
+            state_p2pool,
+            state_xvb,
+        )
+        .await;
+    }
 }
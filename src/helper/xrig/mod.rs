@@ -78,6 +78,35 @@ pub async fn update_xmrig_config(
         .await?;
     anyhow::Ok(())
 }
+
+// update the number of CPU threads of xmrig.
+// xmrig started with --threads keeps them in the "*" profile of its cpu config.
+pub async fn update_xmrig_threads(
+    client: &Client,
+    api_uri: &str,
+    token: &str,
+    threads: u16,
+) -> Result<()> {
+    // get config
+    let request = client
+        .get(api_uri)
+        .header(AUTHORIZATION, ["Bearer ", token].concat());
+    let mut config = request.send().await?.json::<Value>().await?;
+    info!("replace xmrig from api url {api_uri} config with {threads} threads");
+    *config
+        .pointer_mut("/cpu/*/threads")
+        .ok_or_else(|| anyhow!("cpu/*/threads does not exist in xmrig config"))? = threads.into();
+    // send new config
+    client
+        .put(api_uri)
+        .header("Authorization", ["Bearer ", token].concat())
+        .header("Content-Type", "application/json")
+        .timeout(std::time::Duration::from_secs(5))
+        .body(config.to_string())
+        .send()
+        .await?;
+    anyhow::Ok(())
+}
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 struct Hashrate {
     total: [Option<f32>; 3],
@@ -1,5 +1,5 @@
 use crate::constants::*;
-use crate::disk::state::{P2pool, StartOptionsMode, XmrigProxy};
+use crate::disk::state::{P2pool, StartOptionsMode, XmrigProxy, XvbPoolEndpoint};
 use crate::helper::p2pool::ImgP2pool;
 use crate::helper::xrig::update_xmrig_config;
 use crate::helper::{Helper, ProcessName, ProcessSignal, ProcessState};
@@ -248,6 +248,8 @@ impl Helper {
         let process_xp = Arc::clone(&helper.lock().unwrap().xmrig_proxy);
         let process_p2pool = Arc::clone(&helper.lock().unwrap().p2pool);
         let path = path.to_path_buf();
+        // the worker mining on XvB when the hashrate is split by threads uses the same binary.
+        helper.lock().unwrap().xvb_worker.lock().unwrap().path = path.clone();
         let token = state.token.clone();
         let p2pool_state = p2pool_state.clone();
         let p2pool_img = Arc::clone(&helper.lock().unwrap().img_p2pool);
@@ -670,6 +672,7 @@ impl Helper {
             // Always update from output
             debug!("XMRig Watchdog | Starting [update_from_output()]");
             {
+                let xvb_pools = pub_api_xvb.lock().unwrap().pools.clone();
                 let process_p2pool_lock = &process_p2pool.lock().unwrap();
                 let mut process_lock = process.lock().unwrap();
                 let process_xp_lock = &process_xp.lock().unwrap();
@@ -686,6 +689,7 @@ impl Helper {
                     p2pool_img,
                     proxy_state,
                     p2pool_state,
+                    &xvb_pools,
                 );
             }
             // Send an HTTP API request
@@ -857,6 +861,9 @@ pub struct PubXmrigApi {
     pub hashrate_raw: f32,
     pub hashrate_raw_1m: f32,
     pub hashrate_raw_15m: f32,
+    // shares of the current connection, to watch the pool
+    pub accepted_raw: u64,
+    pub rejected_raw: u64,
     pub pool: Option<Pool>,
 }
 
@@ -880,6 +887,8 @@ impl PubXmrigApi {
             hashrate_raw: 0.0,
             hashrate_raw_1m: 0.0,
             hashrate_raw_15m: 0.0,
+            accepted_raw: 0,
+            rejected_raw: 0,
             pool: None,
         }
     }
@@ -912,6 +921,7 @@ impl PubXmrigApi {
         p2pool_img: &Arc<Mutex<ImgP2pool>>,
         proxy_state: &XmrigProxy,
         p2pool_state: &P2pool,
+        xvb_pools: &[XvbPoolEndpoint],
     ) {
         // 1. Take the process's current output buffer and combine it with Pub (if not empty)
         let mut output_pub = output_pub.lock().unwrap();
@@ -936,6 +946,7 @@ impl PubXmrigApi {
                     .current_ports(process_proxy.is_alive(), &proxy_img.lock().unwrap())
                     .0,
                 p2pool_state.current_port(process_p2pool.is_alive(), &p2pool_img.lock().unwrap()),
+                xvb_pools,
             ) {
                 public.pool = Some(name_pool);
             }
@@ -980,6 +991,8 @@ impl PubXmrigApi {
             hashrate_raw,
             hashrate_raw_1m,
             hashrate_raw_15m,
+            accepted_raw: private.connection.accepted as u64,
+            rejected_raw: private.connection.rejected as u64,
             ..std::mem::take(&mut *public)
         }
     }
@@ -30,7 +30,7 @@ use std::{
 };
 use tokio::spawn;
 
-use crate::disk::state::{P2pool, StartOptionsMode, XmrigProxy};
+use crate::disk::state::{P2pool, StartOptionsMode, XmrigProxy, XvbPoolEndpoint};
 use crate::helper::p2pool::ImgP2pool;
 use crate::helper::xrig::current_api_url_xrig;
 use crate::human::{HumanNumber, HumanTime};
@@ -477,6 +477,7 @@ impl Helper {
                 // Always update from output
                 // todo: check difference with xmrig
                 debug!("XMRig-Proxy Watchdog | Starting [update_from_output()]");
+                let xvb_pools = pub_api_xvb.lock().unwrap().pools.clone();
                 let process_p2pool_lock = process_p2pool.lock().unwrap();
                 let mut process_lock = process.lock().unwrap();
                 let mut pub_api_lock = pub_api.lock().unwrap();
@@ -490,6 +491,7 @@ impl Helper {
                     p2pool_img,
                     p2pool_state,
                     state,
+                    &xvb_pools,
                 );
                 drop(pub_api_lock);
                 drop(process_lock);
@@ -630,6 +632,7 @@ impl PubXmrigProxyApi {
         p2pool_img: &Arc<Mutex<ImgP2pool>>,
         p2pool_state: &P2pool,
         state: &XmrigProxy,
+        xvb_pools: &[XvbPoolEndpoint],
     ) {
         // 1. Take the process's current output buffer and combine it with Pub (if not empty)
         let mut output_pub = output_pub.lock().unwrap();
@@ -654,6 +657,7 @@ impl PubXmrigProxyApi {
                 &output_parse,
                 state.bind_port(),
                 p2pool_state.current_port(process_p2pool.is_alive(), &p2pool_img.lock().unwrap()),
+                xvb_pools,
             ) {
                 public.pool = Some(name_pool);
             }
@@ -15,8 +15,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use crate::XVB_FAILOVER_CHECK;
 use crate::XVB_MIN_TIME_SEND;
-use crate::disk::state::P2poolChain;
+use crate::disk::state::{P2poolChain, XvbGoal, XvbSplit};
 use crate::helper::Process;
 use crate::helper::p2pool::ImgP2pool;
 use crate::helper::xrig::current_api_url_xrig;
@@ -46,14 +47,26 @@ use crate::{
     XVB_ROUND_DONOR_VIP_MIN_HR, XVB_ROUND_DONOR_WHALE_MIN_HR, XVB_TIME_ALGO,
     helper::{
         p2pool::PubP2poolApi,
-        xrig::{update_xmrig_config, xmrig::PubXmrigApi},
-        xvb::{nodes::Pool, priv_stats::RuntimeMode},
+        xrig::{update_xmrig_config, update_xmrig_threads, xmrig::PubXmrigApi},
+        xvb::{
+            coordination::{Assignment, Coordinator, FollowerReport, Role},
+            failover::PoolWatch,
+            nodes::Pool,
+            priv_stats::RuntimeMode,
+            split::{XvbWorker, threads_xvb},
+        },
     },
 };
 
-use super::{PubXvbApi, SamplesAverageHour, priv_stats::RuntimeDonationLevel};
+use super::{
+    PubXvbApi, SamplesAverageHour,
+    predictive::Forecast,
+    priv_stats::{RuntimeDonationLevel, RuntimeGoal},
+};
 
 const MARGIN_EXTERNAL_HR: f32 = 0.02;
+// decisions kept for the XvB tab
+pub const XVB_DECISIONS_MAX: usize = 10;
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn algorithm(
     client: &Client,
@@ -72,6 +85,8 @@ pub(crate) async fn algorithm(
     xmrig_img: &Arc<Mutex<ImgXmrig>>,
     p2pool_img: &Arc<Mutex<ImgP2pool>>,
     p2pool_process: &Arc<Mutex<Process>>,
+    xvb_worker: &Arc<Mutex<XvbWorker>>,
+    role: Option<Role>,
 ) {
     let token_xmrig = if xp_alive {
         proxy_img.lock().unwrap().token.clone()
@@ -96,49 +111,158 @@ pub(crate) async fn algorithm(
         xmrig_img,
         p2pool_img,
         p2pool_process,
+        xvb_worker,
     );
+    match &role {
+        Some(Role::Leader(coordinator)) => algorithm.lead(coordinator),
+        Some(Role::Follower(assignment)) => algorithm.follow(assignment),
+        None => {}
+    }
     algorithm.run().await;
 }
 
-#[allow(dead_code)]
-pub struct Algorithm<'a> {
+// What the algorithm acts on: the pool the miner mines on and the time between two decisions.
+// The XvB process controls XMRig or XMRig-Proxy in real time, the simulation replaces both.
+pub(crate) trait MinerController {
+    // mine on [pool] with [address] and [rig] as user and password.
+    async fn set_pool(&self, pool: &Pool, address: &str, rig: &str) -> anyhow::Result<()>;
+    async fn sleep(&self, duration: Duration);
+    // hashrate that can be sent to P2Pool or XvB.
+    fn hashrate(&self) -> f32;
+    // CPU threads that can be split between P2Pool and XvB, 0 if the miner can only mine on one pool at a time.
+    fn threads(&self) -> u16;
+    // mine on XvB [pool] with [threads] of the CPU threads, the others stay on the pool of the miner.
+    async fn split_threads(&self, pool: &Pool, address: &str, threads: u16) -> anyhow::Result<()>;
+    // shares accepted and rejected by the pool the miner is connected to.
+    fn shares(&self) -> (u64, u64);
+}
+
+// Controls XMRig or XMRig-Proxy with their HTTP API.
+pub(crate) struct XrigController<'a> {
     client: &'a Client,
-    pub_api: &'a Arc<Mutex<PubXvbApi>>,
-    gui_api_xvb: &'a Arc<Mutex<PubXvbApi>>,
+    api_url: String,
+    token: &'a str,
+    xp_alive: bool,
     gui_api_xmrig: &'a Arc<Mutex<PubXmrigApi>>,
     gui_api_xp: &'a Arc<Mutex<PubXmrigProxyApi>>,
+    // threads XMRig started with, 0 with XMRig-Proxy which doesn't control the threads of its miners.
+    threads: u16,
+    worker: &'a Arc<Mutex<XvbWorker>>,
+}
+
+impl MinerController for XrigController<'_> {
+    async fn set_pool(&self, pool: &Pool, address: &str, rig: &str) -> anyhow::Result<()> {
+        update_xmrig_config(self.client, &self.api_url, self.token, pool, address, rig).await
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await
+    }
+
+    fn hashrate(&self) -> f32 {
+        current_controllable_hr(self.xp_alive, self.gui_api_xp, self.gui_api_xmrig)
+    }
+
+    fn threads(&self) -> u16 {
+        self.threads
+    }
+
+    async fn split_threads(&self, pool: &Pool, address: &str, threads: u16) -> anyhow::Result<()> {
+        // XMRig keeps the threads the worker doesn't use, all of them once it stops.
+        let main_threads = (threads > 0).then(|| self.threads - threads);
+        if self.worker.lock().unwrap().main_threads != main_threads {
+            let threads = main_threads.unwrap_or(self.threads);
+            update_xmrig_threads(self.client, &self.api_url, self.token, threads).await?;
+            self.worker.lock().unwrap().main_threads = main_threads;
+        }
+        let update = {
+            let mut worker = self.worker.lock().unwrap();
+            if threads == 0 {
+                worker.stop();
+                false
+            } else if !worker.mines_on(pool) {
+                worker.start(pool, address, threads, self.token)?;
+                false
+            } else {
+                worker.threads != threads
+            }
+        };
+        if update {
+            update_xmrig_threads(self.client, &XvbWorker::api_url(), self.token, threads).await?;
+            self.worker.lock().unwrap().threads = threads;
+        }
+        Ok(())
+    }
+
+    fn shares(&self) -> (u64, u64) {
+        if self.xp_alive {
+            let api = self.gui_api_xp.lock().unwrap();
+            (api.accepted as u64, api.rejected as u64)
+        } else {
+            let api = self.gui_api_xmrig.lock().unwrap();
+            (api.accepted_raw, api.rejected_raw)
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub struct Algorithm<'a, M: MinerController = XrigController<'a>> {
+    miner: M,
+    pub_api: &'a Arc<Mutex<PubXvbApi>>,
+    gui_api_xvb: &'a Arc<Mutex<PubXvbApi>>,
     gui_api_p2pool: &'a Arc<Mutex<PubP2poolApi>>,
-    token_xmrig: &'a str,
     state_p2pool: &'a crate::disk::state::P2pool,
     time_donated: &'a Arc<Mutex<u64>>,
     rig: &'a str,
     xp_alive: bool,
+    split: XvbSplit,
     pub stats: Stats,
     p2pool_img: &'a Arc<Mutex<ImgP2pool>>,
     p2pool_process: &'a Arc<Mutex<Process>>,
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
 pub struct Stats {
-    share: u32,
-    hashrate_xmrig: f32,
+    pub share: u32,
+    // seconds before our last share leaves the PPLNS window, if every share is known
+    pub share_expiry: Option<u64>,
+    pub hashrate_xmrig: f32,
+    pub threads: u16,
     pub target_donation_hashrate: f32,
-    xvb_24h_avg: f32,
-    xvb_1h_avg: f32,
-    address: String,
-    runtime_mode: RuntimeMode,
-    runtime_donation_level: RuntimeDonationLevel,
+    pub xvb_24h_avg: f32,
+    pub xvb_1h_avg: f32,
+    // external XvB HR donated by the sibling instances for the same rounds
+    pub siblings_hashrate: f32,
+    pub address: String,
+    pub runtime_mode: RuntimeMode,
+    pub runtime_donation_level: RuntimeDonationLevel,
+    pub runtime_goal: RuntimeGoal,
     // manual slider for p2pool and xvb manual
-    runtime_amount: f64,
-    p2pool_total_hashrate: f32,
-    p2pool_avg_last_hour_hashrate: f32,
-    p2pool_external_hashrate: f32,
-    share_min_hashrate: f32,
-    spareable_hashrate: f32,
-    needed_time_xvb: u64,
-    api_url: String,
-    msg_xmrig_or_xp: String,
+    pub runtime_amount: f64,
+    pub p2pool_total_hashrate: f32,
+    pub p2pool_avg_last_hour_hashrate: f32,
+    pub p2pool_external_hashrate: f32,
+    // inputs of the minimum hashrate to keep a share
+    pub difficulty_p2pool: u64,
+    pub pplns_window: u64,
+    pub second_per_block: u64,
+    pub p2pool_buffer: i8,
+    pub share_min_hashrate: f32,
+    pub spareable_hashrate: f32,
+    pub needed_time_xvb: u64,
+    // time on XvB lost by the last cycles because the pool failed, sent again in this one
+    pub time_lost_xvb: u64,
+    // H/s to send to XvB, given by the leader of the coordination
+    pub assigned_hashrate: Option<f32>,
+    pub msg_xmrig_or_xp: String,
+}
+
+// A decision of the algorithm with the stats it was made with.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    pub timestamp: i64,
+    pub stats: Stats,
+    pub outcome: String,
 }
 
 impl<'a> Algorithm<'a> {
@@ -161,11 +285,78 @@ impl<'a> Algorithm<'a> {
         xmrig_img: &Arc<Mutex<ImgXmrig>>,
         p2pool_img: &'a Arc<Mutex<ImgP2pool>>,
         p2pool_process: &'a Arc<Mutex<Process>>,
+        xvb_worker: &'a Arc<Mutex<XvbWorker>>,
+    ) -> Self {
+        let api_url = if xp_alive {
+            current_api_url_xrig(true, None, Some(&proxy_img.lock().unwrap()))
+        } else {
+            current_api_url_xrig(true, Some(&xmrig_img.lock().unwrap()), None)
+        };
+        let threads = if xp_alive {
+            0
+        } else {
+            xmrig_img
+                .lock()
+                .unwrap()
+                .threads
+                .parse()
+                .unwrap_or_default()
+        };
+        let miner = XrigController {
+            client,
+            api_url,
+            token: token_xmrig,
+            xp_alive,
+            gui_api_xmrig,
+            gui_api_xp,
+            threads,
+            worker: xvb_worker,
+        };
+        Self::with_miner(
+            miner,
+            pub_api,
+            gui_api_xvb,
+            gui_api_p2pool,
+            state_p2pool,
+            share,
+            time_donated,
+            rig,
+            xp_alive,
+            p2pool_buffer,
+            p2pool_img,
+            p2pool_process,
+        )
+    }
+}
+
+impl<'a, M: MinerController> Algorithm<'a, M> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn with_miner(
+        miner: M,
+        pub_api: &'a Arc<Mutex<PubXvbApi>>,
+        gui_api_xvb: &'a Arc<Mutex<PubXvbApi>>,
+        gui_api_p2pool: &'a Arc<Mutex<PubP2poolApi>>,
+        state_p2pool: &'a crate::disk::state::P2pool,
+        share: u32,
+        time_donated: &'a Arc<Mutex<u64>>,
+        rig: &'a str,
+        xp_alive: bool,
+        p2pool_buffer: i8,
+        p2pool_img: &'a Arc<Mutex<ImgP2pool>>,
+        p2pool_process: &'a Arc<Mutex<Process>>,
     ) -> Self {
         let use_sidechain_hr = gui_api_xvb.lock().unwrap().use_p2pool_sidechain_hr;
-        let hashrate_xmrig = current_controllable_hr(xp_alive, gui_api_xp, gui_api_xmrig);
+        let split = gui_api_xvb.lock().unwrap().split;
+        let hashrate_xmrig = miner.hashrate();
+        let threads = miner.threads();
 
-        let address = state_p2pool.address.clone();
+        // the XvB user is the first account, the P2Pool address if the user did not set any.
+        let address = gui_api_xvb
+            .lock()
+            .unwrap()
+            .accounts
+            .first()
+            .map_or_else(|| state_p2pool.address.clone(), |a| a.address.clone());
 
         let runtime_mode = gui_api_xvb.lock().unwrap().stats_priv.runtime_mode.clone();
         let runtime_donation_level = gui_api_xvb
@@ -175,6 +366,7 @@ impl<'a> Algorithm<'a> {
             .runtime_manual_donation_level
             .clone();
         let runtime_amount = gui_api_xvb.lock().unwrap().stats_priv.runtime_manual_amount;
+        let runtime_goal = gui_api_xvb.lock().unwrap().stats_priv.runtime_goal.clone();
 
         let p2pool_total_hashrate = if use_sidechain_hr {
             gui_api_p2pool.lock().unwrap().sidechain_ehr
@@ -197,113 +389,145 @@ impl<'a> Algorithm<'a> {
             "p2pool external hashrate({p2pool_external_hashrate}) = p2ool_total_hashrate({p2pool_total_hashrate}) - p2pool_avg_last_hour_hashrate({p2pool_avg_last_hour_hashrate})"
         );
 
-        let difficulty_p2pool = gui_api_p2pool.lock().unwrap().p2pool_difficulty_u64;
-        let pws_dynamic = gui_api_p2pool.lock().unwrap().window_length_blocks;
-        let share_min_hashrate = Self::minimum_hashrate_share(
-            difficulty_p2pool,
-            state_p2pool.chain.clone(),
-            p2pool_external_hashrate,
-            p2pool_buffer,
-            pws_dynamic,
-        );
-
-        let spareable_hashrate = hashrate_xmrig - share_min_hashrate;
-
-        let api_url = if xp_alive {
-            current_api_url_xrig(true, None, Some(&proxy_img.lock().unwrap()))
+        // the shares recorded by Gupax can miss some, the expiry is only trusted if they are all known.
+        let pplns_window = gui_api_p2pool.lock().unwrap().pplns_window.clone();
+        let share_expiry = if pplns_window.shares.len() >= share as usize {
+            pplns_window.last_expiry()
         } else {
-            current_api_url_xrig(true, Some(&xmrig_img.lock().unwrap()), None)
+            None
         };
 
+        let difficulty_p2pool = gui_api_p2pool.lock().unwrap().p2pool_difficulty_u64;
+        let pws_dynamic = gui_api_p2pool.lock().unwrap().window_length_blocks;
+        let (pplns_window, second_per_block) =
+            pplns_window_length(state_p2pool.chain.clone(), pws_dynamic);
+
         let msg_xmrig_or_xp = (if xp_alive { "XMRig-Proxy" } else { "XMRig" }).to_string();
         info!("xp alive: {xp_alive:?}");
 
         let xvb_24h_avg = pub_api.lock().unwrap().stats_priv.donor_24hr_avg * 1000.0;
         let xvb_1h_avg = pub_api.lock().unwrap().stats_priv.donor_1hr_avg * 1000.0;
+        let siblings_hashrate = pub_api.lock().unwrap().stats_priv.siblings_hashrate;
+        // given to this cycle only, what can't be sent again is not carried further.
+        let time_lost_xvb = std::mem::take(&mut gui_api_xvb.lock().unwrap().time_lost_xvb);
 
-        let stats = Stats {
+        let mut stats = Stats {
             share,
+            share_expiry,
             hashrate_xmrig,
+            threads,
             xvb_24h_avg,
             xvb_1h_avg,
+            siblings_hashrate,
             address,
             target_donation_hashrate: f32::default(),
             runtime_mode,
             runtime_donation_level,
+            runtime_goal,
             runtime_amount,
             p2pool_total_hashrate,
             p2pool_avg_last_hour_hashrate,
             p2pool_external_hashrate,
-            share_min_hashrate,
-            spareable_hashrate,
+            difficulty_p2pool,
+            pplns_window,
+            second_per_block,
+            p2pool_buffer,
+            share_min_hashrate: 0.0,
+            spareable_hashrate: 0.0,
             needed_time_xvb: 0,
-            api_url,
+            time_lost_xvb,
+            assigned_hashrate: None,
             msg_xmrig_or_xp,
         };
+        stats.update();
 
-        let mut new_instance = Self {
-            client,
+        Self {
+            miner,
             pub_api,
             gui_api_xvb,
-            gui_api_xmrig,
-            gui_api_xp,
             gui_api_p2pool,
-            token_xmrig,
             state_p2pool,
             time_donated,
             rig,
             xp_alive,
+            split,
             stats,
             p2pool_img,
             p2pool_process,
-        };
-        // external XvB HR is taken into account with get_target_donation_hashrate so the needed time is calculating how much time is needed from local sparable HR only
-        new_instance.stats.target_donation_hashrate =
-            new_instance.get_target_donation_hashrate().max(0.0);
-        new_instance.stats.needed_time_xvb = Self::get_needed_time_xvb(
-            new_instance.stats.target_donation_hashrate,
-            new_instance.stats.hashrate_xmrig,
-        );
-
-        new_instance
+        }
     }
 
-    fn is_share_fulfilled(&self) -> bool {
-        let is_criteria_fulfilled = self.stats.share > 0;
+    pub(crate) fn is_share_fulfilled(&self) -> bool {
+        // a share leaving the window before the end of this run would not count for the rounds anymore.
+        let expiring = self
+            .stats
+            .share_expiry
+            .is_some_and(|secs| secs * 1000 < XVB_TIME_ALGO);
+        let is_criteria_fulfilled = self.stats.share > 0 && !expiring;
 
         info!(
-            "Algorithm | shares({}) > 0 : {}",
-            self.stats.share, is_criteria_fulfilled,
+            "Algorithm | shares({}) > 0 && last share expiry({:?}s) > algorithm time({}s) : {}",
+            self.stats.share,
+            self.stats.share_expiry,
+            XVB_TIME_ALGO / 1000,
+            is_criteria_fulfilled,
         );
 
         is_criteria_fulfilled
     }
 
     fn is_xvb_fulfilled(&self) -> bool {
-        let runtime_mode = &self.stats.runtime_mode;
-        if *runtime_mode != RuntimeMode::Auto && *runtime_mode != RuntimeMode::ManualDonationLevel {
-            info!("Algorithm | not running auto or manual round selection, no fast average");
-            return true;
-        }
-        let target_donation_hashrate = self.stats.target_donation_hashrate;
+        self.stats.is_xvb_fulfilled()
+    }
 
-        // For 1h average, there is 20% margin.
-        // Since we calculate the time needed for a exact target and hashrate is a bit variable,
-        // The fast mode could get triggered without being useful since being a bit under the 1h target is normal
-        // and still respect the 20% margin. Trigger the fast mode when we are under the 20% of one hour hashrate
-        let is_criteria_fulfilled = self.stats.xvb_24h_avg >= target_donation_hashrate
-            && self.stats.xvb_1h_avg >= target_donation_hashrate * (1.0 - XVB_SIDE_MARGIN_1H);
+    // Decide for the whole team as the leader, this instance sends its part.
+    pub(crate) fn lead(&mut self, coordinator: &Arc<Mutex<Coordinator>>) {
+        let now = chrono::Local::now().timestamp();
+        let followers = coordinator.lock().unwrap().followers(now);
+        let target = self
+            .stats
+            .team_target(&followers, self.is_share_fulfilled());
+        let assigned = coordinator
+            .lock()
+            .unwrap()
+            .plan(self.stats.hashrate_xmrig, target, now);
         info!(
-            "Algorithm | xvb_24h_avg({}) > target_donation_hashrate({}) && xvb_1h_avg({}) > target_donation_hashrate({}) : {}",
-            self.stats.xvb_24h_avg,
-            target_donation_hashrate,
-            self.stats.xvb_1h_avg,
-            target_donation_hashrate,
-            is_criteria_fulfilled
+            "Algorithm | Leader of {} followers, team target({target}H/s), assigned to itself({assigned}H/s)",
+            followers.len()
+        );
+        self.stats.assigned_hashrate = Some(assigned);
+        self.stats.update();
+        output_console(
+            &mut self.gui_api_xvb.lock().unwrap().output,
+            &format!(
+                "Leading {} followers: {:.3}kH/s to send to XvB by the team, {:.3}kH/s by this instance.",
+                followers.len(),
+                target / 1000.0,
+                assigned / 1000.0
+            ),
+            crate::helper::ProcessName::Xvb,
         );
-        is_criteria_fulfilled
     }
 
+    // Send what the leader assigned, or decide alone if it did not plan this instance yet.
+    pub(crate) fn follow(&mut self, assignment: &Assignment) {
+        let msg = if let Some(assigned) = assignment.hashrate {
+            self.stats.assigned_hashrate = Some(assigned);
+            self.stats.update();
+            format!(
+                "The leader assigned {:.3}kH/s to send to XvB.",
+                assigned / 1000.0
+            )
+        } else {
+            "The leader did not plan this instance yet, deciding alone for this cycle.".to_string()
+        };
+        info!("Algorithm | {msg}");
+        output_console(
+            &mut self.gui_api_xvb.lock().unwrap().output,
+            &msg,
+            crate::helper::ProcessName::Xvb,
+        );
+    }
     async fn target_p2pool_node(&self) {
         let node = Pool::P2pool(self.state_p2pool.current_port(
             self.p2pool_process.lock().unwrap().is_alive(),
@@ -314,15 +538,10 @@ impl<'a> Algorithm<'a> {
                 "Algorithm | request {} to mine on p2pool",
                 self.stats.msg_xmrig_or_xp
             );
-            if let Err(err) = update_xmrig_config(
-                self.client,
-                &self.stats.api_url,
-                self.token_xmrig,
-                &node,
-                &self.stats.address,
-                self.rig,
-            )
-            .await
+            if let Err(err) = self
+                .miner
+                .set_pool(&node, &self.stats.address, self.rig)
+                .await
             {
                 warn!(
                     "Algorithm | Failed request HTTP API {}",
@@ -345,25 +564,14 @@ impl<'a> Algorithm<'a> {
         }
     }
 
-    async fn target_xvb_node(&self) {
-        let pool = self.gui_api_xvb.lock().unwrap().stats_priv.pool.clone();
-
+    async fn target_xvb_node(&self, pool: &Pool) {
         info!(
             "Algorithm | request {} to mine on XvB on server: ({})",
             self.stats.msg_xmrig_or_xp, pool
         );
 
-        if self.gui_api_xvb.lock().unwrap().current_pool.as_ref() != Some(&pool) {
-            if let Err(err) = update_xmrig_config(
-                self.client,
-                &self.stats.api_url,
-                self.token_xmrig,
-                &pool,
-                &self.stats.address,
-                "",
-            )
-            .await
-            {
+        if self.gui_api_xvb.lock().unwrap().current_pool.as_ref() != Some(pool) {
+            if let Err(err) = self.miner.set_pool(pool, &self.stats.address, "").await {
                 // show to console error about updating xmrig config
                 warn!(
                     "Algorithm | Failed request HTTP API {}",
@@ -393,30 +601,91 @@ impl<'a> Algorithm<'a> {
             "Algorithm | algo sleep for {} seconds while mining on P2pool",
             XVB_MIN_TIME_SEND / 1000
         );
-        sleep(Duration::from_millis(XVB_TIME_ALGO)).await;
-        let hashrate = current_controllable_hr(self.xp_alive, self.gui_api_xp, self.gui_api_xmrig);
-        self.gui_api_xvb
-            .lock()
-            .unwrap()
+        self.miner.sleep(Duration::from_millis(XVB_TIME_ALGO)).await;
+        let hashrate = self.miner.hashrate();
+        let mut gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+        gui_api_xvb
             .p2pool_sent_last_hour_samples
             .0
             .push_back(hashrate);
+        gui_api_xvb.xvb_sent_last_hour_samples.0.push_back(0.0);
     }
 
     async fn send_all_xvb(&self) {
-        self.target_xvb_node().await;
+        let (sent, fallback) = self.mine_on_xvb(XVB_TIME_ALGO).await;
+        let hashrate = self.miner.hashrate();
+        let mut gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+        gui_api_xvb
+            .p2pool_sent_last_hour_samples
+            .0
+            .push_back(hashrate * fallback as f32 / XVB_TIME_ALGO as f32);
+        gui_api_xvb
+            .xvb_sent_last_hour_samples
+            .0
+            .push_back(hashrate * sent as f32 / XVB_TIME_ALGO as f32);
+    }
 
+    // Mine on XvB during [duration] ms while watching the pool.
+    // If it fails, the miner goes to the other XvB pool, then to P2Pool for the rest of the time.
+    // Returns the ms confirmed sent to XvB and the ms mined on P2Pool after falling back,
+    // the time not confirmed is lost and added to the next cycle.
+    async fn mine_on_xvb(&self, duration: u64) -> (u64, u64) {
+        let pool = self.gui_api_xvb.lock().unwrap().stats_priv.pool.clone();
+        self.target_xvb_node(&pool).await;
         info!(
             "Algorithm | algo sleep for {} seconds while mining on XvB",
-            XVB_TIME_ALGO / 1000
+            duration as f32 / 1000.0
         );
-        sleep(Duration::from_millis(XVB_TIME_ALGO)).await;
-        self.gui_api_xvb
-            .lock()
-            .unwrap()
-            .p2pool_sent_last_hour_samples
-            .0
-            .push_back(0.0);
+        // the time is lost until the pool is seen healthy, so that it is kept if the algorithm is stopped.
+        self.gui_api_xvb.lock().unwrap().time_lost_xvb += duration;
+        let mut watch = PoolWatch::new(pool, self.miner.shares());
+        let mut tried_other = false;
+        let mut sent = 0;
+        let mut left = duration;
+        while left > 0 {
+            let step = left.min(XVB_FAILOVER_CHECK);
+            self.miner.sleep(Duration::from_millis(step)).await;
+            left -= step;
+            let current_pool = self.gui_api_xvb.lock().unwrap().current_pool.clone();
+            let failure = match watch.check(step, current_pool.as_ref(), self.miner.shares()) {
+                Ok(confirmed) => {
+                    sent += confirmed;
+                    let time_lost = &mut self.gui_api_xvb.lock().unwrap().time_lost_xvb;
+                    *time_lost = time_lost.saturating_sub(confirmed);
+                    continue;
+                }
+                Err(failure) => failure,
+            };
+            let failed = watch.pool().clone();
+            warn!(
+                "Algorithm | {failed} failed while mining on it: {failure}, {}s lost",
+                watch.unsettled() as f32 / 1000.0
+            );
+            let pools = self.gui_api_xvb.lock().unwrap().pools.clone();
+            let other = failed.other_xvb_pool(&pools).filter(|_| !tried_other);
+            let msg = match &other {
+                Some(other) => format!("{failed} is failing ({failure}), switching to {other}."),
+                None => format!("{failed} is failing ({failure}), switching back to P2Pool."),
+            };
+            output_console(
+                &mut self.gui_api_xvb.lock().unwrap().output,
+                &format!(
+                    "{msg}\n{}s lost on XvB will be sent during the next cycle.",
+                    watch.unsettled() as f32 / 1000.0
+                ),
+                crate::helper::ProcessName::Xvb,
+            );
+            if let Some(other) = other {
+                tried_other = true;
+                self.target_xvb_node(&other).await;
+                watch = PoolWatch::new(other, self.miner.shares());
+            } else {
+                self.target_p2pool_node().await;
+                self.miner.sleep(Duration::from_millis(left)).await;
+                return (sent, left);
+            }
+        }
+        (sent, 0)
     }
 
     async fn sleep_then_update_node_xmrig(&self) {
@@ -424,104 +693,54 @@ impl<'a> Algorithm<'a> {
             "Algorithm | algo sleep for {} seconds while mining on P2pool",
             (XVB_TIME_ALGO - self.stats.needed_time_xvb) as f32 / 1000.0
         );
-        sleep(Duration::from_millis(
-            XVB_TIME_ALGO - self.stats.needed_time_xvb,
-        ))
-        .await;
-
-        // only update xmrig config if it is actually mining.
-        self.target_xvb_node().await;
+        self.miner
+            .sleep(Duration::from_millis(
+                XVB_TIME_ALGO - self.stats.needed_time_xvb,
+            ))
+            .await;
 
         // will not quit the process until it is really done.
         // xvb process watch this algo handle to see if process is finished or not.
-
-        info!(
-            "Algorithm | algo sleep for {} seconds while mining on XvB",
-            self.stats.needed_time_xvb as f32 / 1000.0
-        );
-        sleep(Duration::from_millis(self.stats.needed_time_xvb)).await;
+        let (sent, _) = self.mine_on_xvb(self.stats.needed_time_xvb).await;
         // HR could be not the same now as the avg sent the last 10mn, will be replaced later by a better history of HR
-        let hashrate = current_controllable_hr(self.xp_alive, self.gui_api_xp, self.gui_api_xmrig);
-        let hashes = hashrate
-            * ((XVB_TIME_ALGO as f32 - self.stats.needed_time_xvb as f32) / XVB_TIME_ALGO as f32);
+        let hashrate = self.miner.hashrate();
+        let hashes = hashrate * ((XVB_TIME_ALGO as f32 - sent as f32) / XVB_TIME_ALGO as f32);
         // dbg
         info!("hashes p2pool sample: {hashes}");
-        self.gui_api_xvb
-            .lock()
-            .unwrap()
+        let mut gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+        gui_api_xvb
             .p2pool_sent_last_hour_samples
             .0
             .push_back(hashes);
+        gui_api_xvb
+            .xvb_sent_last_hour_samples
+            .0
+            .push_back(hashrate - hashes);
     }
 
-    pub fn get_target_donation_hashrate(&self) -> f32 {
-        match self.stats.runtime_mode {
-            RuntimeMode::Auto => self.get_auto_mode_target_donation_hashrate(),
-            RuntimeMode::Hero => self.get_hero_mode_target_donation_hashrate(),
-            RuntimeMode::ManualXvb => {
-                info!(
-                    "Algorithm | ManualXvBMode target_donation_hashrate=runtime_amount({}H/s)",
-                    self.stats.runtime_amount
-                );
-                self.stats.runtime_amount as f32
-            }
-            RuntimeMode::ManualP2pool => {
-                let target_donation_hashrate =
-                    self.stats.hashrate_xmrig - (self.stats.runtime_amount as f32);
-
-                info!(
-                    "Algorithm | ManualP2poolMode target_donation_hashrate({})=hashrate_xmrig({})-runtime_amount({})",
-                    target_donation_hashrate, self.stats.hashrate_xmrig, self.stats.runtime_amount
-                );
-
-                target_donation_hashrate
-            }
-            // manual donation level will take into account external HR
-            RuntimeMode::ManualDonationLevel => {
-                let target_donation_hashrate = self.stats.runtime_donation_level.get_hashrate();
-
-                info!(
-                    "Algorithm | ManualDonationLevelMode target_donation_hashrate({})={:#?}.get_hashrate()",
-                    target_donation_hashrate, self.stats.runtime_donation_level
-                );
-
-                target_donation_hashrate
-            }
-        }
-    }
-
-    fn get_auto_mode_target_donation_hashrate(&self) -> f32 {
-        let donation_level = match self.stats.spareable_hashrate {
-            x if x > (XVB_ROUND_DONOR_MEGA_MIN_HR as f32) => Some(RuntimeDonationLevel::DonorMega),
-            x if x > (XVB_ROUND_DONOR_WHALE_MIN_HR as f32) => {
-                Some(RuntimeDonationLevel::DonorWhale)
-            }
-            x if x > (XVB_ROUND_DONOR_VIP_MIN_HR as f32) => Some(RuntimeDonationLevel::DonorVIP),
-            x if x > (XVB_ROUND_DONOR_MIN_HR as f32) => Some(RuntimeDonationLevel::Donor),
-            _ => None,
-        };
-
-        info!("Algorithm | AutoMode target_donation_level detected ({donation_level:#?})");
-
-        let target_donation_hashrate = if let Some(level) = donation_level {
-            level.get_hashrate()
-        } else {
-            0.0
-        };
-
-        info!("Algorithm | AutoMode target_donation_hashrate ({target_donation_hashrate})");
-
-        target_donation_hashrate
-    }
-    // hero mode, send all spareable hashrate to XvB. the targeted hashrate is the spearable hashrate.
-    // XvB fast average needs to be disabled in hero mode, or else the min share HR will never get his needed time.
-    fn get_hero_mode_target_donation_hashrate(&self) -> f32 {
+    // Same split as [sleep_then_update_node_xmrig], starting on XvB.
+    async fn update_node_xmrig_then_sleep(&self) {
+        let (sent, fallback) = self.mine_on_xvb(self.stats.needed_time_xvb).await;
+        self.target_p2pool_node().await;
         info!(
-            "Algorithm | HeroMode target_donation_hashrate=spareable_hashrate({})",
-            self.stats.spareable_hashrate
+            "Algorithm | algo sleep for {} seconds while mining on P2pool",
+            (XVB_TIME_ALGO - self.stats.needed_time_xvb) as f32 / 1000.0
         );
-
-        self.stats.spareable_hashrate
+        self.miner
+            .sleep(Duration::from_millis(
+                XVB_TIME_ALGO - self.stats.needed_time_xvb,
+            ))
+            .await;
+        let hashrate = self.miner.hashrate();
+        let mut gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+        gui_api_xvb.p2pool_sent_last_hour_samples.0.push_back(
+            hashrate * (XVB_TIME_ALGO - self.stats.needed_time_xvb + fallback) as f32
+                / XVB_TIME_ALGO as f32,
+        );
+        gui_api_xvb
+            .xvb_sent_last_hour_samples
+            .0
+            .push_back(hashrate * sent as f32 / XVB_TIME_ALGO as f32);
     }
 
     // push new value into samples before executing this calcul
@@ -529,41 +748,18 @@ impl<'a> Algorithm<'a> {
         samples.0.iter().sum::<f32>() / samples.0.len() as f32
     }
 
-    fn minimum_hashrate_share(
-        difficulty: u64,
-        chain: P2poolChain,
-        p2pool_external_hashrate: f32,
-        p2pool_buffer: i8,
-        pws_dynamic: Option<u64>,
-    ) -> f32 {
-        let pws;
-        let second_per_block = match chain {
-            P2poolChain::Main => {
-                pws = pws_dynamic.unwrap_or(BLOCK_PPLNS_WINDOW_MAIN_MAX);
-                SECOND_PER_BLOCK_P2POOL_MAIN
-            }
-            P2poolChain::Mini => {
-                pws = BLOCK_PPLNS_WINDOW_MINI;
-                SECOND_PER_BLOCK_P2POOL_MINI
-            }
-            P2poolChain::Nano => {
-                pws = BLOCK_PPLNS_WINDOW_NANO;
-                SECOND_PER_BLOCK_P2POOL_NANO
-            }
+    // Keep the decision with the stats it was made with, for the XvB tab.
+    fn record(&self, outcome: &str) {
+        let decision = Decision {
+            timestamp: chrono::Local::now().timestamp(),
+            stats: self.stats.clone(),
+            outcome: outcome.to_string(),
         };
-        let minimum_hr = ((difficulty / (pws * second_per_block)) as f32
-            * (1.0 + (p2pool_buffer as f32 / 100.0)))
-            - p2pool_external_hashrate;
-
-        info!(
-            "Algorithm | (difficulty({difficulty}) / (window pplns blocks({pws}) * seconds per p2pool block({second_per_block})) * (BUFFER 1 + ({p2pool_buffer})) / 100) - outside HR({p2pool_external_hashrate}H/s) = minimum HR({minimum_hr}H/s) to keep a share."
-        );
-
-        if minimum_hr.is_sign_negative() {
-            info!("Algorithm | if minimum HR is negative, it is 0.");
+        let decisions = &mut self.gui_api_xvb.lock().unwrap().decisions;
+        if decisions.len() >= XVB_DECISIONS_MAX {
+            decisions.pop_front();
         }
-
-        minimum_hr.max(0.0)
+        decisions.push_back(decision);
     }
 
     async fn fulfill_share(&self) {
@@ -574,6 +770,7 @@ impl<'a> Algorithm<'a> {
         );
 
         info!("Algorithm | There are no shares in p2pool. Sending all hashrate to p2pool!");
+        self.record("No share in the PPLNS window, everything on P2Pool.");
 
         self.send_all_p2pool().await
     }
@@ -586,6 +783,7 @@ impl<'a> Algorithm<'a> {
         );
 
         info!("Algorithm | XvB average target not achieved. Sending all hashrate to XvB!");
+        self.record("XvB averages under the target, everything on XvB.");
 
         *self.time_donated.lock().unwrap() = XVB_TIME_ALGO;
 
@@ -612,6 +810,11 @@ impl<'a> Algorithm<'a> {
         );
 
         *self.time_donated.lock().unwrap() = self.stats.needed_time_xvb;
+        self.record(&format!(
+            "{}s on XvB, {}s on P2Pool.",
+            self.stats.needed_time_xvb as f32 / 1000.0,
+            (XVB_TIME_ALGO - self.stats.needed_time_xvb) as f32 / 1000.0
+        ));
 
         match self.stats.needed_time_xvb.cmp(&0) {
             std::cmp::Ordering::Equal => {
@@ -637,6 +840,143 @@ impl<'a> Algorithm<'a> {
         };
     }
 
+    // Mine the cycle on the pool chosen by the plan.
+    // A cycle on XvB keeps the minimum hashrate to keep a share on P2Pool, it starts on the pool the last cycle ended on.
+    async fn fulfill_predictive(&mut self) {
+        let on_xvb = matches!(
+            self.gui_api_xvb.lock().unwrap().current_pool,
+            Some(Pool::XvB(_))
+        );
+        let plan = {
+            let gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+            let sent: Vec<f32> = gui_api_xvb
+                .xvb_sent_last_hour_samples
+                .0
+                .iter()
+                .copied()
+                .collect();
+            Forecast {
+                sent: &sent,
+                avg_1h: self.stats.xvb_1h_avg,
+                avg_24h: self.stats.xvb_24h_avg,
+                hashrate: self.stats.hashrate_xmrig,
+                target: self.stats.round_hashrate(),
+                on_xvb,
+                share_min_hashrate: self.stats.share_min_hashrate,
+                share_expiry: self.stats.share_expiry,
+            }
+            .plan()
+        };
+        info!("Algorithm | Predictive plan: {plan:?}");
+        let msg = format!(
+            "Predictive mode: mining on {} for about {} minutes.",
+            if plan.xvb { "XvB" } else { "P2Pool" },
+            plan.cycles * XVB_TIME_ALGO as usize / 60_000
+        );
+        output_console(
+            &mut self.gui_api_xvb.lock().unwrap().output,
+            &msg,
+            crate::helper::ProcessName::Xvb,
+        );
+        self.record(&msg);
+        if !plan.xvb {
+            *self.time_donated.lock().unwrap() = 0;
+            self.send_all_p2pool().await;
+            return;
+        }
+        self.stats.needed_time_xvb =
+            Stats::get_needed_time_xvb(self.stats.spareable_hashrate, self.stats.hashrate_xmrig);
+        *self.time_donated.lock().unwrap() = self.stats.needed_time_xvb;
+        if self.stats.needed_time_xvb >= XVB_TIME_ALGO {
+            self.send_all_xvb().await
+        } else if on_xvb {
+            self.update_node_xmrig_then_sleep().await
+        } else {
+            self.target_p2pool_node().await;
+            self.sleep_then_update_node_xmrig().await
+        }
+    }
+
+    // Mine on P2Pool and XvB at the same time for the whole cycle, the threads sent to XvB are given by the target.
+    // There is no fast mode to reach the averages: the threads that are not needed to keep a share go to XvB until it is reached.
+    async fn fulfill_threads(&self) {
+        let threads = self.stats.threads;
+        let threads_xvb = if !self.is_share_fulfilled() {
+            0
+        } else {
+            let target = if self.is_xvb_fulfilled() {
+                self.stats.target_donation_hashrate
+            } else {
+                self.stats.spareable_hashrate
+            };
+            threads_xvb(
+                target,
+                self.stats.spareable_hashrate,
+                self.stats.hashrate_xmrig,
+                threads,
+            )
+        };
+        info!("Algorithm | {threads_xvb} threads out of {threads} to send on XvB");
+        // the main miner stays on P2Pool, the worker mines on XvB.
+        self.target_p2pool_node().await;
+        let pool = self.gui_api_xvb.lock().unwrap().stats_priv.pool.clone();
+        if let Err(err) = self
+            .miner
+            .split_threads(&pool, &self.stats.address, threads_xvb)
+            .await
+        {
+            warn!("Algorithm | Failed to split the threads: {err}");
+            output_console(
+                &mut self.gui_api_xvb.lock().unwrap().output,
+                &format!("Failure to split the threads of XMRig.\nError: {err}"),
+                crate::helper::ProcessName::Xvb,
+            );
+        }
+        let msg = format!(
+            "Splitting the threads: {} on P2Pool and {threads_xvb} on XvB.",
+            threads - threads_xvb
+        );
+        output_console(
+            &mut self.gui_api_xvb.lock().unwrap().output,
+            &msg,
+            crate::helper::ProcessName::Xvb,
+        );
+        self.record(&msg);
+        // nothing to switch during the cycle, the threads on XvB donate their share of it.
+        *self.time_donated.lock().unwrap() =
+            XVB_TIME_ALGO * threads_xvb as u64 / threads.max(1) as u64;
+        self.gui_api_xvb.lock().unwrap().threads_xvb = threads_xvb;
+
+        self.miner.sleep(Duration::from_millis(XVB_TIME_ALGO)).await;
+        let hashrate = self.miner.hashrate();
+        let hashes_xvb = hashrate * threads_xvb as f32 / threads as f32;
+        let mut gui_api_xvb = self.gui_api_xvb.lock().unwrap();
+        gui_api_xvb
+            .p2pool_sent_last_hour_samples
+            .0
+            .push_back(hashrate - hashes_xvb);
+        gui_api_xvb
+            .xvb_sent_last_hour_samples
+            .0
+            .push_back(hashes_xvb);
+    }
+
+    // Give back every thread to the main miner if they were split.
+    async fn stop_split_threads(&self) {
+        if self.gui_api_xvb.lock().unwrap().threads_xvb == 0 {
+            return;
+        }
+        let pool = self.gui_api_xvb.lock().unwrap().stats_priv.pool.clone();
+        if let Err(err) = self
+            .miner
+            .split_threads(&pool, &self.stats.address, 0)
+            .await
+        {
+            warn!("Algorithm | Failed to give back the threads: {err}");
+        }
+        self.gui_api_xvb.lock().unwrap().threads_xvb = 0;
+    }
+
     pub async fn run(&mut self) {
         output_console(
             &mut self.gui_api_xvb.lock().unwrap().output,
@@ -659,12 +999,34 @@ impl<'a> Algorithm<'a> {
             );
         }
 
-        if !self.is_share_fulfilled() {
-            self.fulfill_share().await
-        } else if !self.is_xvb_fulfilled() {
-            self.fulfill_xvb().await
+        let split_threads = self.split == XvbSplit::Threads && self.stats.threads > 1;
+        if self.split == XvbSplit::Threads && !split_threads {
+            output_console(
+                &mut self.gui_api_xvb.lock().unwrap().output,
+                &format!(
+                    "The threads of {} can not be split, splitting the hashrate by time.",
+                    self.stats.msg_xmrig_or_xp
+                ),
+                crate::helper::ProcessName::Xvb,
+            );
+        }
+
+        if split_threads {
+            self.fulfill_threads().await
         } else {
-            self.fulfill_normal_cycles().await
+            // the hashrate could have been split by threads during the last cycle.
+            self.stop_split_threads().await;
+            if !self.is_share_fulfilled() {
+                self.fulfill_share().await
+            } else if self.stats.runtime_mode == RuntimeMode::Predictive
+                && self.stats.assigned_hashrate.is_none()
+            {
+                self.fulfill_predictive().await
+            } else if !self.is_xvb_fulfilled() {
+                self.fulfill_xvb().await
+            } else {
+                self.fulfill_normal_cycles().await
+            }
         }
 
         output_console_without_time(
@@ -673,8 +1035,341 @@ impl<'a> Algorithm<'a> {
             crate::helper::ProcessName::Xvb,
         )
     }
+}
+
+impl Stats {
+    // Hashrate to keep on P2Pool, target of XvB and time needed to send it, from the other values.
+    pub fn update(&mut self) {
+        self.share_min_hashrate = self.minimum_hashrate_share();
+        self.spareable_hashrate = self.hashrate_xmrig - self.share_min_hashrate;
+        // external XvB HR is taken into account with get_target_donation_hashrate so the needed time is calculating how much time is needed from local sparable HR only
+        self.target_donation_hashrate = self.get_target_donation_hashrate().max(0.0);
+        let needed_time =
+            Self::get_needed_time_xvb(self.target_donation_hashrate, self.hashrate_xmrig);
+        // the time lost is sent again, without sending more than the spareable hashrate or the cap of the goal.
+        self.needed_time_xvb = if needed_time > 0 && self.time_lost_xvb > 0 {
+            let max_hashrate = if self.runtime_mode == RuntimeMode::Goal {
+                self.goal_max_hashrate()
+            } else {
+                self.spareable_hashrate
+            };
+            let max = Self::get_needed_time_xvb(max_hashrate, self.hashrate_xmrig).max(needed_time);
+            (needed_time + self.time_lost_xvb).min(max)
+        } else {
+            needed_time
+        };
+    }
+
+    // The averages of XvB are high enough for the round, else the fast mode sends everything to XvB.
+    pub fn is_xvb_fulfilled(&self) -> bool {
+        if self.assigned_hashrate.is_some() {
+            info!("Algorithm | the leader decides for the fast average");
+            return true;
+        }
+        let runtime_mode = &self.runtime_mode;
+        if *runtime_mode != RuntimeMode::Auto && *runtime_mode != RuntimeMode::ManualDonationLevel {
+            info!("Algorithm | not running auto or manual round selection, no fast average");
+            return true;
+        }
+        // the averages also count the donations of the siblings.
+        let target_donation_hashrate = self.round_hashrate();
+
+        // For 1h average, there is 20% margin.
+        // Since we calculate the time needed for a exact target and hashrate is a bit variable,
+        // The fast mode could get triggered without being useful since being a bit under the 1h target is normal
+        // and still respect the 20% margin. Trigger the fast mode when we are under the 20% of one hour hashrate
+        let is_criteria_fulfilled = self.xvb_24h_avg >= target_donation_hashrate
+            && self.xvb_1h_avg >= target_donation_hashrate * (1.0 - XVB_SIDE_MARGIN_1H);
+        info!(
+            "Algorithm | xvb_24h_avg({}) > target_donation_hashrate({}) && xvb_1h_avg({}) > target_donation_hashrate({}) : {}",
+            self.xvb_24h_avg,
+            target_donation_hashrate,
+            self.xvb_1h_avg,
+            target_donation_hashrate,
+            is_criteria_fulfilled
+        );
+        is_criteria_fulfilled
+    }
+
+    // H/s the team of the leader must send to XvB, from the stats of the leader and the reports of its followers.
+    pub fn team_target(&self, followers: &[FollowerReport], share: bool) -> f32 {
+        let mut team = self.clone();
+        team.assigned_hashrate = None;
+        team.hashrate_xmrig += followers.iter().map(|f| f.hashrate).sum::<f32>();
+        // the P2Pool HR of the followers is not external to the team.
+        team.p2pool_external_hashrate = (self.p2pool_external_hashrate
+            - followers.iter().map(|f| f.p2pool_hashrate).sum::<f32>())
+        .max(0.0);
+        team.update();
+        if !share {
+            0.0
+        } else if !team.is_xvb_fulfilled() {
+            team.hashrate_xmrig
+        } else {
+            team.target_donation_hashrate
+        }
+    }
+
+    // HR the averages of XvB need for the round, the target of this instance and the HR of its siblings.
+    pub fn round_hashrate(&self) -> f32 {
+        match self.runtime_mode {
+            // the target is not clamped here, the siblings can send more than the round alone.
+            RuntimeMode::Auto | RuntimeMode::Predictive | RuntimeMode::ManualDonationLevel => {
+                self.get_target_donation_hashrate() + self.siblings_hashrate
+            }
+            RuntimeMode::Goal => self.target_donation_hashrate + self.siblings_hashrate,
+            _ => self.target_donation_hashrate,
+        }
+    }
+
+    // Formulas of the decision with their values, to explain it in the XvB tab.
+    pub fn formulas(&self) -> Vec<(&'static str, String)> {
+        let siblings = if self.siblings_hashrate > 0.0 {
+            format!(" - siblings HR({:.0})", self.siblings_hashrate)
+        } else {
+            String::new()
+        };
+        let target = match self.runtime_mode {
+            _ if self.assigned_hashrate.is_some() => "assigned by the leader".to_string(),
+            RuntimeMode::Auto | RuntimeMode::Predictive if self.siblings_hashrate > 0.0 => {
+                format!("highest donor round under the spareable HR + siblings HR{siblings}")
+            }
+            RuntimeMode::Auto | RuntimeMode::Predictive => {
+                "highest donor round under the spareable HR".to_string()
+            }
+            RuntimeMode::Hero => "spareable HR".to_string(),
+            RuntimeMode::ManualXvb => format!("manual amount({:.0})", self.runtime_amount),
+            RuntimeMode::ManualP2pool => format!(
+                "HR({:.0}) - manual amount({:.0})",
+                self.hashrate_xmrig, self.runtime_amount
+            ),
+            RuntimeMode::ManualDonationLevel => {
+                format!(
+                    "donation level({:?}){siblings}",
+                    self.runtime_donation_level
+                )
+            }
+            RuntimeMode::Goal => match self.runtime_goal.goal {
+                XvbGoal::Round => format!(
+                    "highest donor round up to {:?} under min(HR({:.0}) * cap({}%), spareable HR({:.0})){siblings}",
+                    self.runtime_goal.round,
+                    self.hashrate_xmrig,
+                    self.runtime_goal.cap,
+                    self.spareable_hashrate
+                ),
+                XvbGoal::DailyPercent => format!(
+                    "2 * HR({:.0}) * {}% - 24h avg({:.0}){siblings}, under min(HR * cap({}%), spareable HR({:.0}))",
+                    self.hashrate_xmrig,
+                    self.runtime_goal.percent,
+                    self.xvb_24h_avg,
+                    self.runtime_goal.cap,
+                    self.spareable_hashrate
+                ),
+            },
+        };
+        vec![
+            (
+                "External P2Pool HR",
+                format!(
+                    "P2Pool HR({:.0}) - HR sent by Gupax during the last hour({:.0}) = {:.0} H/s",
+                    self.p2pool_total_hashrate,
+                    self.p2pool_avg_last_hour_hashrate,
+                    self.p2pool_external_hashrate
+                ),
+            ),
+            (
+                "Minimum HR for a share",
+                format!(
+                    "difficulty({}) / (PPLNS window({} blocks) * {}s per block) * (1 + buffer({}%)) - external P2Pool HR({:.0}) = {:.0} H/s",
+                    self.difficulty_p2pool,
+                    self.pplns_window,
+                    self.second_per_block,
+                    self.p2pool_buffer,
+                    self.p2pool_external_hashrate,
+                    self.share_min_hashrate
+                ),
+            ),
+            (
+                "Spareable HR",
+                format!(
+                    "HR({:.0}) - minimum HR({:.0}) = {:.0} H/s",
+                    self.hashrate_xmrig, self.share_min_hashrate, self.spareable_hashrate
+                ),
+            ),
+            (
+                "Target donation HR",
+                format!(
+                    "{:?} mode: {target} = {:.0} H/s",
+                    self.runtime_mode, self.target_donation_hashrate
+                ),
+            ),
+            (
+                "Needed time on XvB",
+                format!(
+                    "target({:.0}) / HR({:.0}) * {}s + time lost({}s) = {}s",
+                    self.target_donation_hashrate,
+                    self.hashrate_xmrig,
+                    XVB_TIME_ALGO / 1000,
+                    self.time_lost_xvb as f32 / 1000.0,
+                    self.needed_time_xvb as f32 / 1000.0
+                ),
+            ),
+        ]
+    }
+
+    pub fn get_target_donation_hashrate(&self) -> f32 {
+        if let Some(assigned) = self.assigned_hashrate {
+            info!("Algorithm | target_donation_hashrate=assigned_hashrate({assigned}H/s)");
+            return assigned;
+        }
+        match self.runtime_mode {
+            // the predictive mode targets the same round, it only changes how the time is split.
+            RuntimeMode::Auto | RuntimeMode::Predictive => {
+                self.get_auto_mode_target_donation_hashrate()
+            }
+            RuntimeMode::Hero => self.get_hero_mode_target_donation_hashrate(),
+            RuntimeMode::ManualXvb => {
+                info!(
+                    "Algorithm | ManualXvBMode target_donation_hashrate=runtime_amount({}H/s)",
+                    self.runtime_amount
+                );
+                self.runtime_amount as f32
+            }
+            RuntimeMode::ManualP2pool => {
+                let target_donation_hashrate = self.hashrate_xmrig - (self.runtime_amount as f32);
+
+                info!(
+                    "Algorithm | ManualP2poolMode target_donation_hashrate({})=hashrate_xmrig({})-runtime_amount({})",
+                    target_donation_hashrate, self.hashrate_xmrig, self.runtime_amount
+                );
+
+                target_donation_hashrate
+            }
+            // manual donation level will take into account external HR
+            RuntimeMode::ManualDonationLevel => {
+                let target_donation_hashrate =
+                    self.runtime_donation_level.get_hashrate() - self.siblings_hashrate;
+
+                info!(
+                    "Algorithm | ManualDonationLevelMode target_donation_hashrate({})={:#?}.get_hashrate() - siblings_hashrate({})",
+                    target_donation_hashrate, self.runtime_donation_level, self.siblings_hashrate
+                );
+
+                target_donation_hashrate
+            }
+            RuntimeMode::Goal => self.get_goal_mode_target_donation_hashrate(),
+        }
+    }
+
+    // most HR the goal mode can send: the cap chosen by the user, without losing the share.
+    pub fn goal_max_hashrate(&self) -> f32 {
+        (self.hashrate_xmrig * self.runtime_goal.cap / 100.0)
+            .min(self.spareable_hashrate)
+            .max(0.0)
+    }
+
+    // goal mode, the round or the daily % chosen by the user, never more than the goal max hashrate.
+    // A round out of reach is downgraded to the highest one allowed, a daily % catches up the 24h average.
+    fn get_goal_mode_target_donation_hashrate(&self) -> f32 {
+        let max = self.goal_max_hashrate();
+        let target_donation_hashrate = match self.runtime_goal.goal {
+            XvbGoal::Round => {
+                let levels = [
+                    RuntimeDonationLevel::DonorMega,
+                    RuntimeDonationLevel::DonorWhale,
+                    RuntimeDonationLevel::DonorVIP,
+                    RuntimeDonationLevel::Donor,
+                ];
+                let level = levels
+                    .into_iter()
+                    .skip_while(|l| *l != self.runtime_goal.round)
+                    .find(|l| l.get_hashrate() - self.siblings_hashrate <= max);
+                info!(
+                    "Algorithm | GoalMode round({:?}) reachable under max({max}H/s): {level:?}",
+                    self.runtime_goal.round
+                );
+                level.map_or(0.0, |l| l.get_hashrate() - self.siblings_hashrate)
+            }
+            XvbGoal::DailyPercent => {
+                let daily = self.hashrate_xmrig * self.runtime_goal.percent / 100.0;
+                // the 24h average also counts the siblings.
+                let own_avg = (self.xvb_24h_avg - self.siblings_hashrate).max(0.0);
+                info!(
+                    "Algorithm | GoalMode daily({daily}H/s)=hashrate_xmrig({})*percent({}), own 24h avg({own_avg})",
+                    self.hashrate_xmrig, self.runtime_goal.percent
+                );
+                2.0 * daily - own_avg
+            }
+        };
+        info!(
+            "Algorithm | GoalMode target_donation_hashrate({target_donation_hashrate}) under max({max})"
+        );
+        target_donation_hashrate.clamp(0.0, max)
+    }
+
+    // the siblings donate for the same round, so the round can be higher than what this instance could reach alone.
+    fn get_auto_mode_target_donation_hashrate(&self) -> f32 {
+        let donation_level = match self.spareable_hashrate + self.siblings_hashrate {
+            x if x > (XVB_ROUND_DONOR_MEGA_MIN_HR as f32) => Some(RuntimeDonationLevel::DonorMega),
+            x if x > (XVB_ROUND_DONOR_WHALE_MIN_HR as f32) => {
+                Some(RuntimeDonationLevel::DonorWhale)
+            }
+            x if x > (XVB_ROUND_DONOR_VIP_MIN_HR as f32) => Some(RuntimeDonationLevel::DonorVIP),
+            x if x > (XVB_ROUND_DONOR_MIN_HR as f32) => Some(RuntimeDonationLevel::Donor),
+            _ => None,
+        };
+
+        info!("Algorithm | AutoMode target_donation_level detected ({donation_level:#?})");
+
+        let target_donation_hashrate = if let Some(level) = donation_level {
+            level.get_hashrate() - self.siblings_hashrate
+        } else {
+            0.0
+        };
+
+        info!(
+            "Algorithm | AutoMode target_donation_hashrate ({target_donation_hashrate}), siblings_hashrate ({})",
+            self.siblings_hashrate
+        );
+
+        target_donation_hashrate
+    }
+    // hero mode, send all spareable hashrate to XvB. the targeted hashrate is the spearable hashrate.
+    // XvB fast average needs to be disabled in hero mode, or else the min share HR will never get his needed time.
+    fn get_hero_mode_target_donation_hashrate(&self) -> f32 {
+        info!(
+            "Algorithm | HeroMode target_donation_hashrate=spareable_hashrate({})",
+            self.spareable_hashrate
+        );
+
+        self.spareable_hashrate
+    }
+
+    // (difficulty / (window pplns blocks * seconds per p2pool block)) * (1 + buffer) - outside HR
+    fn minimum_hashrate_share(&self) -> f32 {
+        let minimum_hr = ((self.difficulty_p2pool / (self.pplns_window * self.second_per_block))
+            as f32
+            * (1.0 + (self.p2pool_buffer as f32 / 100.0)))
+            - self.p2pool_external_hashrate;
+
+        info!(
+            "Algorithm | (difficulty({}) / (window pplns blocks({}) * seconds per p2pool block({})) * (BUFFER 1 + ({})) / 100) - outside HR({}H/s) = minimum HR({minimum_hr}H/s) to keep a share.",
+            self.difficulty_p2pool,
+            self.pplns_window,
+            self.second_per_block,
+            self.p2pool_buffer,
+            self.p2pool_external_hashrate
+        );
+
+        if minimum_hr.is_sign_negative() {
+            info!("Algorithm | if minimum HR is negative, it is 0.");
+        }
+
+        minimum_hr.max(0.0)
+    }
+
     // time needed to send on XvB get to the targeted doner round
-    fn get_needed_time_xvb(target_donation_hashrate: f32, hashrate_xmrig: f32) -> u64 {
+    pub fn get_needed_time_xvb(target_donation_hashrate: f32, hashrate_xmrig: f32) -> u64 {
         // the ceil() is required since we dont' send half seconds, we take the value above to be sure to not undersent.
         // It specially makes a difference for whales for whom one second can mean a lot of hashrate.
         let needed_time =
@@ -688,3 +1383,96 @@ impl<'a> Algorithm<'a> {
         needed_time.clamp(0.0, XVB_TIME_ALGO as f32) as u64
     }
 }
+
+// Length of the PPLNS window in blocks and seconds per block of the sidechain.
+fn pplns_window_length(chain: P2poolChain, pws_dynamic: Option<u64>) -> (u64, u64) {
+    match chain {
+        P2poolChain::Main => (
+            pws_dynamic.unwrap_or(BLOCK_PPLNS_WINDOW_MAIN_MAX),
+            SECOND_PER_BLOCK_P2POOL_MAIN,
+        ),
+        P2poolChain::Mini => (BLOCK_PPLNS_WINDOW_MINI, SECOND_PER_BLOCK_P2POOL_MINI),
+        P2poolChain::Nano => (BLOCK_PPLNS_WINDOW_NANO, SECOND_PER_BLOCK_P2POOL_NANO),
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::disk::state::XvbMode;
+    use crate::helper::xvb::rounds::XvbRound;
+    use crate::helper::xvb::simulation::nano_stats;
+    use crate::helper::xvb::what_if::WhatIf;
+
+    #[test]
+    fn goal_mode() {
+        // reach the Whale round with at most 40% of the hashrate
+        let mut stats = Stats {
+            runtime_mode: RuntimeMode::Goal,
+            runtime_goal: RuntimeGoal {
+                goal: XvbGoal::Round,
+                round: RuntimeDonationLevel::DonorWhale,
+                percent: 10.0,
+                cap: 40.0,
+            },
+            ..nano_stats()
+        };
+        stats.update();
+        // the Whale and VIP rounds are above the cap, downgraded to Donor
+        assert_eq!(stats.goal_max_hashrate(), 8_000.0);
+        assert_eq!(stats.target_donation_hashrate, 1_000.0);
+        stats.runtime_goal.cap = 60.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 10_000.0);
+        // the siblings make the Whale round reachable
+        stats.runtime_goal.cap = 40.0;
+        stats.siblings_hashrate = 95_000.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 5_000.0);
+        assert_eq!(stats.round_hashrate(), 100_000.0);
+        // no fast mode, the cap is never exceeded
+        assert!(stats.is_xvb_fulfilled());
+        stats.siblings_hashrate = 0.0;
+        // the share is kept before the goal
+        stats.hashrate_xmrig = 1_000.0;
+        stats.runtime_goal.cap = 100.0;
+        stats.runtime_goal.round = RuntimeDonationLevel::Donor;
+        stats.update();
+        assert!(stats.goal_max_hashrate() < 1_000.0);
+        assert_eq!(stats.target_donation_hashrate, 0.0);
+        // 10% a day catches up the 24h average
+        stats.hashrate_xmrig = 20_000.0;
+        stats.runtime_goal.goal = XvbGoal::DailyPercent;
+        stats.runtime_goal.cap = 40.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 4_000.0);
+        stats.xvb_24h_avg = 3_000.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 1_000.0);
+        stats.xvb_24h_avg = 5_000.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 0.0);
+        stats.xvb_24h_avg = 0.0;
+        stats.runtime_goal.cap = 10.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 2_000.0);
+        // projected round of the goal with the stats of a decision
+        let decision = Decision {
+            timestamp: 1_700_000_000,
+            stats,
+            outcome: String::new(),
+        };
+        let goal = RuntimeGoal {
+            goal: XvbGoal::Round,
+            round: RuntimeDonationLevel::DonorWhale,
+            percent: 0.0,
+            cap: 60.0,
+        };
+        let outcome =
+            WhatIf::new(&decision, XvbMode::Goal, XvbSplit::Time, goal).outcome(&decision);
+        assert_eq!(outcome.sent, 10_000.0);
+        assert_eq!(outcome.round, Some(XvbRound::DonorVip));
+        assert_eq!(outcome.stats.formulas().len(), 5);
+    }
+}
@@ -0,0 +1,560 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Coordination of the XvB algorithm between the instances of Gupax mining to the same P2Pool address.
+// Alone, every instance sees the others as external hashrate and they all react to the same averages at once.
+// The leader runs the algorithm with the hashrate of the whole team and splits what the team must send to XvB
+// between the instances, the biggest first so that the fewest switch pools. The followers report their hashrate
+// every cycle and send what they were assigned by the last decision of the leader.
+// They talk over HTTP on the LAN: a follower POSTs its report to /xvb/report, signed with the key shared by the team
+// (HMAC-SHA256 of its timestamp and the body), the leader answers with its assignment.
+// The key is never sent, and a signature older than a few minutes is refused.
+// A follower without answer runs its own algorithm.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::bail;
+use log::{info, warn};
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware as Client;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    spawn,
+    time::timeout,
+};
+
+use crate::{
+    disk::state::XvbCoordination,
+    helper::ProcessName,
+    miscs::output_console,
+    utils::{
+        constants::{
+            XVB_COORDINATION_MAX_SKEW, XVB_COORDINATION_READ_TIMEOUT, XVB_COORDINATION_TIMEOUT,
+            XVB_TIME_ALGO,
+        },
+        view_key::{hex_decode, hex_encode},
+    },
+};
+
+use super::{PubXvbApi, siblings::instance_name};
+
+// a report is a few bytes, anything bigger is not a follower.
+const MAX_BODY: usize = 4096;
+const MAX_HEADERS: u64 = 8192;
+const TIMESTAMP_HEADER: &str = "x-gupax-timestamp";
+const SIGNATURE_SCHEME: &str = "HMAC-SHA256 ";
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FollowerReport {
+    pub name: String,
+    // H/s of the miners of the follower
+    pub hashrate: f32,
+    // H/s sent to P2Pool during the last hour, so the leader does not count it as external
+    pub p2pool_hashrate: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Assignment {
+    // H/s to send to XvB, None until the leader made a decision with the follower
+    pub hashrate: Option<f32>,
+}
+
+#[derive(Debug, Default)]
+pub struct Coordinator {
+    // name of the leader in the assignments
+    name: String,
+    // last report of every follower with its unix timestamp
+    reports: BTreeMap<String, (FollowerReport, i64)>,
+    // H/s to send to XvB by every instance, from the last decision of the leader
+    assignments: BTreeMap<String, f32>,
+}
+
+impl Coordinator {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // Keep the report of a follower and give it its assignment.
+    pub fn report(&mut self, report: FollowerReport, now: i64) -> Assignment {
+        let assignment = Assignment {
+            hashrate: self.assignments.get(&report.name).copied(),
+        };
+        self.reports.insert(report.name.clone(), (report, now));
+        assignment
+    }
+
+    // Followers that reported recently.
+    pub fn followers(&self, now: i64) -> Vec<FollowerReport> {
+        self.reports
+            .values()
+            .filter(|(_, timestamp)| now - timestamp <= XVB_COORDINATION_TIMEOUT)
+            .map(|(report, _)| report.clone())
+            .collect()
+    }
+
+    // Split [target] H/s between the leader with [hashrate] and its followers.
+    // Returns the H/s assigned to the leader.
+    pub fn plan(&mut self, hashrate: f32, target: f32, now: i64) -> f32 {
+        let mut instances = self
+            .followers(now)
+            .into_iter()
+            .map(|f| (f.name, f.hashrate))
+            .collect::<Vec<_>>();
+        instances.push((self.name.clone(), hashrate));
+        self.assignments = assign(&instances, target);
+        self.assignments
+            .get(&self.name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Listen for the followers on [address] until the XvB process stops.
+    pub async fn listen(address: &str, key: &str, name: &str) -> anyhow::Result<Arc<Mutex<Self>>> {
+        if key.is_empty() {
+            bail!("the key of the coordination is empty");
+        }
+        let listener = TcpListener::bind(address).await?;
+        let coordinator = Arc::new(Mutex::new(Self::new(name)));
+        spawn(serve(listener, key.to_string(), coordinator.clone()));
+        info!("XvB | Leading the coordination on {address}");
+        Ok(coordinator)
+    }
+}
+
+// Give to every instance the most it can send until [target] is reached, the biggest first.
+pub fn assign(instances: &[(String, f32)], target: f32) -> BTreeMap<String, f32> {
+    let mut instances = instances.to_vec();
+    instances.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut left = target.max(0.0);
+    instances
+        .into_iter()
+        .map(|(name, hashrate)| {
+            let assigned = hashrate.max(0.0).min(left);
+            left -= assigned;
+            (name, assigned)
+        })
+        .collect()
+}
+
+//---------------------------------------------------------------------------------------------------- Leader
+pub async fn serve(listener: TcpListener, key: String, coordinator: Arc<Mutex<Coordinator>>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                warn!("XvB | Coordination could not accept a connection: {err}");
+                continue;
+            }
+        };
+        let key = key.clone();
+        let coordinator = coordinator.clone();
+        spawn(async move {
+            if let Err(err) = handle(stream, &key, &coordinator).await {
+                warn!("XvB | Coordination request of {peer} failed: {err}");
+            }
+        });
+    }
+}
+
+async fn handle(
+    stream: TcpStream,
+    key: &str,
+    coordinator: &Arc<Mutex<Coordinator>>,
+) -> anyhow::Result<()> {
+    let mut stream = BufReader::new(stream);
+    // a follower sends its request at once, a slow one is dropped.
+    let Ok(request) = timeout(
+        Duration::from_millis(XVB_COORDINATION_READ_TIMEOUT),
+        read_report(&mut stream, key),
+    )
+    .await
+    else {
+        bail!("the request was not received in time");
+    };
+    let (status, body) = match request? {
+        Ok(report) => {
+            let now = chrono::Local::now().timestamp();
+            let assignment = coordinator.lock().unwrap().report(report, now);
+            ("200 OK", serde_json::to_string(&assignment)?)
+        }
+        Err(status) => (status, String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.get_mut().write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+// Read the report of a follower, or the status refusing it.
+async fn read_report(
+    stream: &mut BufReader<TcpStream>,
+    key: &str,
+) -> anyhow::Result<Result<FollowerReport, &'static str>> {
+    let mut head = (&mut *stream).take(MAX_HEADERS);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line).await?;
+    let mut length = 0;
+    let mut timestamp = None;
+    let mut signature = None;
+    loop {
+        let mut line = String::new();
+        if head.read_line(&mut line).await? == 0 {
+            bail!("headers too big or connection closed before their end");
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => length = value.parse()?,
+                TIMESTAMP_HEADER => timestamp = value.parse::<i64>().ok(),
+                "authorization" => {
+                    signature = value.strip_prefix(SIGNATURE_SCHEME).map(str::to_string)
+                }
+                _ => {}
+            }
+        }
+    }
+    if !request_line.starts_with("POST /xvb/report ") {
+        return Ok(Err("404 Not Found"));
+    }
+    // the body is only read for a recent signature
+    let now = chrono::Local::now().timestamp();
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return Ok(Err("401 Unauthorized"));
+    };
+    if (now - timestamp).abs() > XVB_COORDINATION_MAX_SKEW {
+        return Ok(Err("401 Unauthorized"));
+    }
+    if length > MAX_BODY {
+        bail!("body of {length} bytes is too big");
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await?;
+    if !verify(key, timestamp, &body, &signature) {
+        return Ok(Err("401 Unauthorized"));
+    }
+    Ok(serde_json::from_slice(&body).map_err(|_| "400 Bad Request"))
+}
+
+fn signed_message(timestamp: i64, body: &[u8]) -> Vec<u8> {
+    [format!("{timestamp}\n").as_bytes(), body].concat()
+}
+
+// HMAC-SHA256 of the timestamp and the body with the key of the team, in hex.
+fn sign(key: &str, timestamp: i64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+    hex_encode(hmac::sign(&key, &signed_message(timestamp, body)).as_ref())
+}
+
+// Compared in constant time, the time taken does not tell how much of the signature is right.
+fn verify(key: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes());
+    hex_decode(signature)
+        .is_some_and(|tag| hmac::verify(&key, &signed_message(timestamp, body), &tag).is_ok())
+}
+
+//---------------------------------------------------------------------------------------------------- Follower
+pub async fn request_assignment(
+    client: &Client,
+    leader: &str,
+    key: &str,
+    report: &FollowerReport,
+) -> anyhow::Result<Assignment> {
+    if key.is_empty() {
+        bail!("the key of the coordination is empty");
+    }
+    let body = serde_json::to_string(report)?;
+    let timestamp = chrono::Local::now().timestamp();
+    let resp = client
+        .post(format!("http://{leader}/xvb/report"))
+        .header(
+            reqwest::header::AUTHORIZATION,
+            format!(
+                "{SIGNATURE_SCHEME}{}",
+                sign(key, timestamp, body.as_bytes())
+            ),
+        )
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?;
+    match resp.status() {
+        StatusCode::OK => Ok(resp.json::<Assignment>().await?),
+        StatusCode::UNAUTHORIZED => {
+            bail!("the key is refused by the leader, or the clocks are too far apart")
+        }
+        status => bail!("the leader answered with status {status}"),
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Role
+#[derive(Clone, Debug)]
+pub enum Role {
+    Leader(Arc<Mutex<Coordinator>>),
+    Follower(Assignment),
+}
+
+// Role of this instance for the next decision of the algorithm, None if it decides alone.
+pub async fn role(
+    client: &Client,
+    state_xvb: &crate::disk::state::Xvb,
+    coordinator: Option<&Arc<Mutex<Coordinator>>>,
+    gui_api: &Arc<Mutex<PubXvbApi>>,
+    hashrate: f32,
+) -> Option<Role> {
+    match state_xvb.coordination {
+        XvbCoordination::Alone => None,
+        XvbCoordination::Leader => coordinator.cloned().map(Role::Leader),
+        XvbCoordination::Follower => {
+            let p2pool_hashrate = {
+                let samples = &gui_api.lock().unwrap().p2pool_sent_last_hour_samples.0;
+                samples.iter().sum::<f32>() / samples.len() as f32
+            };
+            let report = FollowerReport {
+                name: instance_name(&state_xvb.sibling_name),
+                hashrate,
+                p2pool_hashrate,
+            };
+            match request_assignment(
+                client,
+                &state_xvb.coordination_address,
+                &state_xvb.coordination_key,
+                &report,
+            )
+            .await
+            {
+                Ok(assignment) => Some(Role::Follower(assignment)),
+                Err(err) => {
+                    warn!("XvB | Could not get the assignment of the leader: {err}");
+                    output_console(
+                        &mut gui_api.lock().unwrap().output,
+                        &format!(
+                            "Could not reach the leader, deciding alone for {}s.\nError: {err}",
+                            XVB_TIME_ALGO / 1000
+                        ),
+                        ProcessName::Xvb,
+                    );
+                    None
+                }
+            }
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helper::xvb::algorithm::Stats;
+    use crate::helper::xvb::simulation::nano_stats;
+
+    #[test]
+    fn assign_biggest_first() {
+        let instances = [
+            ("a".to_string(), 20_000.0),
+            ("b".to_string(), 30_000.0),
+            ("c".to_string(), 10_000.0),
+        ];
+        let assignments = assign(&instances, 45_000.0);
+        assert_eq!(assignments["b"], 30_000.0);
+        assert_eq!(assignments["a"], 15_000.0);
+        assert_eq!(assignments["c"], 0.0);
+        // more than the team can send
+        let assignments = assign(&instances, 100_000.0);
+        assert_eq!(assignments.values().sum::<f32>(), 60_000.0);
+        assert!(assign(&instances, -1.0).values().all(|a| *a == 0.0));
+    }
+
+    #[test]
+    fn coordinator_reports() {
+        let mut coordinator = Coordinator::new("leader");
+        let report = |name: &str, hashrate: f32| FollowerReport {
+            name: name.to_string(),
+            hashrate,
+            p2pool_hashrate: 0.0,
+        };
+        let now = 1_700_000_000;
+        // nothing planned yet
+        assert_eq!(
+            coordinator.report(report("rig1", 5_000.0), now),
+            Assignment::default()
+        );
+        coordinator.report(report("rig2", 2_000.0), now - XVB_COORDINATION_TIMEOUT - 1);
+        assert_eq!(coordinator.followers(now), vec![report("rig1", 5_000.0)]);
+        // the follower that stopped reporting is not assigned anything
+        assert_eq!(coordinator.plan(8_000.0, 10_000.0, now), 8_000.0);
+        assert_eq!(
+            coordinator.report(report("rig1", 5_000.0), now),
+            Assignment {
+                hashrate: Some(2_000.0)
+            }
+        );
+        assert_eq!(
+            coordinator.report(report("rig2", 2_000.0), now),
+            Assignment { hashrate: None }
+        );
+    }
+
+    #[test]
+    fn leader_authentication() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = crate::miscs::client();
+        let report = FollowerReport {
+            name: "rig1".to_string(),
+            hashrate: 5_000.0,
+            p2pool_hashrate: 0.0,
+        };
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let leader = listener.local_addr().unwrap().to_string();
+            let coordinator = Arc::new(Mutex::new(Coordinator::new("leader")));
+            spawn(serve(listener, "secret".to_string(), coordinator.clone()));
+            // signed with the key of the team
+            assert_eq!(
+                request_assignment(&client, &leader, "secret", &report)
+                    .await
+                    .unwrap(),
+                Assignment::default()
+            );
+            assert_eq!(coordinator.lock().unwrap().followers(chrono::Local::now().timestamp()).len(), 1);
+            let err = request_assignment(&client, &leader, "secreT", &report)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("refused"));
+            // a signature too old or without timestamp, the body is not read
+            let body = serde_json::to_string(&report).unwrap();
+            let old = chrono::Local::now().timestamp() - XVB_COORDINATION_MAX_SKEW - 1;
+            let send = |headers: String| {
+                let leader = leader.clone();
+                async move {
+                    let mut stream = TcpStream::connect(leader).await.unwrap();
+                    stream.write_all(headers.as_bytes()).await.unwrap();
+                    let mut response = String::new();
+                    let _ = stream.read_to_string(&mut response).await;
+                    response
+                }
+            };
+            let response = send(format!(
+                "POST /xvb/report HTTP/1.1\r\nAuthorization: {SIGNATURE_SCHEME}{}\r\n{TIMESTAMP_HEADER}: {old}\r\nContent-Length: {}\r\n\r\n",
+                sign("secret", old, body.as_bytes()),
+                body.len()
+            ))
+            .await;
+            assert!(response.starts_with("HTTP/1.1 401"));
+            let response = send(format!(
+                "POST /xvb/report HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            ))
+            .await;
+            assert!(response.starts_with("HTTP/1.1 401"));
+            // endless headers are not read
+            let response = send(format!(
+                "POST /xvb/report HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+                "a".repeat(MAX_HEADERS as usize)
+            ))
+            .await;
+            assert!(response.is_empty());
+        });
+    }
+
+    #[test]
+    fn coordinated_instances() {
+        coordinate();
+    }
+    #[tokio::main(flavor = "current_thread")]
+    async fn coordinate() {
+        // the leader listens on the loopback like it would on the LAN
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let leader = listener.local_addr().unwrap().to_string();
+        let coordinator = Arc::new(Mutex::new(Coordinator::new("leader")));
+        spawn(serve(listener, "secret".to_string(), coordinator.clone()));
+        let client = crate::miscs::client();
+        let report = FollowerReport {
+            name: "follower".to_string(),
+            hashrate: 30_000.0,
+            p2pool_hashrate: 0.0,
+        };
+        assert!(
+            request_assignment(&client, &leader, "wrong", &report)
+                .await
+                .is_err()
+        );
+        // the leader did not decide with the follower yet
+        assert_eq!(
+            request_assignment(&client, &leader, "secret", &report)
+                .await
+                .unwrap(),
+            Assignment { hashrate: None }
+        );
+        // the averages of the leader are at the VIP round
+        let mut leader_stats = Stats {
+            xvb_1h_avg: 10_000.0,
+            xvb_24h_avg: 10_000.0,
+            ..nano_stats()
+        };
+        leader_stats.update();
+        let now = chrono::Local::now().timestamp();
+        let followers = coordinator.lock().unwrap().followers(now);
+        // 50 kH/s for the team is not enough for the Whale round
+        let target = leader_stats.team_target(&followers, true);
+        assert_eq!(target, 10_000.0);
+        // the biggest instance sends everything, the leader stays on P2Pool
+        let assigned = coordinator.lock().unwrap().plan(20_000.0, target, now);
+        assert_eq!(assigned, 0.0);
+        leader_stats.assigned_hashrate = Some(assigned);
+        leader_stats.update();
+        assert_eq!(leader_stats.needed_time_xvb, 0);
+        let assignment = request_assignment(&client, &leader, "secret", &report)
+            .await
+            .unwrap();
+        assert_eq!(assignment.hashrate, Some(10_000.0));
+        let mut follower_stats = Stats {
+            hashrate_xmrig: 30_000.0,
+            assigned_hashrate: assignment.hashrate,
+            ..leader_stats.clone()
+        };
+        follower_stats.update();
+        assert_eq!(follower_stats.needed_time_xvb, 20_000);
+        assert!(follower_stats.is_xvb_fulfilled());
+        // under the averages, the whole team goes to XvB
+        leader_stats.xvb_1h_avg = 0.0;
+        leader_stats.xvb_24h_avg = 0.0;
+        assert_eq!(leader_stats.team_target(&followers, true), 50_000.0);
+        // without a share, the whole team stays on P2Pool
+        assert_eq!(leader_stats.team_target(&followers, false), 0.0);
+    }
+}
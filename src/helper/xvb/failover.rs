@@ -0,0 +1,176 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Watch of the XvB pool while the miner mines on it.
+// The fastest pool is only chosen before the cycles, so a pool dying during the time on XvB would waste
+// the hashrate until the next decision. The connection and the shares of XMRig or XMRig-Proxy are checked
+// every [XVB_FAILOVER_CHECK] ms: the time is only counted as sent once the pool is seen healthy, the time
+// not confirmed when it fails is lost and given back to the next cycle.
+
+use derive_more::Display;
+
+use crate::utils::constants::{XVB_FAILOVER_GRACE, XVB_FAILOVER_REJECTED};
+
+use super::nodes::Pool;
+
+#[derive(Clone, Copy, Debug, PartialEq, Display)]
+pub enum Failure {
+    #[display("not connected")]
+    Disconnected,
+    #[display("{_0} shares rejected without any accepted")]
+    Rejected(u64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolWatch {
+    pool: Pool,
+    // ms since the miner was asked to mine on the pool
+    elapsed: u64,
+    // ms since the last time the pool was seen healthy
+    unsettled: u64,
+    connected: bool,
+    // shares counted by the miner at the last accepted share
+    accepted: u64,
+    rejected: u64,
+}
+
+impl PoolWatch {
+    // [shares] are the accepted and rejected shares counted by the miner when it switches to [pool].
+    pub fn new(pool: Pool, shares: (u64, u64)) -> Self {
+        Self {
+            pool,
+            elapsed: 0,
+            unsettled: 0,
+            connected: false,
+            accepted: shares.0,
+            rejected: shares.1,
+        }
+    }
+
+    pub fn pool(&self) -> &Pool {
+        &self.pool
+    }
+
+    // ms mined on the pool that are not confirmed yet, lost if the pool fails.
+    pub fn unsettled(&self) -> u64 {
+        self.unsettled
+    }
+
+    // Check the pool [ms] after the last check, with the pool the miner is connected to and its shares.
+    // Returns the ms confirmed sent to the pool.
+    pub fn check(
+        &mut self,
+        ms: u64,
+        current_pool: Option<&Pool>,
+        shares: (u64, u64),
+    ) -> Result<u64, Failure> {
+        self.elapsed += ms;
+        self.unsettled += ms;
+        if current_pool != Some(&self.pool) {
+            // XMRig needs a few seconds to connect after a switch.
+            if !self.connected && self.elapsed < XVB_FAILOVER_GRACE {
+                return Ok(0);
+            }
+            return Err(Failure::Disconnected);
+        }
+        self.connected = true;
+        let (accepted, rejected) = shares;
+        // the counters of XMRig start again at every connection.
+        if accepted < self.accepted || rejected < self.rejected || accepted > self.accepted {
+            self.accepted = accepted;
+            self.rejected = rejected;
+        }
+        let rejected = rejected - self.rejected;
+        if rejected >= XVB_FAILOVER_REJECTED {
+            return Err(Failure::Rejected(rejected));
+        }
+        Ok(std::mem::take(&mut self.unsettled))
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::disk::state::XvbPoolEndpoint;
+    use crate::helper::xvb::algorithm::Stats;
+    use crate::helper::xvb::simulation::nano_stats;
+
+    #[test]
+    fn watch_connection() {
+        let pool = Pool::XvB(XvbPoolEndpoint::europe());
+        let mut watch = PoolWatch::new(pool.clone(), (10, 0));
+        // still connecting
+        assert_eq!(watch.check(1000, None, (0, 0)), Ok(0));
+        assert_eq!(watch.check(1000, Some(&Pool::P2pool(3333)), (0, 0)), Ok(0));
+        // connected, the time is confirmed
+        assert_eq!(watch.check(1000, Some(&pool), (0, 0)), Ok(3000));
+        assert_eq!(watch.check(1000, Some(&pool), (1, 0)), Ok(1000));
+        // connection lost, no grace once connected
+        assert_eq!(watch.check(1000, None, (1, 0)), Err(Failure::Disconnected));
+        assert_eq!(watch.unsettled(), 1000);
+        // never connected
+        let mut watch = PoolWatch::new(pool.clone(), (0, 0));
+        assert_eq!(watch.check(XVB_FAILOVER_GRACE - 1, None, (0, 0)), Ok(0));
+        assert_eq!(watch.check(1, None, (0, 0)), Err(Failure::Disconnected));
+        assert_eq!(watch.unsettled(), XVB_FAILOVER_GRACE);
+    }
+
+    #[test]
+    fn watch_rejected() {
+        let pool = Pool::XvB(XvbPoolEndpoint::north_america());
+        let mut watch = PoolWatch::new(pool.clone(), (5, 1));
+        assert_eq!(watch.check(1000, Some(&pool), (5, 2)), Ok(1000));
+        // an accepted share resets the count of the rejected
+        assert_eq!(watch.check(1000, Some(&pool), (6, 3)), Ok(1000));
+        assert_eq!(watch.check(1000, Some(&pool), (6, 5)), Ok(1000));
+        assert_eq!(
+            watch.check(1000, Some(&pool), (6, 3 + XVB_FAILOVER_REJECTED)),
+            Err(Failure::Rejected(XVB_FAILOVER_REJECTED))
+        );
+        // new connection of the miner
+        let mut watch = PoolWatch::new(pool.clone(), (100, 50));
+        assert_eq!(watch.check(1000, Some(&pool), (0, 1)), Ok(1000));
+        assert_eq!(
+            watch.check(1000, Some(&pool), (0, XVB_FAILOVER_REJECTED + 1)),
+            Err(Failure::Rejected(XVB_FAILOVER_REJECTED))
+        );
+    }
+
+    #[test]
+    fn time_lost_compensation() {
+        // the time lost on XvB is sent again in the next cycle
+        let mut stats = Stats {
+            time_lost_xvb: 5_000,
+            ..nano_stats()
+        };
+        stats.update();
+        assert_eq!(stats.needed_time_xvb, 35_000);
+        // never more than the spareable hashrate
+        stats.time_lost_xvb = 50_000;
+        stats.update();
+        assert_eq!(
+            stats.needed_time_xvb,
+            Stats::get_needed_time_xvb(stats.spareable_hashrate, 20_000.0)
+        );
+        assert!(stats.needed_time_xvb < 60_000);
+        // nothing to send, nothing to send again
+        stats.hashrate_xmrig = 100.0;
+        stats.update();
+        assert_eq!(stats.needed_time_xvb, 0);
+    }
+}
@@ -15,10 +15,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::helper::xrig::{current_api_url_xrig, update_xmrig_config};
-use crate::helper::xvb::algorithm::algorithm;
-use crate::helper::xvb::priv_stats::XvbPrivStats;
+use crate::disk::state::{XvbAccount, XvbCoordination, XvbPoolEndpoint, XvbSplit};
+use crate::disk::xvb_history::XvbHistory;
+use crate::helper::xrig::{current_api_url_xrig, update_xmrig_config, update_xmrig_threads};
+use crate::helper::xvb::algorithm::{Decision, algorithm};
+use crate::helper::xvb::coordination::{Coordinator, role};
+use crate::helper::xvb::priv_stats::{AccountError, XvbPrivStats};
 use crate::helper::xvb::public_stats::XvbPubStats;
+use crate::helper::xvb::siblings::{instance_name, update_siblings};
+use crate::helper::xvb::split::XvbWorker;
 use crate::helper::{ProcessName, sleep_end_loop};
 use crate::miscs::{client, output_console};
 use bounded_vec_deque::BoundedVecDeque;
@@ -26,6 +31,7 @@ use enclose::enc;
 use log::{debug, info, warn};
 use readable::up::Uptime;
 use reqwest_middleware::ClientWithMiddleware as Client;
+use std::collections::VecDeque;
 use std::mem;
 use std::time::Duration;
 use std::{
@@ -51,10 +57,19 @@ use super::xrig::xmrig_proxy::{ImgProxy, PubXmrigProxyApi};
 use super::{Helper, Process};
 
 pub mod algorithm;
+pub mod coordination;
+pub mod failover;
 pub mod nodes;
+pub mod predictive;
 pub mod priv_stats;
 pub mod public_stats;
+pub mod registration;
 pub mod rounds;
+pub mod siblings;
+#[cfg(test)]
+pub mod simulation;
+pub mod split;
+pub mod what_if;
 
 impl Helper {
     // Just sets some signals for the watchdog thread to pick up on.
@@ -119,6 +134,8 @@ impl Helper {
         let img_xmrig = Arc::clone(&helper.lock().unwrap().img_xmrig);
         let img_proxy = Arc::clone(&helper.lock().unwrap().img_proxy);
         let img_p2pool = Arc::clone(&helper.lock().unwrap().img_p2pool);
+        let xvb_history = Arc::clone(&helper.lock().unwrap().xvb_history);
+        let xvb_worker = Arc::clone(&helper.lock().unwrap().xvb_worker);
 
         // Reset before printing to output.
         // Need to reset because values of stats would stay otherwise which could bring confusion even if panel is with a disabled theme.
@@ -144,7 +161,7 @@ impl Helper {
 
         info!("XvB | spawn watchdog");
         thread::spawn(
-            enc!((state_xvb, state_p2pool, state_xmrig, state_xmrig,state_xp, img_xmrig, img_proxy, img_p2pool) move || {
+            enc!((state_xvb, state_p2pool, state_xmrig, state_xmrig,state_xp, img_xmrig, img_proxy, img_p2pool, xvb_history, xvb_worker) move || {
                 // thread priority, else there are issue on windows but it is also good for other OS
                     Self::spawn_xvb_watchdog(
                     &gui_api,
@@ -163,7 +180,8 @@ impl Helper {
                     &img_xmrig,
                     &img_proxy,
                     &img_p2pool,
-
+                    &xvb_history,
+                    &xvb_worker,
                 );
             }),
         );
@@ -190,9 +208,14 @@ impl Helper {
         xmrig_img: &Arc<Mutex<ImgXmrig>>,
         proxy_img: &Arc<Mutex<ImgProxy>>,
         p2pool_img: &Arc<Mutex<ImgP2pool>>,
+        xvb_history: &Arc<Mutex<XvbHistory>>,
+        xvb_worker: &Arc<Mutex<XvbWorker>>,
     ) {
         // create uniq client that is going to be used for during the life of the thread.
         let client = client();
+        pub_api.lock().unwrap().pools = state_xvb.pools.clone();
+        let accounts = state_xvb.accounts(&state_p2pool.address);
+        pub_api.lock().unwrap().accounts = accounts.clone();
         // checks confition to start XvB, will set proper state of XvB.
         // if state is middle (everything fine here),set which xvb node could be used.
         // should wait for it, because algo needs to not be started if at least one node of XvB are not responsive.
@@ -207,9 +230,35 @@ impl Helper {
             process_xmrig,
             process_xp,
             process,
-            state_p2pool,
+            &accounts,
         )
         .await;
+        // the leader answers the followers while the process runs.
+        let coordinator = if state_xvb.coordination == XvbCoordination::Leader {
+            let name = instance_name(&state_xvb.sibling_name);
+            match Coordinator::listen(
+                &state_xvb.coordination_address,
+                &state_xvb.coordination_key,
+                &name,
+            )
+            .await
+            {
+                Ok(coordinator) => Some(coordinator),
+                Err(err) => {
+                    warn!("XvB | Could not lead the coordination: {err}");
+                    output_console(
+                        &mut gui_api.lock().unwrap().output,
+                        &format!(
+                            "Could not listen for the followers, deciding alone.\nError: {err}"
+                        ),
+                        ProcessName::Xvb,
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
         let mut xp_alive = false;
         // uptime for log of signal check ?
         let start = process.lock().unwrap().start;
@@ -227,6 +276,13 @@ impl Helper {
         let handle_algo = Arc::new(Mutex::new(None));
         let handle_request = Arc::new(Mutex::new(None));
         let mut msg_retry_done = false;
+        // last time the history of the rounds accounted the time spent on XvB.
+        let mut last_history = std::time::Instant::now();
+        // addresses of the accounts, to know if one of them won.
+        let addresses = accounts
+            .iter()
+            .map(|a| a.address.clone())
+            .collect::<Vec<_>>();
 
         // let's create the memory of last hour average sent to p2pool and XvB
         // tuple (p2pool, xvb)
@@ -325,7 +381,7 @@ impl Helper {
                     // first_loop is false here but could be changed to true under some conditions.
                     // will send a stop signal if public stats failed or update data with new one.
                     *handle_request.lock().unwrap() = Some(spawn(
-                        enc!((client, pub_api, gui_api, gui_api_p2pool, gui_api_xmrig, gui_api_xp,  state_xvb, state_p2pool, state_xmrig,  process, last_algorithm, retry, handle_algo, time_donated, last_request, proxy_img, xmrig_img, process_p2pool, p2pool_img) async move {
+                        enc!((client, pub_api, gui_api, gui_api_p2pool, gui_api_xmrig, gui_api_xp,  state_xvb, state_p2pool, state_xmrig,  process, last_algorithm, retry, handle_algo, time_donated, last_request, proxy_img, xmrig_img, process_p2pool, p2pool_img, xvb_worker, accounts, addresses, coordinator) async move {
                                 // needs to wait here for public stats to get private stats.
                                 if last_request_expired || first_loop || should_refresh_before_next_algo {
                                 XvbPubStats::update_stats(&client, &gui_api, &pub_api, &process).await;
@@ -343,10 +399,12 @@ impl Helper {
                                     debug!("XvB Watchdog | Attempting HTTP private API request...");
                                     // reload private stats, it send a signal if error that will be captured on the upper thread.
                                     XvbPrivStats::update_stats(
-                                        &client, &state_p2pool.address, &pub_api, &gui_api, &process,
+                                        &client, &accounts, &pub_api, &gui_api, &process,
                                     )
                                     .await;
                                     *last_request.lock().unwrap() = Instant::now();
+                                    // donations of the other instances of the team
+                                    update_siblings(&client, &state_xvb, &pub_api, &gui_api).await;
 
                                     // verify in which round type we are
                                     let round = round_type(share, &pub_api);
@@ -354,7 +412,8 @@ impl Helper {
                                     debug!("XvB | Round type: {round:#?}");
                                     pub_api.lock().unwrap().stats_priv.round_participate = round;
                                     // verify if we are the winner of the current round
-                                    let win_current = pub_api.lock().unwrap().stats_pub.winner == Helper::head_tail_of_monero_address(&state_p2pool.address).as_str();                                    pub_api.lock().unwrap().stats_priv.win_current = win_current;
+                                    let win_current = addresses.iter().any(|a| pub_api.lock().unwrap().stats_pub.won_by(a));
+                                    pub_api.lock().unwrap().stats_priv.win_current = win_current;
                                 }
                                 let hashrate = current_controllable_hr(xp_alive, &gui_api_xp, &gui_api_xmrig);
                                 let difficulty_data_is_ready = gui_api_p2pool.lock().unwrap().p2pool_difficulty_u64 > 100_000;
@@ -364,12 +423,14 @@ impl Helper {
                                         *retry.lock().unwrap() = false;
                                         // reset instant because algo will start.
                                         *last_algorithm.lock().unwrap() = Instant::now();
-                                        *handle_algo.lock().unwrap() = Some(spawn(enc!((client, gui_api,  gui_api_xmrig, gui_api_xp, state_xmrig,  time_donated, state_xvb, proxy_img, xmrig_img, p2pool_img, process_p2pool) async move {
+                                        *handle_algo.lock().unwrap() = Some(spawn(enc!((client, gui_api,  gui_api_xmrig, gui_api_xp, state_xmrig,  time_donated, state_xvb, proxy_img, xmrig_img, p2pool_img, process_p2pool, xvb_worker, coordinator) async move {
                         let rig = if xp_alive {
                             ""
                         } else {
                             &state_xmrig.rig
                         };
+                                            // lead the other instances or follow the leader
+                                            let role = role(&client, &state_xvb, coordinator.as_ref(), &gui_api, hashrate).await;
                                             algorithm(
                                                 &client,
                                                 &pub_api,
@@ -386,7 +447,9 @@ impl Helper {
                                                 &proxy_img,
                                                 &xmrig_img,
                                                 &p2pool_img,
-                                                &process_p2pool
+                                                &process_p2pool,
+                                                &xvb_worker,
+                                                role
                                             ).await;
                                         })));
                                     } else {
@@ -431,6 +494,30 @@ impl Helper {
                     *time_donated.lock().unwrap(),
                     &last_algorithm,
                 );
+                // the worker mines with the threads of XMRig, it stops with it.
+                if !process_xmrig.lock().unwrap().is_alive() {
+                    let mut worker = xvb_worker.lock().unwrap();
+                    worker.stop();
+                    worker.main_threads = None;
+                }
+                let worker_pool = xvb_worker.lock().unwrap().pool();
+                pub_api.lock().unwrap().worker_pool = worker_pool;
+                // record the round of this hour, the time donated and the last raffle.
+                let elapsed = last_history.elapsed().as_secs();
+                last_history += Duration::from_secs(elapsed);
+                if process.lock().unwrap().state == ProcessState::Alive {
+                    let api = pub_api.lock().unwrap();
+                    let donated = donated_seconds(elapsed, &api, *time_donated.lock().unwrap());
+                    let now = chrono::Local::now().timestamp();
+                    let mut history = xvb_history.lock().unwrap();
+                    let hour_recorded = history.tick(now, donated, &api.stats_priv);
+                    let raffle_recorded = history.record_raffle(now, &api.stats_pub, &addresses);
+                    if (hour_recorded || raffle_recorded)
+                        && let Err(e) = history.save()
+                    {
+                        warn!("XvB | Could not save the history of the rounds: {e}");
+                    }
+                }
                 // first_loop is done, but maybe retry will allow the algorithm to retry again.
                 if first_loop {
                     first_loop = false;
@@ -439,6 +526,8 @@ impl Helper {
             }
             sleep_end_loop(start_loop, ProcessName::Xvb).await;
         }
+        // the worker must not keep mining on XvB without the XvB process.
+        stop_xvb_worker(&client, xvb_worker, xmrig_img).await;
     }
 }
 //---------------------------------------------------------------------------------------------------- Public XvB API
@@ -448,6 +537,8 @@ pub struct PubXvbApi {
     pub output: String,
     pub _uptime: u64,
     pub p2pool_sent_last_hour_samples: SamplesAverageHour,
+    // hashrate sent to XvB every cycle, for the predictive mode.
+    pub xvb_sent_last_hour_samples: SamplesAverageHour,
     pub stats_pub: XvbPubStats,
     pub stats_priv: XvbPrivStats,
     // where xmrig is mining right now (or trying to).
@@ -457,6 +548,20 @@ pub struct PubXvbApi {
     // Instead of watching stratum data that will account for HR sent only on this p2pool node,
     // Take the value of estimated HR that will account for external miners mininf on the same address.
     pub use_p2pool_sidechain_hr: bool,
+    // how the hashrate is split between P2Pool and XvB, set by the user.
+    pub split: XvbSplit,
+    // CPU threads mining on XvB while the hashrate is split by threads, set by the algorithm.
+    pub threads_xvb: u16,
+    // where the worker of the split by threads is mining, updated by the process.
+    pub worker_pool: Option<Pool>,
+    // last decisions of the algorithm, oldest first
+    pub decisions: VecDeque<Decision>,
+    // ms that should have been sent to XvB but were lost because the pool failed, set by the algorithm.
+    pub time_lost_xvb: u64,
+    // XvB pools set by the user when the process started, to recognize them in the console of XMRig.
+    pub pools: Vec<XvbPoolEndpoint>,
+    // XvB accounts set by the user when the process started, the first one is the XvB user.
+    pub accounts: Vec<XvbAccount>,
 }
 #[derive(Debug, Clone)]
 pub struct SamplesAverageHour(BoundedVecDeque<f32>);
@@ -489,6 +594,7 @@ impl PubXvbApi {
         let runtime_manual_amount = std::mem::take(&mut gui_api.stats_priv.runtime_manual_amount);
         let runtime_manual_donation_level =
             std::mem::take(&mut gui_api.stats_priv.runtime_manual_donation_level);
+        let runtime_goal = std::mem::take(&mut gui_api.stats_priv.runtime_goal);
         let pool = std::mem::take(&mut gui_api.stats_priv.pool);
         *gui_api = Self {
             output,
@@ -496,13 +602,19 @@ impl PubXvbApi {
                 runtime_mode,
                 runtime_manual_amount,
                 runtime_manual_donation_level,
+                runtime_goal,
                 pool,
                 ..pub_api.stats_priv.clone()
             },
             p2pool_sent_last_hour_samples: std::mem::take(
                 &mut gui_api.p2pool_sent_last_hour_samples,
             ),
+            xvb_sent_last_hour_samples: std::mem::take(&mut gui_api.xvb_sent_last_hour_samples),
             use_p2pool_sidechain_hr: std::mem::take(&mut gui_api.use_p2pool_sidechain_hr),
+            split: gui_api.split,
+            threads_xvb: gui_api.threads_xvb,
+            decisions: std::mem::take(&mut gui_api.decisions),
+            time_lost_xvb: gui_api.time_lost_xvb,
             ..pub_api.clone()
         };
     }
@@ -515,20 +627,25 @@ async fn check_conditions_for_start(
     process_xmrig: &Arc<Mutex<Process>>,
     process_xp: &Arc<Mutex<Process>>,
     process_xvb: &Arc<Mutex<Process>>,
-    state_p2pool: &crate::disk::state::P2pool,
+    accounts: &[XvbAccount],
 ) {
-    let state = if let Err(err) = XvbPrivStats::request_api(client, &state_p2pool.address).await {
+    let state = if let Err(err) = XvbPrivStats::request_accounts(client, accounts).await {
         info!("XvB | verify address");
         warn!(
-            "Xvb | Start ... Partially failed because address is not registered on XvB server: {err}\n"
+            "Xvb | Start ... Partially failed because address is not registered on XvB server: {err:#}\n"
         );
-        output_console(
-            &mut gui_api.lock().unwrap().output,
-            &format!(
-                "Address is not valid on XvB API.\nCheck if you are registered.\nError: {err}"
+        let msg = match err.downcast_ref::<AccountError>() {
+            Some(AccountError::NotRegistered) => format!(
+                "Address is not registered on XvB.\nRegister it with the button of the XvB tab or on the website of XvB.\nError: {err:#}"
             ),
-            ProcessName::Xvb,
-        );
+            Some(AccountError::InvalidToken) => format!(
+                "The token of the address is not accepted by XvB.\nCheck the token in the XvB accounts.\nError: {err:#}"
+            ),
+            None => format!(
+                "Address is not valid on XvB API.\nCheck if you are registered.\nError: {err:#}"
+            ),
+        };
+        output_console(&mut gui_api.lock().unwrap().output, &msg, ProcessName::Xvb);
         ProcessState::NotMining
     } else if process_p2pool.lock().unwrap().state != ProcessState::Alive {
         info!("XvB | verify p2pool pool");
@@ -830,7 +947,7 @@ fn signal_interrupt(
                 spawn(
                     enc!((pool, process, client, gui_api, pub_api, was_alive, address, token_xmrig, process_xrig, xmrig_img, proxy_img, process_p2pool, state_p2pool, p2pool_img, state_xvb) async move {
                     match pool {
-                        Pool::XvBNorthAmerica|Pool::XvBEurope if was_alive => {
+                        Pool::XvB(_) if was_alive => {
                             // a pool is failing. We need to first verify if a pool is available
                         Pool::update_fastest_pool( &gui_api, &pub_api, &process, &process_p2pool, &p2pool_img, &state_p2pool, &state_xvb).await;
                             if process.lock().unwrap().state == ProcessState::OfflinePoolsAll {
@@ -848,7 +965,7 @@ fn signal_interrupt(
 
                             
                         },
-                        Pool::XvBNorthAmerica|Pool::XvBEurope if !was_alive => {
+                        Pool::XvB(_) if !was_alive => {
                         // Probably a start. We don't consider XMRig using XvB pools without algo.
                         // can update xmrig and check status of state in the same time.
                         // update prefred pool
@@ -907,22 +1024,67 @@ fn reset_data_xvb(pub_api: &Arc<Mutex<PubXvbApi>>, gui_api: &Arc<Mutex<PubXvbApi
     let runtime_mode = mem::take(&mut gui_api.lock().unwrap().stats_priv.runtime_mode);
     let runtime_manual_amount =
         mem::take(&mut gui_api.lock().unwrap().stats_priv.runtime_manual_amount);
+    let runtime_goal = mem::take(&mut gui_api.lock().unwrap().stats_priv.runtime_goal);
     let use_sidechain_hr = mem::take(&mut gui_api.lock().unwrap().use_p2pool_sidechain_hr);
+    let split = gui_api.lock().unwrap().split;
+    let time_lost_xvb = gui_api.lock().unwrap().time_lost_xvb;
+    let pools = mem::take(&mut pub_api.lock().unwrap().pools);
+    let accounts = mem::take(&mut pub_api.lock().unwrap().accounts);
     let pool = mem::take(&mut gui_api.lock().unwrap().stats_priv.pool);
     // let output = mem::take(&mut gui_api.lock().unwrap().output);
     *pub_api.lock().unwrap() = PubXvbApi::new();
     *gui_api.lock().unwrap() = PubXvbApi::new();
     // to keep the value modified by xmrig even if xvb is dead.
     pub_api.lock().unwrap().current_pool = current_pool;
+    pub_api.lock().unwrap().pools = pools;
+    pub_api.lock().unwrap().accounts = accounts;
     // to keep pinged or manually chosen XvB pool
     gui_api.lock().unwrap().stats_priv.pool = pool;
     // to not loose the information of runtime hero mode between restart
     gui_api.lock().unwrap().stats_priv.runtime_mode = runtime_mode;
     gui_api.lock().unwrap().stats_priv.runtime_manual_amount = runtime_manual_amount;
+    gui_api.lock().unwrap().stats_priv.runtime_goal = runtime_goal;
     // message while starting must be preserved.
     // pub_api.lock().unwrap().output = output;
     // to not lose information about the use of sidechain hr
     gui_api.lock().unwrap().use_p2pool_sidechain_hr = use_sidechain_hr;
+    gui_api.lock().unwrap().split = split;
+    // a pool failing stops the algorithm, the time lost must still be sent when it starts again.
+    gui_api.lock().unwrap().time_lost_xvb = time_lost_xvb;
+}
+// Stop the worker mining on XvB and give back its threads to XMRig.
+async fn stop_xvb_worker(
+    client: &Client,
+    xvb_worker: &Arc<Mutex<XvbWorker>>,
+    xmrig_img: &Arc<Mutex<ImgXmrig>>,
+) {
+    let main_threads = {
+        let mut worker = xvb_worker.lock().unwrap();
+        worker.stop();
+        worker.main_threads.take()
+    };
+    if main_threads.is_some() {
+        let (api_url, token, threads) = {
+            let img = xmrig_img.lock().unwrap();
+            (
+                current_api_url_xrig(true, Some(&img), None),
+                img.token.clone(),
+                img.threads.parse().unwrap_or_default(),
+            )
+        };
+        if let Err(e) = update_xmrig_threads(client, &api_url, &token, threads).await {
+            warn!("XvB | Could not give back its threads to XMRig: {e}");
+        }
+    }
+}
+// Seconds of [elapsed] donated to XvB: all of them if the main miner is on XvB,
+// the share of the threads on XvB ([time_donated] of the cycle) if the worker is.
+pub fn donated_seconds(elapsed: u64, api: &PubXvbApi, time_donated: u64) -> u64 {
+    match (&api.current_pool, &api.worker_pool) {
+        (Some(Pool::XvB(_)), _) => elapsed,
+        (_, Some(Pool::XvB(_))) => elapsed * time_donated.min(XVB_TIME_ALGO) / XVB_TIME_ALGO,
+        _ => 0,
+    }
 }
 // print date time to console output in same format than xmrig
 fn update_indicator_algo(
@@ -937,9 +1099,15 @@ fn update_indicator_algo(
         && !is_algo_finished
         && process.lock().unwrap().state == ProcessState::Alive
     {
-        let pool = pub_api.lock().unwrap().current_pool.clone();
+        let (pool, worker_pool) = {
+            let api = pub_api.lock().unwrap();
+            (api.current_pool.clone(), api.worker_pool.clone())
+        };
         let msg_indicator = match pool {
-            Some(Pool::P2pool(_)) if time_donated > 0 && time_donated != XVB_TIME_ALGO => {
+            // while the threads are split, the main miner stays on P2Pool for the whole cycle.
+            Some(Pool::P2pool(_))
+                if worker_pool.is_none() && time_donated > 0 && time_donated != XVB_TIME_ALGO =>
+            {
                 // algo is mining on p2pool but will switch to XvB after
                 // show time remaining on p2pool
                 // todo: debug and fix brief 0s
@@ -975,7 +1143,7 @@ fn update_indicator_algo(
 
 // get the current HR of xmrig or xmrig-proxy
 // will get a longer average HR since it will be more accurate. Shorter timeframe can induce volatility.
-fn current_controllable_hr(
+pub(crate) fn current_controllable_hr(
     xp_alive: bool,
     gui_api_xp: &Arc<Mutex<PubXmrigProxyApi>>,
     gui_api_xmrig: &Arc<Mutex<PubXmrigApi>>,
@@ -15,20 +15,17 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{
-    net::{SocketAddr, ToSocketAddrs},
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex};
 
 use derive_more::Display;
 use log::{error, info, warn};
 use serde::Deserialize;
-use tokio::spawn;
+use tokio::{net::lookup_host, spawn};
 
 use crate::{
-    GUPAX_VERSION_UNDERSCORE, XVB_NODE_EU, XVB_NODE_NA, XVB_NODE_PORT,
+    GUPAX_VERSION_UNDERSCORE,
     components::node::TIMEOUT_NODE_PING,
-    disk::state::{P2pool, Xvb},
+    disk::state::{P2pool, Xvb, XvbPoolEndpoint},
     helper::{
         Process, ProcessName, ProcessSignal, ProcessState, p2pool::ImgP2pool, xvb::output_console,
     },
@@ -39,13 +36,10 @@ use crate::{
 };
 
 use super::PubXvbApi;
-#[derive(Clone, Debug, Default, PartialEq, Display, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Display, Deserialize)]
 pub enum Pool {
-    #[display("XvB North America Pool")]
-    XvBNorthAmerica,
-    #[default]
-    #[display("XvB European Pool")]
-    XvBEurope,
+    #[display("XvB {} Pool", _0.label)]
+    XvB(XvbPoolEndpoint),
     #[display("Local P2pool")]
     P2pool(u16),
     #[display("Xmrig Proxy")]
@@ -55,11 +49,16 @@ pub enum Pool {
     #[display("Not connected to any pool")]
     Unknown,
 }
+// the default XvB pool, used to ask the XvB process for the fastest one
+impl Default for Pool {
+    fn default() -> Self {
+        Self::XvB(XvbPoolEndpoint::default())
+    }
+}
 impl Pool {
     pub fn url(&self) -> String {
         match self {
-            Self::XvBNorthAmerica => String::from(XVB_NODE_NA),
-            Self::XvBEurope => String::from(XVB_NODE_EU),
+            Self::XvB(endpoint) => endpoint.host.clone(),
             Self::P2pool(_) => String::from("127.0.0.1"),
             Self::XmrigProxy(_) => String::from("127.0.0.1"),
             Self::Custom(url, _) => url.clone(),
@@ -68,7 +67,7 @@ impl Pool {
     }
     pub fn port(&self) -> String {
         match self {
-            Self::XvBNorthAmerica | Self::XvBEurope => String::from(XVB_NODE_PORT),
+            Self::XvB(endpoint) => endpoint.port.to_string(),
             Self::P2pool(port) => port.to_string(),
             Self::XmrigProxy(port) => port.to_string(),
             Self::Custom(_, port) => port.to_string(),
@@ -77,15 +76,13 @@ impl Pool {
     }
     pub fn user(&self, address: &str) -> String {
         match self {
-            Self::XvBNorthAmerica => address.chars().take(8).collect(),
-            Self::XvBEurope => address.chars().take(8).collect(),
+            Self::XvB(_) => address.chars().take(8).collect(),
             _ => GUPAX_VERSION_UNDERSCORE.to_string(),
         }
     }
     pub fn tls(&self) -> bool {
         match self {
-            Self::XvBNorthAmerica => true,
-            Self::XvBEurope => true,
+            Self::XvB(endpoint) => endpoint.tls,
             Self::P2pool(_) => false,
             Self::XmrigProxy(_) => false,
             Self::Custom(_, _) => false,
@@ -94,8 +91,7 @@ impl Pool {
     }
     pub fn keepalive(&self) -> bool {
         match self {
-            Self::XvBNorthAmerica => true,
-            Self::XvBEurope => true,
+            Self::XvB(_) => true,
             Self::P2pool(_) => false,
             Self::XmrigProxy(_) => false,
             Self::Custom(_, _) => false,
@@ -114,26 +110,27 @@ impl Pool {
         xvb_state: &Xvb,
     ) {
         // ping XvB nodes, or only one if set manual
-        let xvb_pools_to_ping = if xvb_state.manual_pool_enabled {
-            if xvb_state.manual_pool_eu {
-                vec![Pool::XvBEurope]
-            } else {
-                vec![Pool::XvBNorthAmerica]
-            }
-        } else {
-            vec![Pool::XvBNorthAmerica, Pool::XvBEurope]
-        };
+        let xvb_pools_to_ping = xvb_state
+            .pools_to_ping()
+            .into_iter()
+            .map(Pool::XvB)
+            .collect::<Vec<_>>();
 
         // prepare the ping job
         let mut handles = vec![];
         for pool in xvb_pools_to_ping.clone() {
             info!("XvB | ping {pool} XvB pool");
             handles.push(spawn(async move {
-                let socket_address = format!("{}:{}", pool.url(), pool.port())
-                    .to_socket_addrs()
-                    .expect("hardcored valued should always convert to SocketAddr")
-                    .collect::<Vec<SocketAddr>>()[0];
-                (port_ping(socket_address, TIMEOUT_NODE_PING).await, pool)
+                // the address is given by the user, it may not resolve.
+                let socket_address = lookup_host(format!("{}:{}", pool.url(), pool.port()))
+                    .await
+                    .ok()
+                    .and_then(|mut addrs| addrs.next());
+                let ping = match socket_address {
+                    Some(socket_address) => port_ping(socket_address, TIMEOUT_NODE_PING).await,
+                    None => Err(anyhow::anyhow!("{pool} address can not be resolved")),
+                };
+                (ping, pool)
             }));
         }
         // ping pools at the same time
@@ -206,6 +203,22 @@ impl Pool {
         }
         pub_api_xvb.lock().unwrap().stats_priv.pool = chosen_pool;
     }
+
+    // The next XvB pool of [pools] to fail over to.
+    pub fn other_xvb_pool(&self, pools: &[XvbPoolEndpoint]) -> Option<Pool> {
+        let Self::XvB(endpoint) = self else {
+            return None;
+        };
+        let index = pools.iter().position(|p| p == endpoint).unwrap_or(0);
+        pools
+            .iter()
+            .cycle()
+            .skip(index + 1)
+            .take(pools.len())
+            .find(|p| *p != endpoint)
+            .cloned()
+            .map(Self::XvB)
+    }
     pub fn update_current_pool(
         line: &str,
         proxy_port: u16,
@@ -229,7 +242,8 @@ impl Pool {
         if contains_usepool(&line) {
             // need to update current pool because it was updated.
             // if custom pool made by user, it is not supported because algo is deciding which pool to use.
-            let pool = detect_pool_xmrig(&line, proxy_port, p2pool_port);
+            let xvb_pools = pub_api_xvb.lock().unwrap().pools.clone();
+            let pool = detect_pool_xmrig(&line, proxy_port, p2pool_port, &xvb_pools);
 
             if pool.is_none() {
                 error!("{process} PTY Parse | pool is not understood, switching to backup.");
@@ -0,0 +1,259 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Planner of the predictive mode.
+// Instead of splitting every cycle between P2Pool and XvB, the spareable hashrate stays on one pool for
+// several cycles. The 1h average of XvB is projected forward with the hashrate sent by Gupax during
+// the last hour: the oldest cycles leave the average while the next ones enter it.
+// Mining stays on XvB until the projection reaches a high mark and stays on P2Pool while it is above a low mark,
+// which keeps the average around the target of the round with two switches per plan.
+// While the 24h average is under the target, XvB gets everything it can like the fast mode of the Auto mode.
+//
+// The share in the PPLNS window is protected all along the plan: every cycle on XvB keeps the minimum
+// hashrate to find a share on P2Pool, and the cycles on XvB end before our last share leaves the window.
+
+use crate::{XVB_SIDE_MARGIN_1H, XVB_TIME_ALGO};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub xvb: bool,
+    // cycles planned on this pool before switching, the plan is made again every cycle.
+    pub cycles: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forecast<'a> {
+    // H/s sent to XvB by Gupax every cycle of the last hour, oldest first
+    pub sent: &'a [f32],
+    // averages given by XvB, in H/s
+    pub avg_1h: f32,
+    pub avg_24h: f32,
+    pub hashrate: f32,
+    // H/s needed for the round
+    pub target: f32,
+    // mining on XvB during the last cycle
+    pub on_xvb: bool,
+    // H/s kept on P2Pool by every cycle on XvB to keep a share in the window
+    pub share_min_hashrate: f32,
+    // seconds before our last share leaves the PPLNS window, None if some shares are unknown.
+    // Given by [PplnsWindow::last_expiry].
+    pub share_expiry: Option<u64>,
+}
+
+impl Forecast<'_> {
+    // 1h average after [cycles] more cycles sending [value] H/s to XvB.
+    // Hashrate sent to XvB by other miners of the address is supposed to stay the same.
+    pub fn project(&self, cycles: usize, value: f32) -> f32 {
+        let len = self.sent.len().max(1) as f32;
+        let local: f32 = self.sent.iter().sum::<f32>() / len;
+        let external = (self.avg_1h - local).max(0.0);
+        let cycles = cycles.min(self.sent.len());
+        let kept: f32 = self.sent[cycles..].iter().sum();
+        external + (kept + value * cycles as f32) / len
+    }
+
+    // Limits of the 1h average. It stays above the target so that the 24h average does too.
+    pub fn marks(&self) -> (f32, f32) {
+        (self.target, self.target * (1.0 + XVB_SIDE_MARGIN_1H / 2.0))
+    }
+
+    // H/s sent to XvB by a cycle planned on XvB.
+    pub fn donated(&self) -> f32 {
+        (self.hashrate - self.share_min_hashrate).max(0.0)
+    }
+
+    // Cycles on XvB ending before our last share leaves the window.
+    // Without a known expiry, the plan does not go further than the next cycle.
+    pub fn max_xvb_cycles(&self) -> usize {
+        self.share_expiry
+            .map_or(1, |secs| (secs * 1000 / XVB_TIME_ALGO) as usize)
+    }
+
+    // First number of cycles after which [done] is true for the projection, an hour at most.
+    fn cycles_until(&self, value: f32, done: impl Fn(f32) -> bool) -> usize {
+        (1..=self.sent.len())
+            .find(|cycles| done(self.project(*cycles, value)))
+            .unwrap_or(self.sent.len())
+    }
+
+    pub fn plan(&self) -> Plan {
+        let donated = self.donated();
+        if self.target <= 0.0 || donated <= 0.0 {
+            return Plan {
+                xvb: false,
+                cycles: self.sent.len(),
+            };
+        }
+        let max_xvb_cycles = self.max_xvb_cycles();
+        if max_xvb_cycles == 0 {
+            // the share leaves the window during the next cycle, P2Pool must find a new one first.
+            return Plan {
+                xvb: false,
+                cycles: 1,
+            };
+        }
+        if self.avg_24h < self.target {
+            // cycles on XvB needed to fill the gap of the 24h average, if nothing leaves it.
+            let cycles =
+                ((self.target - self.avg_24h) * 24.0 * self.sent.len() as f32 / donated).ceil();
+            return Plan {
+                xvb: true,
+                cycles: (cycles as usize).min(max_xvb_cycles),
+            };
+        }
+        let (low, high) = self.marks();
+        let xvb = if self.on_xvb {
+            self.project(0, 0.0) < high
+        } else {
+            self.project(1, 0.0) < low
+        };
+        let cycles = if xvb {
+            self.cycles_until(donated, |avg| avg >= high)
+                .min(max_xvb_cycles)
+        } else {
+            // the cycle after which mining on P2Pool would bring the average under the low mark
+            self.cycles_until(0.0, |avg| avg < low)
+        };
+        Plan { xvb, cycles }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn forecast(sent: &[f32], avg_1h: f32, on_xvb: bool) -> Forecast<'_> {
+        Forecast {
+            sent,
+            avg_1h,
+            avg_24h: 1000.0,
+            hashrate: 4000.0,
+            target: 1000.0,
+            on_xvb,
+            share_min_hashrate: 0.0,
+            // far enough to not cut the plans
+            share_expiry: Some(24 * 3600),
+        }
+    }
+
+    #[test]
+    fn project_average() {
+        // 15 cycles on XvB at 4 kH/s a quarter of an hour ago
+        let mut sent = vec![0.0; 60];
+        sent[..15].fill(4000.0);
+        let f = forecast(&sent, 1000.0, false);
+        assert_eq!(f.project(0, 0.0), 1000.0);
+        assert_eq!(f.project(5, 0.0), 10.0 * 4000.0 / 60.0);
+        assert_eq!(f.project(15, 4000.0), 1000.0);
+        // 500 H/s sent by another rig of the address
+        let f = forecast(&sent, 1500.0, false);
+        assert_eq!(f.project(15, 0.0), 500.0);
+    }
+
+    #[test]
+    fn plan_blocks() {
+        let mut sent = vec![0.0; 60];
+        sent[..14].fill(4000.0);
+        // the next cycle on P2Pool would bring the average under the low mark, XvB until the high mark.
+        // the 14 cycles on XvB leaving the average are replaced before it rises.
+        let plan = forecast(&sent, 14.0 * 4000.0 / 60.0, false).plan();
+        assert_eq!(
+            plan,
+            Plan {
+                xvb: true,
+                cycles: 17
+            }
+        );
+        // already on XvB and under the high mark, stay
+        sent.rotate_left(14);
+        assert!(forecast(&sent, 14.0 * 4000.0 / 60.0, true).plan().xvb);
+        // above the high mark, P2Pool until the average gets near the low mark
+        let mut sent = vec![0.0; 60];
+        sent[40..].fill(4000.0);
+        let plan = forecast(&sent, 4000.0 * 20.0 / 60.0, true).plan();
+        assert!(!plan.xvb);
+        assert_eq!(plan.cycles, 46);
+        // without target everything goes to P2Pool
+        let mut f = forecast(&sent, 0.0, true);
+        f.target = 0.0;
+        assert!(!f.plan().xvb);
+        // while the 24h average is under the target, everything goes to XvB
+        let mut sent = vec![0.0; 60];
+        sent[..16].fill(4200.0);
+        let mut f = forecast(&sent, 16.0 * 4200.0 / 60.0, false);
+        assert!(!f.plan().xvb);
+        f.avg_24h = 900.0;
+        assert_eq!(
+            f.plan(),
+            Plan {
+                xvb: true,
+                cycles: 36
+            }
+        );
+    }
+
+    #[test]
+    fn plan_keeps_the_share() {
+        let mut sent = vec![0.0; 60];
+        sent[..14].fill(4000.0);
+        let mut f = forecast(&sent, 14.0 * 4000.0 / 60.0, false);
+        // 2 kH/s stay on P2Pool, the 2 kH/s left need 33 cycles to reach the high mark
+        f.share_min_hashrate = 2000.0;
+        assert_eq!(f.donated(), 2000.0);
+        assert_eq!(
+            f.plan(),
+            Plan {
+                xvb: true,
+                cycles: 33
+            }
+        );
+        // the last share leaves the window in 10 and a half cycles, the plan ends before
+        f.share_expiry = Some(10 * 60 + 30);
+        assert_eq!(
+            f.plan(),
+            Plan {
+                xvb: true,
+                cycles: 10
+            }
+        );
+        // the 24h average under the target is cut too
+        f.avg_24h = 900.0;
+        assert_eq!(f.plan().cycles, 10);
+        // the share leaves the window during the next cycle
+        f.share_expiry = Some(30);
+        assert_eq!(
+            f.plan(),
+            Plan {
+                xvb: false,
+                cycles: 1
+            }
+        );
+        // unknown expiry, no further than the next cycle
+        f.share_expiry = None;
+        assert_eq!(
+            f.plan(),
+            Plan {
+                xvb: true,
+                cycles: 1
+            }
+        );
+        // nothing left above the minimum to keep a share
+        f.share_min_hashrate = 4000.0;
+        assert!(!f.plan().xvb);
+    }
+}
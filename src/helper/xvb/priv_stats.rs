@@ -21,6 +21,7 @@ use std::{
 };
 
 use anyhow::bail;
+use derive_more::Display;
 use log::{debug, error, info, warn};
 use reqwest::StatusCode;
 use reqwest_middleware::ClientWithMiddleware as Client;
@@ -32,8 +33,8 @@ use crate::{
 };
 use crate::{
     XVB_URL,
-    disk::state::ManualDonationLevel,
-    helper::{Process, ProcessName, ProcessState, xvb::output_console},
+    disk::state::{ManualDonationLevel, Xvb, XvbAccount, XvbGoal},
+    helper::{Helper, Process, ProcessName, ProcessState, xvb::output_console},
 };
 
 use super::{PubXvbApi, nodes::Pool, rounds::XvbRound};
@@ -46,6 +47,8 @@ pub enum RuntimeMode {
     ManualP2pool,
     Hero,
     ManualDonationLevel,
+    Predictive,
+    Goal,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
@@ -68,6 +71,39 @@ impl RuntimeDonationLevel {
     }
 }
 
+// Constraints of the goal mode, set by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeGoal {
+    pub goal: XvbGoal,
+    pub round: RuntimeDonationLevel,
+    // % of the hashrate donated on average over 24h
+    pub percent: f32,
+    // % of the hashrate never exceeded
+    pub cap: f32,
+}
+
+impl Default for RuntimeGoal {
+    fn default() -> Self {
+        Self {
+            goal: XvbGoal::default(),
+            round: RuntimeDonationLevel::default(),
+            percent: 0.0,
+            cap: 100.0,
+        }
+    }
+}
+
+impl From<&Xvb> for RuntimeGoal {
+    fn from(state: &Xvb) -> Self {
+        Self {
+            goal: state.goal,
+            round: state.goal_round.clone().into(),
+            percent: state.goal_percent,
+            cap: state.goal_cap,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct XvbPrivStats {
     pub fails: u8,
@@ -92,19 +128,42 @@ pub struct XvbPrivStats {
     pub runtime_manual_amount: f64,
     #[serde(skip)]
     pub runtime_manual_donation_level: RuntimeDonationLevel,
+    #[serde(skip)]
+    pub runtime_goal: RuntimeGoal,
+    #[serde(skip)]
+    // H/s donated during the last hour by the other instances of Gupax reporting to the same siblings.
+    pub siblings_hashrate: f32,
 }
 
+// Answers of XvB about the account itself, to tell the user what to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum AccountError {
+    #[display("the address is not registered")]
+    NotRegistered,
+    #[display("the token is not valid for this address")]
+    InvalidToken,
+}
+
+impl std::error::Error for AccountError {}
+
 impl XvbPrivStats {
-    pub async fn request_api(client: &Client, address: &str) -> anyhow::Result<Self> {
+    pub async fn request_api(client: &Client, account: &XvbAccount) -> anyhow::Result<Self> {
+        Self::request_api_from(client, XVB_URL, account).await
+    }
+    // [url] of XvB, changed only by the tests.
+    pub async fn request_api_from(
+        client: &Client,
+        url: &str,
+        account: &XvbAccount,
+    ) -> anyhow::Result<Self> {
+        let mut query = vec![("address", account.address.as_str())];
+        // the token is not documented by XvB yet, it is only sent for an account that has one.
+        if !account.token.is_empty() {
+            query.push(("token", account.token.as_str()));
+        }
         let resp = client
-            .get(
-                [
-                    XVB_URL,
-                    "/cgi-bin/p2pool_bonus_history_gupaxx_api.cgi?address=",
-                    address,
-                ]
-                .concat(),
-            )
+            .get([url, "/cgi-bin/p2pool_bonus_history_gupaxx_api.cgi"].concat())
+            .query(&query)
             .timeout(Duration::from_secs(5))
             .send()
             .await?;
@@ -118,20 +177,49 @@ impl XvbPrivStats {
                     bail!("Data provided from private API is not deserializ-able.Error: {err}");
                 }
             },
-            StatusCode::UNPROCESSABLE_ENTITY => {
-                bail!("the address is not registered")
-            }
+            StatusCode::UNPROCESSABLE_ENTITY => bail!(AccountError::NotRegistered),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => bail!(AccountError::InvalidToken),
             _ => bail!("The status of the response is not expected"),
         }
     }
+    // Private stats of every account, added together.
+    pub async fn request_accounts(
+        client: &Client,
+        accounts: &[XvbAccount],
+    ) -> anyhow::Result<Self> {
+        Self::request_accounts_from(client, XVB_URL, accounts).await
+    }
+    async fn request_accounts_from(
+        client: &Client,
+        url: &str,
+        accounts: &[XvbAccount],
+    ) -> anyhow::Result<Self> {
+        let mut stats = Self::default();
+        for account in accounts {
+            match Self::request_api_from(client, url, account).await {
+                Ok(new_data) => stats.add(&new_data),
+                // the context keeps the [AccountError] reachable.
+                Err(err) => {
+                    return Err(err.context(Helper::head_tail_of_monero_address(&account.address)));
+                }
+            }
+        }
+        Ok(stats)
+    }
+    // The donations of the accounts count for the same rounds, so the averages are summed.
+    pub fn add(&mut self, other: &Self) {
+        self.fails = self.fails.saturating_add(other.fails);
+        self.donor_1hr_avg += other.donor_1hr_avg;
+        self.donor_24hr_avg += other.donor_24hr_avg;
+    }
     pub async fn update_stats(
         client: &Client,
-        address: &str,
+        accounts: &[XvbAccount],
         pub_api: &Arc<Mutex<PubXvbApi>>,
         gui_api: &Arc<Mutex<PubXvbApi>>,
         process: &Arc<Mutex<Process>>,
     ) {
-        match XvbPrivStats::request_api(client, address).await {
+        match XvbPrivStats::request_accounts(client, accounts).await {
             Ok(new_data) => {
                 debug!("XvB Watchdog | HTTP API request OK");
                 pub_api.lock().unwrap().stats_priv.fails = new_data.fails;
@@ -151,7 +239,7 @@ impl XvbPrivStats {
             }
             Err(err) => {
                 warn!(
-                    "XvB Watchdog | Could not send HTTP private API request to: {XVB_URL}\n:{err}"
+                    "XvB Watchdog | Could not send HTTP private API request to: {XVB_URL}\n:{err:#}"
                 );
                 if process.lock().unwrap().state != ProcessState::Failed {
                     output_console(
@@ -180,6 +268,8 @@ impl From<XvbMode> for RuntimeMode {
             XvbMode::ManualP2pool => Self::ManualP2pool,
             XvbMode::Hero => Self::Hero,
             XvbMode::ManualDonationLevel => Self::ManualDonationLevel,
+            XvbMode::Predictive => Self::Predictive,
+            XvbMode::Goal => Self::Goal,
         }
     }
 }
@@ -194,3 +284,77 @@ impl From<ManualDonationLevel> for RuntimeDonationLevel {
         }
     }
 }
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::miscs::client;
+    use crate::utils::http_mock::mock_http;
+
+    // Start a fake XvB knowing "first" without a token and "second" with the token "a+b/c".
+    fn mock_xvb() -> String {
+        let addr = mock_http(|request| {
+            let query = request
+                .path
+                .strip_prefix("/cgi-bin/p2pool_bonus_history_gupaxx_api.cgi?")
+                .unwrap_or_default();
+            match query {
+                "address=first" => (
+                    "200 OK",
+                    r#"{"fails":1,"donor_1hr_avg":1.5,"donor_24hr_avg":3.0}"#.to_string(),
+                ),
+                // the token is URL-encoded
+                "address=second&token=a%2Bb%2Fc" => (
+                    "200 OK",
+                    r#"{"fails":0,"donor_1hr_avg":2.5,"donor_24hr_avg":5.0}"#.to_string(),
+                ),
+                q if q.starts_with("address=second") => ("401 Unauthorized", String::new()),
+                _ => ("422 Unprocessable Entity", String::new()),
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    fn account(address: &str, token: &str) -> XvbAccount {
+        XvbAccount {
+            address: address.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    #[test]
+    fn private_stats_accounts() {
+        let url = mock_xvb();
+        let client = client();
+        let request = |accounts: &[XvbAccount]| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(XvbPrivStats::request_accounts_from(&client, &url, accounts))
+        };
+        // the private stats of the accounts are added together
+        let stats = request(&[account("first", ""), account("second", "a+b/c")]).unwrap();
+        assert_eq!(stats.fails, 1);
+        assert_eq!(stats.donor_1hr_avg, 4.0);
+        assert_eq!(stats.donor_24hr_avg, 8.0);
+        // the error of the failing account is kept
+        let err = request(&[account("first", ""), account("second", "wrong")]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<AccountError>(),
+            Some(&AccountError::InvalidToken)
+        );
+        // the second account is not accepted without its token
+        let err = request(&[account("second", ""), account("unknown", "")]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<AccountError>(),
+            Some(&AccountError::InvalidToken)
+        );
+        let err = request(&[account("unknown", "")]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<AccountError>(),
+            Some(&AccountError::NotRegistered)
+        );
+    }
+}
@@ -70,6 +70,23 @@ impl XvbPubStats {
         }
         vec
     }
+    // The winner is given by XvB as the head and tail of its address ("44hintoF...z7Vi9CYW").
+    // The separator and the spaces around are not compared, the full [address] must start with the head and end with the tail.
+    pub fn won_by(&self, address: &str) -> bool {
+        let winner = self.winner.trim();
+        match winner.split_once(['.', '…']) {
+            Some((head, tail)) => {
+                let tail = tail.trim_start_matches(['.', '…']).trim();
+                let head = head.trim();
+                !head.is_empty()
+                    && !tail.is_empty()
+                    && address.len() >= head.len() + tail.len()
+                    && address.starts_with(head)
+                    && address.ends_with(tail)
+            }
+            None => !winner.is_empty() && winner == address,
+        }
+    }
     #[inline]
     // Send an HTTP request to XvB's API, serialize it into [Self] and return it
     pub(in crate::helper) async fn request_api(
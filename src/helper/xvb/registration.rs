@@ -0,0 +1,327 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Registration of the address to XvB from the XvB tab.
+// The address is first verified with the private stats, the same request the XvB process does when starting.
+// If it is not registered, it is sent by POST as {"address": address} to the registration endpoint,
+// which answers {"token": token}. The token is stored with the address in the XvB accounts, then the
+// registration is verified again with it.
+// XvB can refuse the registration, for example if the address has no share in the PPLNS window yet.
+// The endpoint is set in the XvB tab, it is the one of XvB by default.
+
+use std::{
+    sync::{Arc, Mutex},
+    thread::spawn,
+    time::Duration,
+};
+
+use derive_more::Display;
+use enclose::enc;
+use log::{info, warn};
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware as Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    XVB_URL,
+    disk::state::XvbAccount,
+    helper::xvb::priv_stats::{AccountError, XvbPrivStats},
+    miscs::client,
+};
+
+#[derive(Debug, Clone, PartialEq, Default, Display)]
+pub enum RegistrationStatus {
+    #[default]
+    #[display("not verified")]
+    Unknown,
+    #[display("verifying...")]
+    Verifying,
+    #[display("registering...")]
+    Registering,
+    #[display("registered")]
+    Registered,
+    #[display("not registered")]
+    NotRegistered,
+    #[display("the token is not valid for this address")]
+    InvalidToken,
+    #[display("the address is not valid for XvB")]
+    InvalidAddress,
+    #[display("refused by XvB: {_0}")]
+    Refused(String),
+    #[display("XvB could not be reached: {_0}")]
+    Unreachable(String),
+}
+
+impl RegistrationStatus {
+    // Shown in red in the XvB tab.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidToken | Self::InvalidAddress | Self::Refused(_) | Self::Unreachable(_)
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RegisterRequest<'a> {
+    address: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegisterResponse {
+    #[serde(default)]
+    token: String,
+}
+
+// Verify the registration of the account with its private stats.
+pub async fn verify(client: &Client, url: &str, account: &XvbAccount) -> RegistrationStatus {
+    match XvbPrivStats::request_api_from(client, url, account).await {
+        Ok(_) => RegistrationStatus::Registered,
+        Err(err) => match err.downcast_ref::<AccountError>() {
+            Some(AccountError::NotRegistered) => RegistrationStatus::NotRegistered,
+            Some(AccountError::InvalidToken) => RegistrationStatus::InvalidToken,
+            None => RegistrationStatus::Unreachable(format!("{err:#}")),
+        },
+    }
+}
+
+// Send the address to the registration [endpoint], the token of the registration is returned.
+pub async fn register(
+    client: &Client,
+    endpoint: &str,
+    address: &str,
+) -> Result<String, RegistrationStatus> {
+    let body = serde_json::to_string(&RegisterRequest { address })
+        .map_err(|e| RegistrationStatus::Unreachable(e.to_string()))?;
+    let resp = client
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| RegistrationStatus::Unreachable(format!("{e:#}")))?;
+    match resp.status() {
+        StatusCode::OK | StatusCode::CREATED => resp
+            .json::<RegisterResponse>()
+            .await
+            .map(|r| r.token)
+            .map_err(|e| RegistrationStatus::Unreachable(e.to_string())),
+        StatusCode::UNPROCESSABLE_ENTITY | StatusCode::BAD_REQUEST => {
+            Err(RegistrationStatus::InvalidAddress)
+        }
+        // the reason is given by XvB
+        status => {
+            let reason = resp.text().await.unwrap_or_default();
+            let reason = reason.trim();
+            Err(RegistrationStatus::Refused(if reason.is_empty() {
+                status.to_string()
+            } else {
+                reason.to_string()
+            }))
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- XvbRegistration
+#[derive(Debug, Default)]
+pub struct XvbRegistration {
+    pub busy: bool,
+    // address the status is about
+    pub address: String,
+    pub status: RegistrationStatus,
+    // token given by XvB, to be stored in the accounts by the XvB tab
+    pub token: Option<String>,
+}
+
+impl XvbRegistration {
+    // Verify the account, and register its address to [endpoint] if it is not registered.
+    // Only verified without an endpoint.
+    pub fn start(registration: &Arc<Mutex<Self>>, account: XvbAccount, endpoint: Option<String>) {
+        let mut lock = registration.lock().unwrap();
+        if lock.busy {
+            return;
+        }
+        lock.busy = true;
+        lock.address = account.address.clone();
+        lock.status = RegistrationStatus::Verifying;
+        drop(lock);
+        info!("Spawning XvB registration thread...");
+        spawn(enc!((registration) move || {
+            Self::run(&registration, XVB_URL, endpoint.as_deref(), account);
+            registration.lock().unwrap().busy = false;
+        }));
+    }
+
+    // [url] of the private stats of XvB, changed only by the tests.
+    #[tokio::main]
+    pub async fn run(
+        registration: &Arc<Mutex<Self>>,
+        url: &str,
+        endpoint: Option<&str>,
+        mut account: XvbAccount,
+    ) {
+        let client = client();
+        let mut status = verify(&client, url, &account).await;
+        info!("XvB Registration | verify ... {status}");
+        if let Some(endpoint) = endpoint
+            && status == RegistrationStatus::NotRegistered
+        {
+            registration.lock().unwrap().status = RegistrationStatus::Registering;
+            status = match register(&client, endpoint, &account.address).await {
+                Ok(token) => {
+                    info!("XvB Registration | register ... OK");
+                    account.token = token.clone();
+                    registration.lock().unwrap().token = Some(token);
+                    verify(&client, url, &account).await
+                }
+                Err(status) => status,
+            };
+        }
+        if status.is_error() {
+            warn!("XvB Registration | {status}");
+        }
+        registration.lock().unwrap().status = status;
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::http_mock::mock_http;
+    use std::collections::BTreeMap;
+
+    const ADDRESS: &str = "44hintoFpuo3ugKfcqJvh5BmrsTRpnTasJmetKC4VXCt6QDtbHVuixdTtsm6Ptp7Y8haXnJ6j8Gj2dra8CKy5ewz7Vi9CYW";
+    const REGISTER: &str = "/register";
+
+    // Start a fake XvB with the registered addresses and their token, registering on [REGISTER].
+    // The address "refused" can not be registered.
+    fn mock_xvb(registered: BTreeMap<String, String>) -> String {
+        let mut registered = registered;
+        let addr = mock_http(move |request| {
+            let path = &request.path;
+            if let Some(query) =
+                path.strip_prefix("/cgi-bin/p2pool_bonus_history_gupaxx_api.cgi?address=")
+            {
+                let (address, token) = query.split_once("&token=").unwrap_or((query, ""));
+                match registered.get(address) {
+                    None => ("422 Unprocessable Entity", String::new()),
+                    Some(t) if t != token => ("401 Unauthorized", String::new()),
+                    Some(_) => (
+                        "200 OK",
+                        r#"{"fails":0,"donor_1hr_avg":0.0,"donor_24hr_avg":0.0}"#.to_string(),
+                    ),
+                }
+            } else if path == REGISTER {
+                assert_eq!(request.method, "POST");
+                let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+                match body["address"].as_str().unwrap() {
+                    "refused" => ("403 Forbidden", "no share in the PPLNS window".to_string()),
+                    "" => ("422 Unprocessable Entity", String::new()),
+                    address => {
+                        let token = format!("token{}", registered.len());
+                        registered.insert(address.to_string(), token.clone());
+                        ("200 OK", format!(r#"{{"token":"{token}"}}"#))
+                    }
+                }
+            } else {
+                ("404 Not Found", String::new())
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    fn account(address: &str, token: &str) -> XvbAccount {
+        XvbAccount {
+            address: address.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    #[test]
+    fn registration_flow() {
+        let url = mock_xvb(BTreeMap::from([("registered".to_string(), String::new())]));
+        let endpoint = [url.as_str(), REGISTER].concat();
+        let run = |account: XvbAccount, register: bool| {
+            let registration = Arc::new(Mutex::new(XvbRegistration::default()));
+            XvbRegistration::run(
+                &registration,
+                &url,
+                register.then_some(endpoint.as_str()),
+                account,
+            );
+            let lock = registration.lock().unwrap();
+            (lock.status.clone(), lock.token.clone())
+        };
+        // already registered, nothing is sent
+        assert_eq!(
+            run(account("registered", ""), true),
+            (RegistrationStatus::Registered, None)
+        );
+        // only verified
+        assert_eq!(
+            run(account(ADDRESS, ""), false),
+            (RegistrationStatus::NotRegistered, None)
+        );
+        // registered, the token is given and accepted
+        assert_eq!(
+            run(account(ADDRESS, ""), true),
+            (RegistrationStatus::Registered, Some("token1".to_string()))
+        );
+        assert_eq!(
+            run(account(ADDRESS, "token1"), false),
+            (RegistrationStatus::Registered, None)
+        );
+        assert_eq!(
+            run(account(ADDRESS, "other"), true),
+            (RegistrationStatus::InvalidToken, None)
+        );
+        // refused by XvB, with its reason
+        let (status, token) = run(account("refused", ""), true);
+        assert_eq!(
+            status,
+            RegistrationStatus::Refused("no share in the PPLNS window".to_string())
+        );
+        assert!(status.is_error());
+        assert_eq!(token, None);
+        // nothing listens there
+        let registration = Arc::new(Mutex::new(XvbRegistration::default()));
+        XvbRegistration::run(
+            &registration,
+            "http://127.0.0.1:1",
+            Some("http://127.0.0.1:1/register"),
+            account(ADDRESS, ""),
+        );
+        assert!(matches!(
+            registration.lock().unwrap().status,
+            RegistrationStatus::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn registration_invalid_address() {
+        let url = mock_xvb(BTreeMap::new());
+        let client = client();
+        let status = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(register(&client, &[url.as_str(), REGISTER].concat(), ""));
+        assert_eq!(status, Err(RegistrationStatus::InvalidAddress));
+    }
+}
@@ -18,7 +18,7 @@
 use std::sync::{Arc, Mutex};
 
 use derive_more::Display;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use crate::{
@@ -27,7 +27,7 @@ use crate::{
 };
 
 use super::PubXvbApi;
-#[derive(Debug, Clone, Default, Display, Deserialize, PartialEq, EnumIter)]
+#[derive(Debug, Clone, Default, Display, Deserialize, Serialize, PartialEq, EnumIter)]
 pub enum XvbRound {
     #[default]
     #[display("VIP")]
@@ -86,3 +86,14 @@ pub(crate) fn round_type(share: u32, pub_api: &Arc<Mutex<PubXvbApi>>) -> Option<
         None
     }
 }
+
+/// The round reached by sending [hashrate] to XvB on average, with a share in the PPLNS window.
+pub(crate) fn donor_round(hashrate: f32) -> XvbRound {
+    match hashrate as u32 {
+        x if x >= XVB_ROUND_DONOR_MEGA_MIN_HR => XvbRound::DonorMega,
+        x if x >= XVB_ROUND_DONOR_WHALE_MIN_HR => XvbRound::DonorWhale,
+        x if x >= XVB_ROUND_DONOR_VIP_MIN_HR => XvbRound::DonorVip,
+        x if x >= XVB_ROUND_DONOR_MIN_HR => XvbRound::Donor,
+        _ => XvbRound::Vip,
+    }
+}
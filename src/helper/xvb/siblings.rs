@@ -0,0 +1,346 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Donations of the sibling instances of Gupax.
+// Machines of a team can donate to the same XvB registration, each one running its own algorithm.
+// Every instance reports the hashrate it sent to XvB during the last hour, keyed by its name, either in a
+// shared file (on a network folder for example) or to an endpoint. The reports of the other instances are
+// external XvB hashrate for the algorithm, which then only sends what is missing for the round.
+// The endpoint receives the report of the instance by POST as {name: report} and answers with every report.
+
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::bail;
+use log::{debug, warn};
+use reqwest_middleware::ClientWithMiddleware as Client;
+use serde::{Deserialize, Serialize};
+
+use crate::{helper::ProcessName, miscs::output_console, utils::constants::XVB_SIBLING_TIMEOUT};
+
+use super::PubXvbApi;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct SiblingReport {
+    // H/s sent to XvB during the last hour
+    pub hashrate: f32,
+    // unix timestamp of the report
+    pub timestamp: i64,
+}
+
+pub type SiblingReports = BTreeMap<String, SiblingReport>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Siblings {
+    File(PathBuf),
+    Endpoint(String),
+}
+
+impl Siblings {
+    // None if the user did not set any.
+    pub fn new(source: &str) -> Option<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            None
+        } else if source.starts_with("http://") || source.starts_with("https://") {
+            Some(Self::Endpoint(source.to_string()))
+        } else {
+            Some(Self::File(PathBuf::from(source)))
+        }
+    }
+
+    // Report the hashrate of this instance and get the reports of every instance.
+    pub async fn exchange(
+        &self,
+        client: &Client,
+        name: &str,
+        report: SiblingReport,
+    ) -> anyhow::Result<SiblingReports> {
+        match self {
+            Self::File(path) => {
+                // the instances update the file one at a time, no report is lost.
+                let _lock = FileLock::acquire(path.with_extension("lock")).await?;
+                // a file not created yet has no report.
+                let mut reports = tokio::fs::read_to_string(path)
+                    .await
+                    .ok()
+                    .and_then(|s| serde_json::from_str::<SiblingReports>(&s).ok())
+                    .unwrap_or_default();
+                reports.insert(name.to_string(), report);
+                // the others must never read a half written file.
+                let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+                tokio::fs::write(&tmp, serde_json::to_string_pretty(&reports)?).await?;
+                tokio::fs::rename(&tmp, path).await?;
+                Ok(reports)
+            }
+            Self::Endpoint(url) => {
+                let resp = client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(serde_json::to_string(&SiblingReports::from([(
+                        name.to_string(),
+                        report,
+                    )]))?)
+                    .timeout(Duration::from_secs(5))
+                    .send()
+                    .await?;
+                if !resp.status().is_success() {
+                    bail!("the endpoint answered with status {}", resp.status());
+                }
+                Ok(resp.json::<SiblingReports>().await?)
+            }
+        }
+    }
+}
+
+// Lock of the file next to the shared file, released when dropped or if the instance stops.
+// The lock file is never removed, an instance could still be waiting on it.
+struct FileLock(std::fs::File);
+
+impl FileLock {
+    // Wait for the other instances to release the lock.
+    async fn acquire(path: PathBuf) -> anyhow::Result<Self> {
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .await?
+            .into_std()
+            .await;
+        for _ in 0..50 {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self(file)),
+                Err(std::fs::TryLockError::WouldBlock) => {
+                    tokio::time::sleep(Duration::from_millis(100)).await
+                }
+                Err(std::fs::TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+        bail!("the siblings file is locked by another instance")
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+// H/s sent to XvB by the other instances that reported recently.
+pub fn external_hashrate(reports: &SiblingReports, name: &str, now: i64) -> f32 {
+    reports
+        .iter()
+        .filter(|(n, r)| *n != name && now - r.timestamp <= XVB_SIBLING_TIMEOUT)
+        .map(|(_, r)| r.hashrate)
+        .sum()
+}
+
+// Name of this instance in the reports, the host name if the user did not set any.
+pub fn instance_name(sibling_name: &str) -> String {
+    if sibling_name.is_empty() {
+        sysinfo::System::host_name().unwrap_or_else(|| "Gupax".to_string())
+    } else {
+        sibling_name.to_string()
+    }
+}
+
+// Exchange the reports and keep the hashrate of the siblings for the algorithm.
+// Without reports, the siblings are considered to donate nothing so the rounds are not missed.
+pub async fn update_siblings(
+    client: &Client,
+    state_xvb: &crate::disk::state::Xvb,
+    pub_api: &Arc<Mutex<PubXvbApi>>,
+    gui_api: &Arc<Mutex<PubXvbApi>>,
+) {
+    let Some(siblings) = Siblings::new(&state_xvb.siblings) else {
+        return;
+    };
+    let name = instance_name(&state_xvb.sibling_name);
+    let hashrate = {
+        let samples = &gui_api.lock().unwrap().xvb_sent_last_hour_samples.0;
+        samples.iter().sum::<f32>() / samples.len() as f32
+    };
+    let now = chrono::Local::now().timestamp();
+    let report = SiblingReport {
+        hashrate,
+        timestamp: now,
+    };
+    let siblings_hashrate = match siblings.exchange(client, &name, report).await {
+        Ok(reports) => {
+            debug!("XvB | Reports of the siblings: {reports:?}");
+            external_hashrate(&reports, &name, now)
+        }
+        Err(err) => {
+            warn!("XvB | Could not exchange the reports with the siblings: {err}");
+            output_console(
+                &mut gui_api.lock().unwrap().output,
+                &format!("Could not get the donations of the other instances.\nError: {err}"),
+                ProcessName::Xvb,
+            );
+            0.0
+        }
+    };
+    pub_api.lock().unwrap().stats_priv.siblings_hashrate = siblings_hashrate;
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helper::xvb::algorithm::Stats;
+    use crate::helper::xvb::priv_stats::{RuntimeDonationLevel, RuntimeMode, XvbPrivStats};
+    use crate::helper::xvb::simulation::nano_stats;
+
+    #[test]
+    fn siblings_source() {
+        assert_eq!(Siblings::new(" "), None);
+        assert_eq!(
+            Siblings::new("http://192.168.1.10:8080/siblings"),
+            Some(Siblings::Endpoint(
+                "http://192.168.1.10:8080/siblings".to_string()
+            ))
+        );
+        assert_eq!(
+            Siblings::new("/mnt/team/siblings.json"),
+            Some(Siblings::File(PathBuf::from("/mnt/team/siblings.json")))
+        );
+    }
+
+    #[test]
+    fn siblings_file() {
+        let path =
+            std::env::temp_dir().join(format!("gupax_siblings_test_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let siblings = Siblings::new(path.to_str().unwrap()).unwrap();
+        let client = crate::miscs::client();
+        let exchange = |name: &str, hashrate: f32, timestamp: i64| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(siblings.exchange(
+                    &client,
+                    name,
+                    SiblingReport {
+                        hashrate,
+                        timestamp,
+                    },
+                ))
+                .unwrap()
+        };
+        let now = 1_700_000_000;
+        exchange("rig1", 5_000.0, now - XVB_SIBLING_TIMEOUT - 1);
+        exchange("rig2", 2_000.0, now);
+        let reports = exchange("rig3", 1_000.0, now);
+        assert_eq!(reports.len(), 3);
+        // the report of rig1 is too old
+        assert_eq!(external_hashrate(&reports, "rig3", now), 2_000.0);
+        assert_eq!(external_hashrate(&reports, "rig2", now), 1_000.0);
+        // a new report replaces the last one of the instance
+        let reports = exchange("rig1", 500.0, now);
+        assert_eq!(reports.len(), 3);
+        assert_eq!(external_hashrate(&reports, "rig3", now), 2_500.0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn siblings_file_concurrent() {
+        let path = std::env::temp_dir().join(format!(
+            "gupax_siblings_concurrent_test_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let handles = (0..8)
+            .map(|i| {
+                let siblings = Siblings::new(path.to_str().unwrap()).unwrap();
+                std::thread::spawn(move || {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .unwrap()
+                        .block_on(siblings.exchange(
+                            &crate::miscs::client(),
+                            &format!("rig{i}"),
+                            SiblingReport::default(),
+                        ))
+                        .unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        // no report was overwritten by another instance
+        let reports: SiblingReports =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(reports.len(), 8);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(path.with_extension("lock")).unwrap();
+    }
+
+    #[test]
+    fn siblings_donations() {
+        // the VIP round alone
+        let mut stats = Stats {
+            siblings_hashrate: 6_000.0,
+            ..nano_stats()
+        };
+        stats.update();
+        // the siblings already send a part of the VIP round
+        assert_eq!(stats.target_donation_hashrate, 4_000.0);
+        assert_eq!(stats.needed_time_xvb, 12_000);
+        assert_eq!(stats.round_hashrate(), 10_000.0);
+        // together they reach the Whale round
+        stats.siblings_hashrate = 85_000.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 15_000.0);
+        assert_eq!(stats.round_hashrate(), 100_000.0);
+        // the siblings send enough alone
+        stats.siblings_hashrate = 12_000.0;
+        stats.update();
+        assert_eq!(stats.needed_time_xvb, 0);
+        assert_eq!(stats.round_hashrate(), 10_000.0);
+        // the donation level is reached with the siblings
+        stats.runtime_mode = RuntimeMode::ManualDonationLevel;
+        stats.runtime_donation_level = RuntimeDonationLevel::DonorVIP;
+        stats.siblings_hashrate = 2_500.0;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, 7_500.0);
+        // the hero sends everything anyway
+        stats.runtime_mode = RuntimeMode::Hero;
+        stats.update();
+        assert_eq!(stats.target_donation_hashrate, stats.spareable_hashrate);
+        // the private stats of the accounts are added together
+        let mut total = XvbPrivStats::default();
+        for avg in [1.5, 2.5] {
+            total.add(&XvbPrivStats {
+                fails: 1,
+                donor_1hr_avg: avg,
+                donor_24hr_avg: avg * 2.0,
+                ..Default::default()
+            });
+        }
+        assert_eq!(total.fails, 2);
+        assert_eq!(total.donor_1hr_avg, 4.0);
+        assert_eq!(total.donor_24hr_avg, 8.0);
+    }
+}
@@ -0,0 +1,502 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Deterministic simulation of the XvB algorithm.
+// The algorithm runs cycle after cycle on a virtual clock, with a simulated miner instead of XMRig.
+// Hashrate and sidechain difficulty follow a trace, synthetic or recorded, the shares are found
+// exactly every [difficulty] hashes sent to P2Pool and XvB averages are computed from the hashes sent to XvB.
+// Days of decisions run in a fraction of a second, to compare the behavior of changes to the algorithm.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, anyhow};
+use strum::IntoEnumIterator;
+
+use crate::XVB_TIME_ALGO;
+use crate::disk::share_history::{PplnsWindow, WindowShare};
+use crate::disk::state::{P2pool, P2poolChain, Xvb, XvbPoolEndpoint, XvbSplit};
+use crate::helper::p2pool::{ImgP2pool, PubP2poolApi};
+use crate::helper::{Process, ProcessName};
+use crate::utils::constants::{BLOCK_PPLNS_WINDOW_NANO, SECOND_PER_BLOCK_P2POOL_NANO};
+
+use super::PubXvbApi;
+use super::algorithm::{Algorithm, MinerController, Stats};
+use super::nodes::Pool;
+use super::priv_stats::{RuntimeDonationLevel, RuntimeMode};
+use super::rounds::{XvbRound, round_type};
+
+const HOUR: u64 = 3600;
+const DAY: u64 = 24 * HOUR;
+
+// Stats of a miner at 20 kH/s on P2Pool Nano with a share in the window, before [Stats::update].
+pub fn nano_stats() -> Stats {
+    Stats {
+        share: 1,
+        hashrate_xmrig: 20_000.0,
+        difficulty_p2pool: 9_000_000,
+        pplns_window: BLOCK_PPLNS_WINDOW_NANO,
+        second_per_block: SECOND_PER_BLOCK_P2POOL_NANO,
+        p2pool_buffer: 5,
+        ..Default::default()
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Trace
+// Value of the inputs from [time], until the next point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TracePoint {
+    // seconds since the start of the simulation
+    pub time: u64,
+    // H/s of the miner
+    pub hashrate: f32,
+    pub difficulty: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace(Vec<TracePoint>);
+
+impl Trace {
+    pub fn constant(hashrate: f32, difficulty: u64) -> Self {
+        Self(vec![TracePoint {
+            time: 0,
+            hashrate,
+            difficulty,
+        }])
+    }
+
+    pub fn new(mut points: Vec<TracePoint>) -> anyhow::Result<Self> {
+        if points.is_empty() {
+            return Err(anyhow!("a trace needs at least one point"));
+        }
+        points.sort_by_key(|p| p.time);
+        Ok(Self(points))
+    }
+
+    // One point per line: "seconds,hashrate,difficulty", lines starting with # are ignored.
+    // Recorded from the XMRig and P2Pool APIs, it replays a real run.
+    pub fn from_csv(content: &str) -> anyhow::Result<Self> {
+        let mut points = vec![];
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [time, hashrate, difficulty] = fields[..] else {
+                return Err(anyhow!("line {}: expected 3 fields", i + 1));
+            };
+            points.push(TracePoint {
+                time: time.parse().with_context(|| format!("line {}", i + 1))?,
+                hashrate: hashrate
+                    .parse()
+                    .with_context(|| format!("line {}", i + 1))?,
+                difficulty: difficulty
+                    .parse()
+                    .with_context(|| format!("line {}", i + 1))?,
+            });
+        }
+        Self::new(points)
+    }
+
+    pub fn at(&self, time: u64) -> &TracePoint {
+        self.0
+            .iter()
+            .rev()
+            .find(|p| p.time <= time)
+            .unwrap_or(&self.0[0])
+    }
+}
+
+// Time during which a pool doesn't answer, in seconds since the start of the simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outage {
+    pub pool: Pool,
+    pub from: u64,
+    pub to: u64,
+}
+
+//---------------------------------------------------------------------------------------------------- World
+// Hashes sent during one minute.
+#[derive(Clone, Debug, Default)]
+struct Minute {
+    minute: u64,
+    p2pool: f64,
+    xvb: f64,
+}
+
+// Everything the simulated miner changes: the clock, the pool, the shares and the hashes sent.
+#[derive(Debug, Default)]
+struct World {
+    now_ms: u64,
+    hashrate: f32,
+    difficulty: u64,
+    pool: Option<Pool>,
+    switches: u32,
+    // threads of the miner and threads mining on XvB while they are split
+    threads: u16,
+    threads_xvb: u16,
+    // hashes sent to P2Pool since the last share
+    progress: f64,
+    // time of the shares found, in seconds
+    shares: Vec<u64>,
+    // last 24 hours, oldest first
+    minutes: VecDeque<Minute>,
+    hashes_p2pool: f64,
+    hashes_xvb: f64,
+    hashes_lost: f64,
+    outages: Vec<Outage>,
+}
+
+impl World {
+    fn now(&self) -> u64 {
+        self.now_ms / 1000
+    }
+
+    // pool the miner is connected to, None while its pool is down.
+    fn connected_pool(&self) -> Option<Pool> {
+        let now = self.now();
+        self.pool.clone().filter(|pool| {
+            !self
+                .outages
+                .iter()
+                .any(|o| o.pool == *pool && (o.from..o.to).contains(&now))
+        })
+    }
+
+    fn mine(&mut self, duration: Duration) {
+        let mut hashes = self.hashrate as f64 * duration.as_secs_f64();
+        let split = if self.threads_xvb > 0 {
+            let xvb = hashes * self.threads_xvb as f64 / self.threads as f64;
+            hashes -= xvb;
+            xvb
+        } else {
+            0.0
+        };
+        let minute = self.now() / 60;
+        if self.minutes.back().is_none_or(|m| m.minute != minute) {
+            self.minutes.push_back(Minute {
+                minute,
+                ..Default::default()
+            });
+            if self.minutes.len() > (DAY / 60) as usize {
+                self.minutes.pop_front();
+            }
+        }
+        let pool = self.connected_pool();
+        let bucket = self.minutes.back_mut().expect("pushed just before");
+        self.now_ms += duration.as_millis() as u64;
+        bucket.xvb += split;
+        self.hashes_xvb += split;
+        match pool {
+            Some(Pool::P2pool(_)) => {
+                bucket.p2pool += hashes;
+                self.hashes_p2pool += hashes;
+                self.progress += hashes;
+                while self.difficulty > 0 && self.progress >= self.difficulty as f64 {
+                    self.progress -= self.difficulty as f64;
+                    self.shares.push(self.now_ms / 1000);
+                }
+            }
+            Some(Pool::XvB(_)) => {
+                bucket.xvb += hashes;
+                self.hashes_xvb += hashes;
+            }
+            // not connected, the hashes are lost
+            _ => self.hashes_lost += hashes,
+        }
+    }
+
+    // average H/s over the last [seconds], with the hashes of [f] of every minute.
+    fn average(&self, seconds: u64, f: impl Fn(&Minute) -> f64) -> f64 {
+        let from = self.now().saturating_sub(seconds) / 60;
+        self.minutes
+            .iter()
+            .filter(|m| m.minute >= from)
+            .map(f)
+            .sum::<f64>()
+            / seconds as f64
+    }
+
+    fn window(&self, chain: &P2poolChain) -> PplnsWindow {
+        let length = chain.pplns_window_blocks();
+        let window_secs = length * chain.seconds_per_block();
+        let now = self.now();
+        let shares = self
+            .shares
+            .iter()
+            .filter(|found| *found + window_secs > now)
+            .map(|found| {
+                let expires_in = found + window_secs - now;
+                WindowShare {
+                    sidechain_height: 0,
+                    difficulty: self.difficulty,
+                    blocks_left: expires_in / chain.seconds_per_block(),
+                    expires_in,
+                }
+            })
+            .collect();
+        PplnsWindow {
+            sidechain_height: 0,
+            length,
+            shares,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Simulated miner
+struct SimulatedMiner<'a> {
+    world: &'a RefCell<World>,
+    gui_api_xvb: &'a Arc<Mutex<PubXvbApi>>,
+}
+
+impl MinerController for SimulatedMiner<'_> {
+    async fn set_pool(&self, pool: &Pool, _address: &str, _rig: &str) -> anyhow::Result<()> {
+        let mut world = self.world.borrow_mut();
+        if world.pool.as_ref().is_some_and(|p| p != pool) {
+            world.switches += 1;
+        }
+        world.pool = Some(pool.clone());
+        // what XMRig would print in its console, read by the XvB process.
+        self.gui_api_xvb.lock().unwrap().current_pool = world.connected_pool();
+        Ok(())
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let mut world = self.world.borrow_mut();
+        world.mine(duration);
+        // XMRig loses the connection when the pool goes down and connects again when it is back.
+        self.gui_api_xvb.lock().unwrap().current_pool = world.connected_pool();
+    }
+
+    fn hashrate(&self) -> f32 {
+        self.world.borrow().hashrate
+    }
+
+    fn threads(&self) -> u16 {
+        self.world.borrow().threads
+    }
+
+    async fn split_threads(
+        &self,
+        _pool: &Pool,
+        _address: &str,
+        threads: u16,
+    ) -> anyhow::Result<()> {
+        self.world.borrow_mut().threads_xvb = threads;
+        Ok(())
+    }
+
+    fn shares(&self) -> (u64, u64) {
+        (0, 0)
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Simulation
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub trace: Trace,
+    pub mode: RuntimeMode,
+    pub chain: P2poolChain,
+    pub p2pool_buffer: i8,
+    // for the manual modes
+    pub manual_amount: f64,
+    pub manual_donation_level: RuntimeDonationLevel,
+    pub split: XvbSplit,
+    // 0 if the miner can't be split by threads
+    pub threads: u16,
+    // XvB pools, the first one is the fastest
+    pub pools: Vec<XvbPoolEndpoint>,
+    pub outages: Vec<Outage>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub cycles: u64,
+    // cycles starting with a share in the PPLNS window
+    pub cycles_with_share: u64,
+    // round detected at the end of every hour
+    pub rounds: Vec<Option<XvbRound>>,
+    // changes of pool asked to the miner
+    pub switches: u32,
+    pub hashes_p2pool: f64,
+    pub hashes_xvb: f64,
+    // mined while the pool of the miner was down
+    pub hashes_lost: f64,
+}
+
+impl SimulationReport {
+    pub fn share_retention(&self) -> f64 {
+        if self.cycles == 0 {
+            return 0.0;
+        }
+        self.cycles_with_share as f64 / self.cycles as f64
+    }
+
+    pub fn hours_in(&self, round: Option<XvbRound>) -> usize {
+        self.rounds.iter().filter(|r| **r == round).count()
+    }
+
+    pub fn donated_ratio(&self) -> f64 {
+        let total = self.hashes_p2pool + self.hashes_xvb;
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.hashes_xvb / total
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} cycles | share retention {:.2}% | {} switches | {:.2}% donated",
+            self.cycles,
+            self.share_retention() * 100.0,
+            self.switches,
+            self.donated_ratio() * 100.0
+        )?;
+        let mut rounds = vec![(None, self.hours_in(None))];
+        rounds.extend(XvbRound::iter().map(|r| (Some(r.clone()), self.hours_in(Some(r)))));
+        for (round, hours) in rounds.into_iter().filter(|(_, h)| *h > 0) {
+            let name = round.map_or("None".to_string(), |r| r.to_string());
+            writeln!(f, "{name}: {hours} hours")?;
+        }
+        Ok(())
+    }
+}
+
+impl Simulation {
+    pub fn new(trace: Trace, mode: RuntimeMode) -> Self {
+        Self {
+            trace,
+            mode,
+            chain: P2pool::default().chain,
+            p2pool_buffer: 5,
+            manual_amount: 0.0,
+            manual_donation_level: RuntimeDonationLevel::default(),
+            split: XvbSplit::Time,
+            threads: 0,
+            pools: Xvb::default().pools,
+            outages: vec![],
+        }
+    }
+
+    // Run the algorithm during [seconds] of virtual time, one cycle after the other like the XvB process.
+    #[tokio::main(flavor = "current_thread")]
+    pub async fn run(&self, seconds: u64) -> SimulationReport {
+        let world = RefCell::new(World {
+            threads: self.threads,
+            outages: self.outages.clone(),
+            ..Default::default()
+        });
+        let pub_api = Arc::new(Mutex::new(PubXvbApi::new()));
+        let gui_api_xvb = Arc::new(Mutex::new(PubXvbApi::new()));
+        let gui_api_p2pool = Arc::new(Mutex::new(PubP2poolApi::new()));
+        let state_p2pool = P2pool {
+            chain: self.chain.clone(),
+            ..Default::default()
+        };
+        let time_donated = Arc::new(Mutex::new(0));
+        let p2pool_img = Arc::new(Mutex::new(ImgP2pool::new()));
+        let p2pool_process = Arc::new(Mutex::new(Process::new(
+            ProcessName::P2pool,
+            String::new(),
+            PathBuf::new(),
+        )));
+        gui_api_xvb.lock().unwrap().split = self.split;
+        gui_api_xvb.lock().unwrap().pools = self.pools.clone();
+        gui_api_xvb.lock().unwrap().stats_priv.pool =
+            Pool::XvB(self.pools.first().cloned().unwrap_or_default());
+        {
+            let stats = &mut gui_api_xvb.lock().unwrap().stats_priv;
+            stats.runtime_mode = self.mode.clone();
+            stats.runtime_manual_amount = self.manual_amount;
+            stats.runtime_manual_donation_level = self.manual_donation_level.clone();
+        }
+        let mut report = SimulationReport::default();
+        while world.borrow().now() < seconds {
+            // inputs given by P2Pool, XMRig and the XvB API at the start of the cycle.
+            let share = {
+                let mut world = world.borrow_mut();
+                let now = world.now();
+                let point = self.trace.at(now);
+                world.hashrate = point.hashrate;
+                world.difficulty = point.difficulty;
+                let window = world.window(&self.chain);
+                let share = window.shares.len() as u32;
+                let mut p2pool = gui_api_p2pool.lock().unwrap();
+                p2pool.p2pool_difficulty_u64 = point.difficulty;
+                p2pool.hashrate_15m = world.average(HOUR / 4, |m| m.p2pool) as u64;
+                p2pool.hashrate_1h = world.average(HOUR, |m| m.p2pool) as u64;
+                p2pool.sidechain_shares = share;
+                p2pool.pplns_window = window;
+                let mut xvb = pub_api.lock().unwrap();
+                xvb.stats_priv.donor_1hr_avg = (world.average(HOUR, |m| m.xvb) / 1000.0) as f32;
+                xvb.stats_priv.donor_24hr_avg = (world.average(DAY, |m| m.xvb) / 1000.0) as f32;
+                if now > 0 && now.is_multiple_of(HOUR) {
+                    drop(xvb);
+                    report.rounds.push(round_type(share, &pub_api));
+                }
+                share
+            };
+            report.cycles += 1;
+            if share > 0 {
+                report.cycles_with_share += 1;
+            }
+            // the console would grow for every cycle.
+            gui_api_xvb.lock().unwrap().output.clear();
+            let start = world.borrow().now_ms;
+            let miner = SimulatedMiner {
+                world: &world,
+                gui_api_xvb: &gui_api_xvb,
+            };
+            Algorithm::with_miner(
+                miner,
+                &pub_api,
+                &gui_api_xvb,
+                &gui_api_p2pool,
+                &state_p2pool,
+                share,
+                &time_donated,
+                "",
+                false,
+                self.p2pool_buffer,
+                &p2pool_img,
+                &p2pool_process,
+            )
+            .run()
+            .await;
+            // a cycle always lasts the time of the algorithm, even if it stopped earlier.
+            let mut world = world.borrow_mut();
+            let elapsed = world.now_ms - start;
+            if elapsed < XVB_TIME_ALGO {
+                world.mine(Duration::from_millis(XVB_TIME_ALGO - elapsed));
+            }
+        }
+        let world = world.into_inner();
+        report.switches = world.switches;
+        report.hashes_p2pool = world.hashes_p2pool;
+        report.hashes_xvb = world.hashes_xvb;
+        report.hashes_lost = world.hashes_lost;
+        report
+    }
+}
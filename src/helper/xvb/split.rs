@@ -0,0 +1,206 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Split of the hashrate by CPU threads.
+// Instead of switching the whole XMRig between P2Pool and XvB during the cycle, a second XMRig
+// started by Gupax mines on XvB with some of the threads while the main XMRig keeps mining on P2Pool
+// with the others. The number of threads is given every cycle through the HTTP API of both XMRig,
+// so the target of the round is sent continuously without reconnecting to the pools.
+
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use log::{info, warn};
+
+use crate::XMRIG_API_CONFIG_ENDPOINT;
+use crate::utils::constants::XMRIG_XVB_WORKER_API_PORT;
+
+use super::nodes::Pool;
+
+// Threads to mine on XvB to send [target] H/s, out of [threads] mining [hashrate] H/s.
+// The threads on P2Pool must keep the [spareable] limit to keep a share, and the main XMRig keeps at least one.
+pub fn threads_xvb(target: f32, spareable: f32, hashrate: f32, threads: u16) -> u16 {
+    if target <= 0.0 || hashrate <= 0.0 || threads < 2 {
+        return 0;
+    }
+    let per_thread = hashrate / threads as f32;
+    // like the needed time, round up to not undersend
+    let needed = (target / per_thread).ceil();
+    let max = (spareable / per_thread).floor().max(0.0);
+    needed.min(max).min((threads - 1) as f32) as u16
+}
+
+//---------------------------------------------------------------------------------------------------- [XvbWorker]
+// The second XMRig, mining on XvB.
+// It uses the binary of the main XMRig, but is started without root so it doesn't apply the MSR mod again.
+#[derive(Debug, Default)]
+pub struct XvbWorker {
+    // binary of the main XMRig, set when it starts
+    pub path: PathBuf,
+    child: Option<Child>,
+    // pool and threads of the running worker
+    pool: Option<Pool>,
+    pub threads: u16,
+    // threads given to the main XMRig, None while it mines with the threads it started with
+    pub main_threads: Option<u16>,
+}
+
+impl XvbWorker {
+    pub fn is_alive(&mut self) -> bool {
+        self.child
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+    }
+
+    // true if the worker mines on [pool].
+    pub fn mines_on(&mut self, pool: &Pool) -> bool {
+        self.is_alive() && self.pool.as_ref() == Some(pool)
+    }
+
+    // pool of the running worker
+    pub fn pool(&mut self) -> Option<Pool> {
+        if self.is_alive() {
+            self.pool.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn api_url() -> String {
+        format!("http://127.0.0.1:{XMRIG_XVB_WORKER_API_PORT}/{XMRIG_API_CONFIG_ENDPOINT}")
+    }
+
+    pub fn args(pool: &Pool, address: &str, threads: u16, token: &str) -> Vec<String> {
+        let mut args = vec![
+            "--no-color".to_string(),
+            "--url".to_string(),
+            [pool.url(), ":".to_string(), pool.port()].concat(),
+            "--user".to_string(),
+            pool.user(&address.chars().take(8).collect::<String>()),
+            "--threads".to_string(),
+            threads.to_string(),
+            "--http-host".to_string(),
+            "127.0.0.1".to_string(),
+            "--http-port".to_string(),
+            XMRIG_XVB_WORKER_API_PORT.to_string(),
+            format!("--http-access-token={token}"),
+            "--http-no-restricted".to_string(),
+        ];
+        if pool.tls() {
+            args.push("--tls".to_string());
+        }
+        if pool.keepalive() {
+            args.push("--keepalive".to_string());
+        }
+        args
+    }
+
+    // (Re)start the worker on [pool] with [threads].
+    pub fn start(
+        &mut self,
+        pool: &Pool,
+        address: &str,
+        threads: u16,
+        token: &str,
+    ) -> anyhow::Result<()> {
+        self.stop();
+        let args = Self::args(pool, address, threads, token);
+        info!(
+            "XvB Worker | Starting [{}] with {args:?}",
+            self.path.display()
+        );
+        let mut cmd = Command::new(&self.path);
+        cmd.args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        if let Some(dir) = self.path.parent() {
+            cmd.current_dir(dir);
+        }
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000);
+        }
+        self.child = Some(cmd.spawn()?);
+        self.pool = Some(pool.clone());
+        self.threads = threads;
+        info!("XvB Worker | Start ... OK");
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            if let Err(e) = child.kill() {
+                warn!("XvB Worker | Kill ... FAIL ... {e}");
+            }
+            let _ = child.wait();
+            info!("XvB Worker | Stop ... OK");
+        }
+        self.pool = None;
+        self.threads = 0;
+    }
+}
+
+impl Drop for XvbWorker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_threads() {
+        // 8 threads at 2.5 kH/s each
+        assert_eq!(threads_xvb(10_000.0, 19_000.0, 20_000.0, 8), 4);
+        // rounded up
+        assert_eq!(threads_xvb(10_100.0, 19_000.0, 20_000.0, 8), 5);
+        // the threads on P2Pool keep enough for a share
+        assert_eq!(threads_xvb(10_100.0, 11_000.0, 20_000.0, 8), 4);
+        assert_eq!(threads_xvb(20_000.0, 20_000.0, 20_000.0, 8), 7);
+        // nothing to send or nothing to split
+        assert_eq!(threads_xvb(0.0, 19_000.0, 20_000.0, 8), 0);
+        assert_eq!(threads_xvb(10_000.0, -100.0, 20_000.0, 8), 0);
+        assert_eq!(threads_xvb(10_000.0, 19_000.0, 20_000.0, 1), 0);
+        assert_eq!(threads_xvb(10_000.0, 19_000.0, 0.0, 8), 0);
+    }
+
+    #[test]
+    fn worker_args() {
+        let args = XvbWorker::args(&Pool::default(), "4AbCdEfGhIjK", 3, "token");
+        let arg = |name: &str| {
+            args.iter()
+                .position(|a| a == name)
+                .map(|i| args[i + 1].as_str())
+        };
+        assert_eq!(arg("--threads"), Some("3"));
+        assert_eq!(arg("--user"), Some("4AbCdEfG"));
+        assert_eq!(
+            arg("--http-port"),
+            Some(XMRIG_XVB_WORKER_API_PORT.to_string().as_str())
+        );
+        assert!(args.contains(&"--http-access-token=token".to_string()));
+        assert!(args.contains(&"--tls".to_string()));
+        let mut worker = XvbWorker::default();
+        assert!(!worker.is_alive());
+        assert!(!worker.mines_on(&Pool::default()));
+    }
+}
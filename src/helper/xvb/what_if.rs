@@ -0,0 +1,142 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// What-if of the XvB tab.
+// The stats of a decision of the algorithm are taken again with the hashrate, the P2Pool buffer
+// and the mode chosen by the user, to see the split and the round they would give.
+// It works on a copy, the running algorithm is not touched.
+
+use crate::XVB_TIME_ALGO;
+use crate::disk::state::{XvbMode, XvbSplit};
+
+use super::algorithm::{Decision, Stats};
+use super::priv_stats::RuntimeGoal;
+use super::rounds::{XvbRound, donor_round};
+use super::split::threads_xvb;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhatIf {
+    // decision the inputs were taken from
+    pub timestamp: i64,
+    pub hashrate: f32,
+    pub p2pool_buffer: i8,
+    pub mode: XvbMode,
+    pub split: XvbSplit,
+    // constraints of the goal mode
+    pub goal: RuntimeGoal,
+}
+
+#[derive(Clone, Debug)]
+pub struct WhatIfOutcome {
+    pub stats: Stats,
+    // H/s sent to XvB on average during a cycle without fast mode
+    pub sent: f32,
+    // round reached by sending it, None without a share
+    pub round: Option<XvbRound>,
+    // threads on XvB if the hashrate is split by threads
+    pub threads_xvb: Option<u16>,
+}
+
+impl WhatIf {
+    pub fn new(decision: &Decision, mode: XvbMode, split: XvbSplit, goal: RuntimeGoal) -> Self {
+        Self {
+            timestamp: decision.timestamp,
+            hashrate: decision.stats.hashrate_xmrig,
+            p2pool_buffer: decision.stats.p2pool_buffer,
+            mode,
+            split,
+            goal,
+        }
+    }
+
+    // The stats are logged like for a decision, it should only be called when an input changes.
+    pub fn outcome(&self, decision: &Decision) -> WhatIfOutcome {
+        let mut stats = decision.stats.clone();
+        stats.hashrate_xmrig = self.hashrate;
+        stats.p2pool_buffer = self.p2pool_buffer;
+        stats.runtime_mode = self.mode.clone().into();
+        stats.runtime_goal = self.goal.clone();
+        stats.update();
+        let threads_xvb = (self.split == XvbSplit::Threads && stats.threads > 1).then(|| {
+            threads_xvb(
+                stats.target_donation_hashrate,
+                stats.spareable_hashrate,
+                stats.hashrate_xmrig,
+                stats.threads,
+            )
+        });
+        let sent = match threads_xvb {
+            Some(threads) => stats.hashrate_xmrig * threads as f32 / stats.threads as f32,
+            None => stats.hashrate_xmrig * stats.needed_time_xvb as f32 / XVB_TIME_ALGO as f32,
+        };
+        // the siblings donate for the same round.
+        let round = (stats.share > 0).then(|| donor_round(sent + stats.siblings_hashrate));
+        WhatIfOutcome {
+            stats,
+            sent,
+            round,
+            threads_xvb,
+        }
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::helper::xvb::simulation::nano_stats;
+
+    #[test]
+    fn what_if_decision() {
+        // with 8 threads
+        let mut stats = Stats {
+            threads: 8,
+            ..nano_stats()
+        };
+        stats.update();
+        let decision = Decision {
+            timestamp: 1_700_000_000,
+            stats,
+            outcome: String::new(),
+        };
+        // 9_000_000 / (2160 * 30) * 1.05
+        assert_eq!(decision.stats.share_min_hashrate, 138.0 * 1.05);
+        assert_eq!(decision.stats.target_donation_hashrate, 10_000.0);
+        assert_eq!(decision.stats.needed_time_xvb, 30_000);
+        let mut what_if = WhatIf::new(&decision, XvbMode::Auto, XvbSplit::Time, Default::default());
+        let outcome = what_if.outcome(&decision);
+        assert_eq!(outcome.sent, 10_000.0);
+        assert_eq!(outcome.round, Some(XvbRound::DonorVip));
+        assert_eq!(outcome.threads_xvb, None);
+        // less hashrate, lower round
+        what_if.hashrate = 5_000.0;
+        let outcome = what_if.outcome(&decision);
+        assert_eq!(outcome.round, Some(XvbRound::Donor));
+        assert_eq!(outcome.stats.needed_time_xvb, 12_000);
+        // everything but the minimum for a share
+        what_if.mode = XvbMode::Hero;
+        what_if.split = XvbSplit::Threads;
+        what_if.p2pool_buffer = 100;
+        let outcome = what_if.outcome(&decision);
+        assert_eq!(outcome.stats.share_min_hashrate, 276.0);
+        assert_eq!(outcome.threads_xvb, Some(7));
+        assert_eq!(outcome.sent, 5_000.0 * 7.0 / 8.0);
+        // the decision itself is not changed
+        assert_eq!(decision.stats.hashrate_xmrig, 20_000.0);
+        assert_eq!(decision.stats.formulas().len(), 5);
+    }
+}
@@ -6,7 +6,7 @@ use crate::helper::{Helper, ProcessName, ProcessSignal};
 use crate::utils::constants::{
     APP_MAX_HEIGHT, APP_MAX_WIDTH, APP_MIN_HEIGHT, APP_MIN_WIDTH, BYTES_ICON,
 };
-use crate::utils::regex::Regexes;
+use crate::utils::monero_address::{Network, check_address};
 use std::io::Write;
 //---------------------------------------------------------------------------------------------------- Init functions
 use crate::app::App;
@@ -199,6 +199,7 @@ pub fn init_auto(app: &mut App) {
                 &app.helper,
                 &app.state.node,
                 &app.state.gupax.absolute_node_path,
+                app.state.gupax.network,
             );
         }
     } else {
@@ -214,8 +215,12 @@ pub fn init_auto(app: &mut App) {
         .auto
         .is_enabled(&AutoStart::Process(ProcessName::P2pool))
     {
-        if !Regexes::addr_ok(&app.state.p2pool.address) {
-            warn!("Gupax | P2Pool address is not valid! Skipping auto-p2pool...");
+        if let Err(e) = check_address(
+            &app.state.p2pool.address,
+            ProcessName::P2pool,
+            app.state.gupax.network,
+        ) {
+            warn!("Gupax | P2Pool address is not valid: {e}! Skipping auto-p2pool...");
         } else if !Gupax::path_is_file(&app.state.gupax.p2pool_path) {
             warn!("Gupax | P2Pool path is not a file! Skipping auto-p2pool...");
         } else if !check_binary_path(&app.state.gupax.p2pool_path, ProcessName::P2pool) {
@@ -313,13 +318,17 @@ pub fn init_auto(app: &mut App) {
         .auto
         .is_enabled(&AutoStart::Process(ProcessName::Xvb))
     {
-        Helper::start_xvb(
-            &app.helper,
-            &app.state.xvb,
-            &app.state.p2pool,
-            &app.state.xmrig,
-            &app.state.xmrig_proxy,
-        );
+        if app.state.gupax.network != Network::Mainnet {
+            warn!("Gupax | XvB only runs on mainnet! Skipping auto-xvb...");
+        } else {
+            Helper::start_xvb(
+                &app.helper,
+                &app.state.xvb,
+                &app.state.p2pool,
+                &app.state.xmrig,
+                &app.state.xmrig_proxy,
+            );
+        }
     } else {
         info!("Skipping auto-xvb...");
     }
@@ -135,6 +135,27 @@ pub fn print_gupax_p2pool_api(gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>) {
     exit(0);
 }
 
+pub fn print_payout_export(
+    gupax_p2pool_api: &Arc<Mutex<GupaxP2poolApi>>,
+    format: ExportFormat,
+    args: &PayoutsArgs,
+) {
+    let log = match std::fs::read_to_string(&gupax_p2pool_api.lock().unwrap().path_log) {
+        Ok(string) => string,
+        Err(e) => {
+            error!("{e}");
+            exit(1);
+        }
+    };
+    match export_payout_log(&log, format, args.from, args.to, args.prices.as_deref()) {
+        Ok(export) => print!("{export}"),
+        Err(e) => {
+            error!("Payout Export | {e}");
+            exit(1);
+        }
+    }
+}
+
 #[inline]
 pub fn cmp_f64(a: f64, b: f64) -> std::cmp::Ordering {
     match (a <= b, a >= b) {
@@ -146,7 +167,9 @@ pub fn cmp_f64(a: f64, b: f64) -> std::cmp::Ordering {
 }
 // Free functions.
 
+use crate::cli::PayoutsArgs;
 use crate::disk::gupax_p2pool_api::GupaxP2poolApi;
+use crate::disk::payout_export::{ExportFormat, export_payout_log};
 use crate::helper::ProcessName;
 use chrono::Local;
 use egui::TextStyle;
@@ -95,6 +95,14 @@ pub const P2POOL_API_PATH_NETWORK: &str = "network/stats";
 pub const P2POOL_API_PATH_POOL: &str = "pool/stats";
 #[cfg(target_family = "unix")]
 pub const P2POOL_API_PATH_P2P: &str = "local/p2p";
+// interval in seconds between updates of the known nodes with the time P2Pool stayed connected to its node
+pub const P2POOL_KNOWN_NODES_INTERVAL: u64 = 300;
+// interval in seconds between health checks of the nodes used by P2Pool
+pub const P2POOL_MONITOR_INTERVAL: u64 = 60;
+// number of checks in a row finding every node degraded before switching to new nodes
+pub const P2POOL_MONITOR_DEGRADED_CHECKS: u8 = 3;
+// minimum time in seconds between two switches to new nodes
+pub const P2POOL_MONITOR_COOLDOWN: u64 = 900;
 pub const XMRIG_API_SUMMARY_ENDPOINT: &str = "1/summary"; // The default relative URI of XMRig's API summary
 pub const XMRIG_API_CONFIG_ENDPOINT: &str = "1/config"; // The default relative URI of XMRig's API config
 
@@ -124,6 +132,7 @@ pub const XMRIG_PROXY_FAILED: &str = "XMRig-Proxy is offline and failed when exi
 pub const XMRIG_PROXY_MIDDLE: &str = "XMRig-Proxy is in the middle of (re)starting/stopping";
 pub const XMRIG_PROXY_NOT_MINING: &str = "XMRig-Proxy is online, but not mining to any pool";
 pub const XMRIG_PROXY_REDIRECT: &str = "point local xmrig instance on this proxy instead of the p2pool instance (recommended if using XvB)";
+pub const XMRIG_PROXY_ADDRESS: &str = "Specify which Monero address the pools of XMRig-Proxy pay out to. This does nothing if mining to P2Pool since the address being paid out to will be the one P2Pool started with.";
 pub const XMRIG_PROXY_INPUT: &str = "Send a command to XMRig-Proxy";
 pub const XMRIG_PROXY_SIMPLE: &str = r#"Use simple XMRig-Proxy settings:
   - Mine to local P2Pool (localhost:3333)
@@ -150,6 +159,8 @@ pub const XVB_DEAD: &str = "XvB process is offline";
 pub const XVB_FAILED: &str = "XvB process is misconfigured or the XvB pool is offline";
 pub const XVB_MIDDLE: &str = "XvB is in the middle of (re)starting/stopping";
 pub const XVB_NOT_CONFIGURED: &str = "You need to insert an existent token before starting XvB";
+pub const XVB_MAINNET_ONLY: &str =
+    "XvB only runs on mainnet, select mainnet in the Gupax tab to use it";
 pub const XVB_PUBLIC_ONLY: &str = "XvB process is started only to get public stats.";
 pub const XVB_SIDECHAIN: &str = "
 If checked:\n
@@ -158,6 +169,15 @@ If unchecked (default):\n
 The algorithm will watch the HR estimated by the stratum data of the p2pool node, which is more accurate but will only take into account the miners that are using your P2Pool node.
 ";
 pub const XVB_MANUAL_POOL: &str = "Enable this to force the algorithm to connect to a specific XvB pool, without using the considered fastest";
+pub const XVB_ACCOUNTS: &str = "Addresses registered on XvB, with their token if the registration has one. The token is not documented by XvB yet, leave it empty if XvB did not give one.\nTheir private stats are added together and the first one is used as the XvB user of the pool.\nIf none is set, the address of the P2Pool tab is used.\nChanges are used at the next start of the XvB process.";
+pub const XVB_REGISTRATION_VERIFY: &str =
+    "Verify that XvB knows the address, with its token if it has one.";
+pub const XVB_REGISTRATION: &str = "Register the address to XvB. It is verified first, then sent to the registration endpoint if it is not registered yet. The token given by XvB is stored with the address in the XvB accounts.\nXvB can refuse an address without a share in the PPLNS window.";
+pub const XVB_REGISTRATION_ENDPOINT: &str = "URL the address is sent to by the Register button, the registration endpoint of XvB by default.\nIf the registration fails, the address can be registered on the website of XvB.";
+pub const XVB_SIBLINGS: &str = "Shared file or URL where the instances of Gupax donating to the same registration report the hashrate they send to XvB. The donations of the others are counted so that this instance only sends what is missing for the round.\nLeave empty to disable.";
+pub const XVB_SIBLING_NAME: &str = "Name of this instance in the reports of the siblings and to the leader of the coordination, it must be different on every machine. The host name is used if empty.";
+pub const XVB_COORDINATION: &str = "Coordinate the algorithm of the instances of Gupax mining to the same P2Pool address on the LAN.\n\n- Leader: runs the algorithm with the hashrate of every instance and assigns to each one what to send to XvB, the address is the one to listen on, only this machine by default: use the LAN address of the leader for the followers to reach it\n\n- Follower: reports its hashrate to the leader and sends what it is assigned, the address is the one of the leader. Without the leader, it runs its own algorithm\n\nThe key must be the same on every instance, and the clocks of the machines set within two minutes. Changes are used at the next start of the XvB process.";
+pub const XVB_POOLS: &str = "Pools of XvB or compatible with it. The fastest is chosen when the XvB process starts, another one is used if it fails.\nChanges are used at the next start of the XvB process.";
 pub const XVB_P2POOL_BUFFER: &str = "Set the % amount of additional HR to send to p2pool. Will reduce (if positive) or augment (if negative) the chances to miss the p2pool window.\n\n- In Auto or Hero mode, the algorithm will keep enough HR on the p2pool side to conform to the buffer\n\n- In Manual modes, the algorithm will ignore the p2pool buffer";
 
 pub const START_OPTIONS_HOVER: &str = "Start the process with theses options.\nThe \"Reset to simple/advanced options\" are arguments constructed from the settings.\nYou can replace them with your own";
@@ -277,6 +297,8 @@ pub const STATUS_SUBMENU_PROCESSES: &str =
     "View the status of process related data for [Gupax|P2Pool|XMRig]";
 pub const STATUS_SUBMENU_P2POOL: &str = "View P2Pool specific data";
 pub const STATUS_SUBMENU_HASHRATE: &str = "Compare your CPU hashrate with others";
+pub const STATUS_SUBMENU_CALCULATOR: &str =
+    "Estimate your earnings and profit on the P2Pool chains";
 //-- P2Pool
 pub const STATUS_SUBMENU_PAYOUT: &str = "The total amount of payouts received via P2Pool across all time. This includes all payouts you have ever received using Gupax and P2Pool.";
 pub const STATUS_SUBMENU_XMR: &str = "The total of XMR mined via P2Pool across all time. This includes all the XMR you have ever mined using Gupax and P2Pool.";
@@ -284,6 +306,35 @@ pub const STATUS_SUBMENU_LATEST: &str = "Sort the payouts from latest to oldest"
 pub const STATUS_SUBMENU_OLDEST: &str = "Sort the payouts from oldest to latest";
 pub const STATUS_SUBMENU_BIGGEST: &str = "Sort the payouts from biggest to smallest";
 pub const STATUS_SUBMENU_SMALLEST: &str = "Sort the payouts from smallest to biggest";
+// Calculator
+pub const STATUS_CALCULATOR_CHAIN: &str = "Estimate the earnings on this P2Pool chain";
+pub const STATUS_CALCULATOR_DIFFICULTY_RUNNING: &str =
+    "Current difficulty and PPLNS window of the chain, given by the running P2Pool";
+pub const STATUS_CALCULATOR_DIFFICULTY: &str = "Difficulty of the sidechain, it can be found on a P2Pool observer. It is taken from P2Pool when it runs on this chain";
+pub const STATUS_CALCULATOR_POWER: &str = "Power consumed by your mining rig in watts";
+pub const STATUS_CALCULATOR_KWH_PRICE: &str =
+    "Price of a kWh of electricity, in the same currency as the price of XMR";
+pub const STATUS_CALCULATOR_XMR_PRICE: &str =
+    "Price of a XMR, in the same currency as the price of a kWh";
+pub const STATUS_CALCULATOR_P2POOL_OFFLINE: &str =
+    "P2Pool must be running to know the Monero difficulty and block reward";
+pub const STATUS_CALCULATOR_XMR: &str = "XMR earned on average with this hashrate, the chain only changes how often and how much it pays";
+pub const STATUS_CALCULATOR_NET: &str = "Value of the XMR earned minus the cost of the electricity";
+pub const STATUS_CALCULATOR_WINDOW_SHARES: &str =
+    "Average number of your shares in the PPLNS window of the chain";
+pub const STATUS_CALCULATOR_WINDOW_CHANCE: &str = "Chance to have at least one share in the PPLNS window, which is needed to be paid when P2Pool finds a block";
+pub const STATUS_CALCULATOR_PAYOUT_MEAN: &str = "Average time between two of your payouts";
+pub const STATUS_CALCULATOR_PAYOUT: &str = "Average amount of one of your payouts";
+pub const STATUS_SUBMENU_PPLNS_WINDOW: &str = "The last blocks of the sidechain, shared between their miners when P2Pool finds a Monero block. You are paid only if one of your shares is in it";
+pub const STATUS_SUBMENU_PPLNS_EXPIRY: &str =
+    "When your last share leaves the window. In orange when your next share is not expected before";
+pub const STATUS_SUBMENU_PPLNS_PAYOUT: &str = "Estimated payout of your shares in the window if P2Pool finds a Monero block now, with the current block reward and sidechain difficulty";
+pub const STATUS_SUBMENU_SHARES: &str = "Shares found by your P2Pool on the sidechain and network it runs on, since Gupax started recording them";
+pub const STATUS_SUBMENU_SINCE_LAST_SHARE: &str =
+    "Time since your last share, in orange when it is more than three times your share mean";
+pub const STATUS_SUBMENU_SHARE_INTERVAL: &str = "Time between two of your shares";
+pub const STATUS_SUBMENU_SHARE_EFFORT: &str = "Hashes needed to find a share compared to its difficulty. Under 100% the share was found faster than expected";
+pub const STATUS_SUBMENU_LUCK: &str = "Number of your shares found in each range of effort, compared with the number expected by chance. Low efforts are the most likely";
 pub const STATUS_SUBMENU_AUTOMATIC: &str =
     "Automatically calculate share/block time with your current P2Pool 1 hour average hashrate";
 pub const STATUS_SUBMENU_MANUAL: &str = "Manually input a hashrate to calculate share/block time with current P2Pool/Monero network stats";
@@ -303,6 +354,27 @@ pub const STATUS_SUBMENU_MONERO_HASHRATE: &str = "The current Monero network's h
 pub const STATUS_SUBMENU_P2POOL_DIFFICULTY: &str = "The current P2Pool network's difficulty (how many hashes it will take on average to find a share)";
 pub const STATUS_SUBMENU_P2POOL_HASHRATE: &str = "The current P2Pool network's hashrate";
 pub const STATUS_SUBMENU_P2POOL_MINERS: &str = "The current amount of miners on P2Pool";
+pub const STATUS_SUBMENU_WORKERS: &str = "The miners connected to the stratum of your P2Pool";
+pub const STATUS_SUBMENU_WORKERS_MISSING: &str = "XMRig or XMRig-Proxy is set to mine on your P2Pool but the stratum does not see it connected from this machine.\nCheck the pool they are pointed at and the stratum port of P2Pool.";
+pub const STATUS_SUBMENU_EXPORT: &str = "Export the payouts with their date, block, atomic units and fiat value if a price source is set";
+pub const STATUS_SUBMENU_PRICE_SOURCE: &str = "Optional source of the price of XMR to add the fiat value of the payouts at their date:\n - a CSV file of \"YYYY-MM-DD,price\" lines\n - an URL where {date} is replaced by YYYY-MM-DD, answering with the price or {\"price\": price}";
+pub const STATUS_SUBMENU_VIEW_KEY: &str = "Private view key of the wallet of P2Pool, found in your wallet under Settings/Seed & Keys.\nIt can not spend your XMR, but it reveals every payment you receive.\nIt is stored encrypted with your passphrase and only decrypted for the session.";
+pub const STATUS_SUBMENU_PASSPHRASE: &str =
+    "Passphrase used to encrypt the view key, it is never saved";
+pub const STATUS_SUBMENU_ENCRYPT: &str = "Encrypt the view key with the passphrase.\nSave the state to keep the encrypted key for the next sessions.";
+pub const STATUS_SUBMENU_UNLOCK: &str = "Decrypt the view key for this session";
+pub const STATUS_SUBMENU_LOCK: &str = "Forget the decrypted view key";
+pub const STATUS_SUBMENU_FORGET: &str = "Delete the encrypted view key from the state";
+pub const STATUS_SUBMENU_SCAN: &str = "Scan the coinbase of the last blocks with the local node to find the payouts received by the P2Pool address.\nPayouts missed while Gupax was not running are added to the log.\nThe first scan covers a week of blocks, the next ones continue from the last block scanned.";
+pub const STATUS_SUBMENU_UNCONFIRMED: &str = "Payouts read from the P2Pool console that were not found on chain.\nThe block may have been orphaned or the payout paid another address.";
+pub const STATUS_SUBMENU_OBSERVER: &str = "Data of the P2Pool observer of the chain, set in the Advanced P2Pool tab.\nUnlike the local P2Pool, it knows the blocks found by the whole pool and the shares found by every rig mining to your address.";
+pub const STATUS_SUBMENU_OBSERVER_FETCH: &str = "Fetch the last blocks found by P2Pool, and the shares and payouts of your P2Pool address from the observer";
+pub const STATUS_SUBMENU_OBSERVER_BLOCKS: &str = "Last Monero block found by the P2Pool chain";
+pub const STATUS_SUBMENU_OBSERVER_SHARES: &str = "Shares found by your address in the last 24 hours, on every rig. An uncle is a share found at the same time as another one, it is paid less";
+pub const STATUS_SUBMENU_OBSERVER_PAYOUTS: &str =
+    "Payouts of your address known by the observer, compared with the payouts in the log";
+pub const STATUS_SUBMENU_OBSERVER_VERIFY: &str = "Look for the payouts known by the observer but missing from the log in the coinbase of their blocks, with your view key and the local node.\nOnly the payouts found on chain are added to the log, with the amount found on chain.";
+pub const STATUS_SUBMENU_OBSERVER_VERIFY_LOCKED: &str = "Unlock the payout verification with your view key first, the payouts of an observer are not added to the log without being found on chain.";
 pub const STATUS_SUBMENU_P2POOL_DOMINANCE: &str =
     "The percent of hashrate P2Pool accounts for in the entire Monero network";
 pub const STATUS_SUBMENU_YOUR_P2POOL_DOMINANCE: &str =
@@ -360,6 +432,13 @@ pub const GUPAX_LOCK_HEIGHT: &str =
     "Automatically match the WIDTH against the HEIGHT in a 4:3 ratio";
 pub const GUPAX_NO_LOCK: &str = "Allow individual selection of width and height";
 pub const GUPAX_SET: &str = "Set the width/height of the Gupax window to the current values";
+pub const GUPAX_NETWORK: &str = "Monero network used by the Node, P2Pool and the remote nodes. The processes must be restarted to use a new network.";
+pub const GUPAX_NETWORK_MAINNET: &str = "Monero main network, mined coins have value";
+pub const GUPAX_NETWORK_STAGENET: &str = "Monero stagenet, same rules as mainnet but coins have no value. Useful to test a setup without real funds";
+pub const GUPAX_NETWORK_TESTNET: &str =
+    "Monero testnet, used to test the next versions of Monero. Coins have no value";
+pub const GUPAX_NOT_MAINNET: &str =
+    "coins have no value, select mainnet in the Gupax tab to mine real Monero";
 pub const GUPAX_TAB: &str = "Set the default tab Gupax starts on";
 pub const GUPAX_TAB_ABOUT: &str = "Set the tab Gupax starts on to: About";
 pub const GUPAX_TAB_STATUS: &str = "Set the tab Gupax starts on to: Status";
@@ -390,6 +469,17 @@ pub const P2POOL_PORT_DEFAULT: u16 = 3333;
 pub const P2POOL_MAIN: &str = "Use the P2Pool main-chain. This P2Pool finds blocks faster, but has a higher difficulty. Suitable for miners with more than 100kH/s";
 pub const P2POOL_MINI: &str = "Use the P2Pool mini-chain. This P2Pool finds blocks slower, but has a lower difficulty. Suitable for miners with less than 100kH/s";
 pub const P2POOL_NANO: &str = "Use the P2Pool nano-chain. This P2Pool finds blocks slower, but has a lower difficulty. Suitable for miners with less than 30kH/s";
+pub const P2POOL_ADVISOR: &str = "Compare the P2Pool chains for your hashrate. Every chain earns the same on average, but the difficulty of a chain changes how often you get paid.\nThe recommended chain is the one giving the steadiest earnings: a small hashrate rarely finds a share on a chain with a high difficulty, and a chain with a low difficulty rarely finds a Monero block.";
+pub const P2POOL_ADVISOR_HASHRATE: &str = "Hashrate typed in the Status tab if the manual hashrate is enabled, otherwise the one of XMRig-Proxy or XMRig";
+pub const P2POOL_ADVISOR_FETCH: &str = "Fetch the difficulty of every chain from its P2Pool observer. The difficulty of the chain P2Pool is running on is taken from P2Pool";
+pub const P2POOL_ADVISOR_OBSERVER: &str =
+    "Base URL of the P2Pool observer used to fetch the difficulty of this chain";
+pub const P2POOL_ADVISOR_NO_HASHRATE: &str =
+    "No hashrate to give an advice: start XMRig or type your hashrate in the Status tab";
+pub const P2POOL_ADVISOR_SIMPLE: &str =
+    "The simple mode always uses P2Pool Nano, select the chain in the Advanced tab.";
+pub const P2POOL_ADVISOR_CHAIN: &str = "The recommended chain is in green";
+pub const P2POOL_ADVISOR_VARIATION: &str = "How much your earnings of a month can differ from the average, from the time between your shares and the time between the blocks of the chain. The lower the better";
 pub const P2POOL_OUT: &str = "How many out-bound peers to connect to? (you connecting to others)";
 pub const P2POOL_IN: &str = "How many in-bound peers to allow? (others connecting to you)";
 pub const P2POOL_LOG: &str = "Verbosity of the console log.\nA verbosity level more than 0 is recommended to let the P2Pool process detect more rapidly errors with the Monero Node.\nIf the level is at 0, it can take up to 2 minutes to detect an error.";
@@ -403,11 +493,15 @@ pub const P2POOL_BACKUP_HOST_ADVANCED: &str =
     "Automatically switch to the other nodes in your list if the current one is down.";
 pub const P2POOL_AUTOSWITCH_LOCAL_NODE: &str =
     "Automatically switch to the local node when it will be ready to be used.";
+pub const P2POOL_AUTO_FAILOVER: &str = r#"Regularly check the health of the node used by P2Pool and of the backup nodes.
+
+If all of them stay degraded for several checks, find new nodes and restart P2Pool with them.
+Applied at the next start of P2Pool."#;
 pub const P2POOL_SELECT_FASTEST: &str = "Select the fastest remote Monero node";
 pub const P2POOL_SELECT_RANDOM: &str = "Select a random remote Monero node";
 pub const P2POOL_SELECT_LAST: &str = "Select the previous remote Monero node";
 pub const P2POOL_SELECT_NEXT: &str = "Select the next remote Monero node";
-pub const P2POOL_PING: &str = "Ping the built-in remote Monero nodes";
+pub const P2POOL_PING: &str = "Check the latency and health (synchronization, height, network) of the remote Monero nodes and sort them from best to worst";
 pub const P2POOL_ADDRESS: &str = "You must use a primary Monero address to mine on P2Pool (starts with a 4). It is highly recommended to create a new wallet since addresses are public on P2Pool!";
 pub const P2POOL_COMMUNITY_NODE_WARNING: &str = r#"TL;DR: Run & use your own Monero Node.
 
@@ -443,8 +537,17 @@ pub const P2POOL_PATH_NOT_VALID: &str = "P2Pool binary at the given PATH in the
 pub const P2POOL_PATH_OK: &str = "P2Pool was found at the given PATH";
 pub const P2POOL_PATH_EMPTY: &str = "P2Pool PATH is empty! To fix: goto the [Gupax Advanced] tab, select [Open] and specify where P2Pool is located.";
 pub const P2POOL_URL: &str = "https://github.com/SChernykh/p2pool";
+pub const P2POOL_OBSERVER_MAIN: &str = "https://p2pool.observer";
+pub const P2POOL_OBSERVER_MINI: &str = "https://mini.p2pool.observer";
+pub const P2POOL_OBSERVER_NANO: &str = "https://nano.p2pool.observer";
 
 pub const CRAWLER_PARAMETERS_HELP: &str = "You can define parameters for the crawling. Depending on the value, it will make the crawling faster or slower to reach the requirements";
+pub const CRAWLER_ALLOWLIST: &str = "Only use nodes inside these IP/CIDR entries, separated by commas (e.g. 37.187.74.171, 5.9.0.0/16).\nLeave empty to allow every node.";
+pub const CRAWLER_DENYLIST: &str = "Never use nodes inside these IP/CIDR entries, separated by commas (e.g. 37.187.74.171, 5.9.0.0/16).";
+pub const CRAWLER_SPY_NODES: &str = "Path of a file listing spy nodes to exclude, one IP/CIDR entry per line.\nLines starting with # are ignored.";
+pub const CRAWLER_PREFIX_TABLE: &str = "Path of an offline prefix table giving the ASN of the nodes, one [ip/prefix ASN] entry per line (e.g. 1.2.0.0/16 AS13335).\nOptional, without it nodes of the same /16 are considered to be on the same network.";
+pub const CRAWLER_DIVERSITY: &str = "Do not use more than one backup node of the same ASN, or of the same /16 if the ASN is unknown.\nIt makes it less likely for all backup nodes to go down at the same time.";
+pub const CRAWLER_FULL_RPC: &str = "Only use nodes with an unrestricted RPC.\nEvery found node and every reused known node is asked for its network, its answer also tells if its RPC is restricted.";
 // Node/Pool list
 pub const LIST_ADD: &str = "Add the current values to the list";
 pub const LIST_SAVE: &str = "Save the current values to the already existing entry";
@@ -532,6 +635,8 @@ pub const XMRIG_PATH_EMPTY: &str = "XMRig PATH is empty! To fix: goto the [Gupax
 pub const XMRIG_PROXY_URL: &str = "https://github.com/xmrig/xmrig-proxy";
 pub const PROXY_API_PORT_DEFAULT: u16 = 18089;
 pub const PROXY_PORT_DEFAULT: u16 = 3355;
+// HTTP API of the second XMRig mining on XvB when the hashrate is split by threads
+pub const XMRIG_XVB_WORKER_API_PORT: u16 = 18090;
 
 // XvB
 pub const XVB_MANUAL_SLIDER_MANUAL_XVB_HELP: &str = "Set the hashrate amount to donate to XvB manually, The remaining hashrate will be sent to p2pool. If the selected hashrate is more than your xmrig hashrate it will be overwritten";
@@ -547,19 +652,38 @@ pub const XVB_ADVANCED: &str = r#"Use advanced XvB settings:
       Hero,
       Manual XvB,
       Manual P2pool,
-      Round
-  - P2Pool Buffer"#;
+      Round,
+      Predictive
+  - P2Pool Buffer
+  - Split of the hashrate by time or by threads"#;
 pub const XVB_URL_PUBLIC_API: &str = "https://xmrvsbeast.com/p2pool/stats";
-pub const XVB_NODE_PORT: &str = "4247";
+pub const XVB_NODE_PORT: u16 = 4247;
 pub const XVB_NODE_EU: &str = "eu.xmrvsbeast.com";
 pub const XVB_NODE_NA: &str = "na.xmrvsbeast.com";
 pub const XVB_URL_RULES: &str = "https://xmrvsbeast.com/p2pool/rules.html";
+pub const XVB_URL_REGISTER: &str = "https://xmrvsbeast.com/cgi-bin/p2pool_register_gupaxx_api.cgi";
 // buffer in percentage of HR to have plus the requirement.
 pub const XVB_SIDE_MARGIN_1H: f32 = 0.2;
 // time is in ms
 pub const XVB_TIME_ALGO: u64 = 60_000;
 // minimum time to send to XvB if any
 pub const XVB_MIN_TIME_SEND: u64 = 50;
+// time between two checks of the XvB pool while mining on it
+pub const XVB_FAILOVER_CHECK: u64 = 1_000;
+// time given to XMRig to connect to the XvB pool before it is considered offline
+pub const XVB_FAILOVER_GRACE: u64 = 10_000;
+// shares rejected by the XvB pool in a row before leaving it
+pub const XVB_FAILOVER_REJECTED: u64 = 3;
+// seconds after which the report of a sibling instance is ignored
+pub const XVB_SIBLING_TIMEOUT: i64 = 300;
+// port the leader of the XvB coordination listens on by default
+pub const XVB_COORDINATION_PORT: u16 = 18095;
+// seconds after which a follower that stopped reporting is not assigned anything
+pub const XVB_COORDINATION_TIMEOUT: i64 = 180;
+// ms given to a follower to send its request
+pub const XVB_COORDINATION_READ_TIMEOUT: u64 = 5_000;
+// seconds of difference allowed between the timestamp of a report and the clock of the leader
+pub const XVB_COORDINATION_MAX_SKEW: i64 = 120;
 pub const XVB_HERO_SELECT: &str = "Donate as much as possible while keeping a share on p2pool, increases the odds of your round winning\nWhen modified, the algorithm will use the new choice at the next decision.";
 pub const XVB_FAILURE_FIELD: &str = "Failures";
 pub const XVB_DONATED_1H_FIELD: &str = "Donated last hour";
@@ -567,6 +691,12 @@ pub const XVB_DONATED_24H_FIELD: &str = "Donated last 24 hours";
 pub const XVB_ROUND_TYPE_FIELD: &str = "Round";
 pub const XVB_WINNER_FIELD: &str = "Win";
 pub const XVB_MINING_ON_FIELD: &str = "Currently Mining on";
+pub const XVB_HISTORY: &str = "Rounds detected every hour by the XvB process and results of the raffles it has seen.\nThe history is kept for a month and saved across restarts of Gupax.";
+pub const XVB_HISTORY_DONATED: &str = "Time spent mining on the XvB pool during the hour";
+pub const XVB_HISTORY_WINNING: &str =
+    "Whether you were winning the raffle of the current round at the end of the hour";
+pub const XVB_HISTORY_ROLL: &str =
+    "Number rolled for the winner and for the round type of the raffle";
 
 pub const XVB_ROUND_DONOR_MIN_HR: u32 = 1000;
 pub const XVB_ROUND_DONOR_VIP_MIN_HR: u32 = 10000;
@@ -582,6 +712,17 @@ pub const HELP_STRATUM_IP: &str = "Specify the stratum ip to bind to";
 pub const XVB_MODE_MANUAL_XVB_HELP: &str = "Manually set the amount to donate to XmrVsBeast, If value is more than xmrig hashrate it might be changed";
 pub const XVB_MODE_MANUAL_P2POOL_HELP: &str = "Manually set the amount to keep on P2pool, If value is more than xmrig hashrate it might be changed";
 pub const XVB_MODE_MANUAL_DONATION_LEVEL_HELP: &str = "Manually set the XvB donation level";
+pub const XVB_MODE_PREDICTIVE_HELP: &str = "Target the same round as the Auto mode, but send to XvB during stretches of several minutes instead of every minute.\nThe 1h average of XvB is projected with the hashrate sent during the last hour to know when to switch, which reduces the number of switches between P2Pool and XvB.\nThe minimum hashrate to keep a share stays on P2Pool, and XvB is left before your last share leaves the PPLNS window.";
+pub const XVB_MODE_GOAL_HELP: &str = "Donate toward a round or a daily % of your hashrate, never more than the cap and never the hashrate needed to keep a share in the PPLNS window.";
+pub const XVB_GOAL_ROUND_HELP: &str = "Reach the chosen round. If the cap or the share do not allow it, the highest round under them is reached instead.";
+pub const XVB_GOAL_DAILY_PERCENT_HELP: &str = "Donate this % of your hashrate on average over the last 24h. Missed donations are caught up, within the cap.";
+pub const XVB_GOAL_CAP: &str =
+    "The goal mode never sends more than this % of your hashrate to XvB.";
+pub const XVB_SPLIT_TIME_HELP: &str =
+    "XMRig mines on P2Pool and switches to XvB for the needed time of every cycle.";
+pub const XVB_SPLIT_THREADS_HELP: &str = "XMRig mines on P2Pool with some of its threads while a second XMRig started by Gupax mines on XvB with the others, without switching pools.\nThe hashrate sent to XvB can only change by the hashrate of a thread. Not available with XMRig-Proxy or with custom XMRig arguments without --threads, the hashrate is then split by time.";
+pub const XVB_DECISIONS: &str = "Last decisions of the algorithm with the values they were made with and how the hashrate to send was computed.";
+pub const XVB_WHAT_IF: &str = "Change the hashrate, the P2Pool buffer or the mode to see what the algorithm would do with the values of its last decision. The running algorithm is not changed.";
 
 // Manual Donation Levels
 pub const XVB_DONATION_LEVEL_DONOR_HELP: &str =
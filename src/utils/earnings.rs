@@ -0,0 +1,261 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Projection of the earnings of a hashrate mining on P2Pool.
+//
+// P2Pool has no fee, so on average a miner earns the share of the Monero hashrate it owns:
+//     XMR per second = hashrate / monero difficulty × block reward
+// whatever the chain. The chain changes how the earnings are distributed:
+// a payout happens when P2Pool finds a Monero block while at least one share of the miner is in the PPLNS window.
+// The window contains [window] sidechain blocks, so the miner expects
+//     shares = window × sidechain block time × hashrate / sidechain difficulty
+// shares in it, and has a chance of 1 - e^(-shares) to be in it (Poisson).
+// A low hashrate on a chain with a high difficulty gets rare and big payouts, with a lot of variance.
+//
+// What is earned over a period depends on two random counts, the shares found by the miner and the blocks found by the chain.
+// Approximating both as Poisson, the relative standard deviation of the earnings over [seconds] is
+//     sqrt((share mean + block mean) / seconds)
+// so the best chain for a hashrate is the one minimizing the sum of the two means.
+
+use crate::disk::state::P2poolChain;
+use crate::utils::xmr::AtomicUnit;
+
+//---------------------------------------------------------------------------------------------------- Const
+pub const HOUR: f64 = 3600.0;
+pub const DAY: f64 = HOUR * 24.0;
+// Same month as the [XMR mined per] stats of P2Pool.
+pub const MONTH: f64 = DAY * 30.0;
+pub const WEEK: f64 = DAY * 7.0;
+
+//---------------------------------------------------------------------------------------------------- Earnings
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarningsInput {
+    pub hashrate: u64,
+    pub monero_difficulty: u64,
+    pub block_reward: AtomicUnit,
+    pub chain: P2poolChain,
+    pub sidechain_difficulty: u64,
+    // Number of sidechain blocks in the PPLNS window
+    pub window_length_blocks: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Earnings {
+    // XMR earned per second on average
+    pub xmr_per_second: f64,
+    // Shares of the miner expected in the PPLNS window
+    pub window_shares: f64,
+    // Probability to have at least one share in the PPLNS window when P2Pool finds a block
+    pub window_chance: f64,
+    // Seconds between two blocks found by the chain, None if the difficulties are unknown
+    pub block_mean: Option<f64>,
+    // Seconds between two payouts of the miner, None if it will never get one
+    pub payout_mean: Option<f64>,
+    // Seconds between two shares of the miner, None if the sidechain difficulty is unknown
+    pub share_mean: Option<f64>,
+}
+
+impl Earnings {
+    pub fn new(input: &EarningsInput) -> Self {
+        if input.hashrate == 0 || input.monero_difficulty == 0 {
+            return Self::default();
+        }
+        let hashrate = input.hashrate as f64;
+        let xmr_per_second = hashrate / input.monero_difficulty as f64
+            * input.block_reward.to_u64() as f64
+            / 1_000_000_000_000.0;
+        if input.sidechain_difficulty == 0 {
+            return Self {
+                xmr_per_second,
+                ..Default::default()
+            };
+        }
+        let block_time = input.chain.seconds_per_block() as f64;
+        let sidechain_difficulty = input.sidechain_difficulty as f64;
+        let window_shares =
+            input.window_length_blocks as f64 * block_time * hashrate / sidechain_difficulty;
+        let window_chance = 1.0 - (-window_shares).exp();
+        // the hashrate of the chain is its difficulty spread over its block time
+        let block_mean = input.monero_difficulty as f64 * block_time / sidechain_difficulty;
+        let payout_mean = (window_chance > 0.0).then(|| block_mean / window_chance);
+        Self {
+            xmr_per_second,
+            window_shares,
+            window_chance,
+            block_mean: Some(block_mean),
+            payout_mean,
+            share_mean: Some(sidechain_difficulty / hashrate),
+        }
+    }
+
+    // XMR earned on average during [seconds].
+    pub fn xmr(&self, seconds: f64) -> f64 {
+        self.xmr_per_second * seconds
+    }
+
+    // Average amount of a payout, when the miner is in the window.
+    pub fn payout(&self) -> Option<f64> {
+        self.payout_mean.map(|mean| self.xmr(mean))
+    }
+
+    // Relative standard deviation of the XMR earned during [seconds], 0.5 means ±50%.
+    pub fn variation(&self, seconds: f64) -> Option<f64> {
+        Some(((self.share_mean? + self.block_mean?) / seconds).sqrt())
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- Profit
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Profit {
+    pub income: f64,
+    pub electricity: f64,
+}
+
+impl Profit {
+    // Fiat earned and spent in electricity during [seconds], with the price of a XMR,
+    // the consumption of the rig in watts and the price of a kWh.
+    pub fn new(
+        earnings: &Earnings,
+        seconds: f64,
+        xmr_price: f64,
+        watts: f64,
+        kwh_price: f64,
+    ) -> Self {
+        Self {
+            income: earnings.xmr(seconds) * xmr_price,
+            electricity: watts / 1000.0 * seconds / HOUR * kwh_price,
+        }
+    }
+
+    pub fn net(&self) -> f64 {
+        self.income - self.electricity
+    }
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn input(hashrate: u64, chain: P2poolChain, sidechain_difficulty: u64) -> EarningsInput {
+        EarningsInput {
+            hashrate,
+            // 3 GH/s network
+            monero_difficulty: 360_000_000_000,
+            block_reward: AtomicUnit::from_u64(600_000_000_000),
+            chain: chain.clone(),
+            sidechain_difficulty,
+            window_length_blocks: chain.pplns_window_blocks(),
+        }
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() <= b.abs() * 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn expected_xmr() {
+        // 10 kH/s on a 3 GH/s network earns 1/300_000 of the 720 blocks of a day
+        let earnings = Earnings::new(&input(10_000, P2poolChain::Mini, 30_000_000));
+        assert_close(earnings.xmr(DAY), 720.0 * 0.6 / 300_000.0);
+        assert_close(earnings.xmr(WEEK), earnings.xmr(DAY) * 7.0);
+        assert_close(earnings.xmr(MONTH), earnings.xmr(DAY) * 30.0);
+        // the chain does not change the average
+        let main = Earnings::new(&input(10_000, P2poolChain::Main, 2_000_000_000));
+        let nano = Earnings::new(&input(10_000, P2poolChain::Nano, 3_000_000));
+        assert_close(main.xmr(DAY), earnings.xmr(DAY));
+        assert_close(nano.xmr(DAY), earnings.xmr(DAY));
+        // nothing is known without hashrate or network difficulty
+        assert_eq!(
+            Earnings::new(&input(0, P2poolChain::Mini, 30_000_000)),
+            Earnings::default()
+        );
+        let unknown = EarningsInput {
+            monero_difficulty: 0,
+            ..input(10_000, P2poolChain::Mini, 30_000_000)
+        };
+        assert_eq!(Earnings::new(&unknown), Earnings::default());
+    }
+
+    #[test]
+    fn pplns_window() {
+        // Mini at 3 MH/s: 10 kH/s expects 2160 × 10 × 10_000 / 30_000_000 = 7.2 shares in the window
+        let mini = Earnings::new(&input(10_000, P2poolChain::Mini, 30_000_000));
+        assert_close(mini.window_shares, 7.2);
+        assert_close(mini.window_chance, 1.0 - (-7.2f64).exp());
+        // P2Pool Mini finds a block every 360_000_000_000 / 3_000_000 seconds
+        assert_close(mini.block_mean.unwrap(), 120_000.0);
+        assert_close(
+            mini.payout_mean.unwrap(),
+            120_000.0 / (1.0 - (-7.2f64).exp()),
+        );
+        // Main at 200 MH/s: 0.108 shares, rare payouts
+        let main = Earnings::new(&input(10_000, P2poolChain::Main, 2_000_000_000));
+        assert_close(main.window_shares, 0.108);
+        assert!(main.window_chance < 0.11);
+        assert!(main.payout_mean.unwrap() > main.block_mean.unwrap() * 9.0);
+        // bigger payouts to earn the same
+        assert!(main.payout().unwrap() > main.xmr(main.block_mean.unwrap()) * 9.0);
+        // window set by the running P2Pool
+        let short = EarningsInput {
+            window_length_blocks: 1080,
+            ..input(10_000, P2poolChain::Mini, 30_000_000)
+        };
+        assert_close(Earnings::new(&short).window_shares, 3.6);
+        // unknown sidechain difficulty
+        let unknown = Earnings::new(&input(10_000, P2poolChain::Nano, 0));
+        assert!(unknown.xmr_per_second > 0.0);
+        assert_eq!(unknown.block_mean, None);
+        assert_eq!(unknown.payout_mean, None);
+    }
+
+    #[test]
+    fn earnings_variation() {
+        // Mini: a share every 3000 seconds and a block every 120_000 seconds
+        let mini = Earnings::new(&input(10_000, P2poolChain::Mini, 30_000_000));
+        assert_close(mini.share_mean.unwrap(), 3000.0);
+        assert_close(mini.variation(DAY).unwrap(), (123_000.0 / DAY).sqrt());
+        // the variation fades with time
+        assert_close(
+            mini.variation(MONTH).unwrap(),
+            mini.variation(DAY).unwrap() / 30f64.sqrt(),
+        );
+        // Main finds blocks often but 10 kH/s rarely finds a share there
+        let main = Earnings::new(&input(10_000, P2poolChain::Main, 2_000_000_000));
+        assert!(main.variation(DAY).unwrap() > mini.variation(DAY).unwrap());
+        // 10 MH/s finds shares on Main all the time and gets paid every block
+        let big_main = Earnings::new(&input(10_000_000, P2poolChain::Main, 2_000_000_000));
+        let big_mini = Earnings::new(&input(10_000_000, P2poolChain::Mini, 30_000_000));
+        assert!(big_main.variation(DAY).unwrap() < big_mini.variation(DAY).unwrap());
+        assert_eq!(
+            Earnings::new(&input(10_000, P2poolChain::Mini, 0)).variation(DAY),
+            None
+        );
+    }
+
+    #[test]
+    fn electricity_cost() {
+        let earnings = Earnings::new(&input(10_000, P2poolChain::Mini, 30_000_000));
+        // 100 W during a day is 2.4 kWh
+        let profit = Profit::new(&earnings, DAY, 150.0, 100.0, 0.25);
+        assert_close(profit.electricity, 0.6);
+        assert_close(profit.income, earnings.xmr(DAY) * 150.0);
+        assert_close(profit.net(), profit.income - 0.6);
+        let free = Profit::new(&earnings, DAY, 150.0, 0.0, 0.25);
+        assert_close(free.net(), free.income);
+    }
+}
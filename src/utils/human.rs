@@ -53,6 +53,11 @@ impl HumanTime {
         HumanTime(Duration::from_secs(u))
     }
 
+    #[inline]
+    pub const fn as_secs(&self) -> u64 {
+        self.0.as_secs()
+    }
+
     fn plural(started: &mut bool, name: &str, value: u64, separator: &str) -> String {
         // do not show time if value is 0 unless it is for seconds.
         let mut string = String::new();
@@ -0,0 +1,285 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// IP rules used to choose which remote nodes can be used.
+// Lists of IP/CIDR entries (allowlist, denylist, spy nodes) and an offline
+// prefix table giving the ASN of an IP, to avoid picking several nodes from the same network.
+
+use anyhow::{Result, anyhow, bail};
+use std::{fs, net::IpAddr, path::Path, str::FromStr};
+
+//---------------------------------------------------------------------------------------------------- Const
+// prefix used to group IPv4 nodes of the same network when the ASN is unknown
+pub const DIVERSITY_PREFIX_V4: u8 = 16;
+// prefix used to group IPv6 nodes of the same network when the ASN is unknown
+pub const DIVERSITY_PREFIX_V6: u8 = 32;
+
+//---------------------------------------------------------------------------------------------------- Cidr
+// A network in the form [ip/prefix]. A single IP is a network with the maximum prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    pub addr: IpAddr,
+    pub prefix: u8,
+}
+
+impl FromStr for Cidr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = addr
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| anyhow!("invalid IP address [{}]", addr.trim()))?
+            .to_canonical();
+        let max = max_prefix(addr);
+        let prefix = match prefix {
+            Some(prefix) => match prefix.trim().parse::<u8>() {
+                Ok(p) if p <= max => p,
+                _ => bail!("invalid prefix [{}] for [{addr}]", prefix.trim()),
+            },
+            None => max,
+        };
+        Ok(Self::new(addr, prefix))
+    }
+}
+
+impl std::fmt::Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl Cidr {
+    // The address is masked so that two equal networks are always equal.
+    pub fn new(addr: IpAddr, prefix: u8) -> Self {
+        let addr = addr.to_canonical();
+        let prefix = prefix.min(max_prefix(addr));
+        let addr = match addr {
+            IpAddr::V4(ip) => IpAddr::from((u32::from(ip) & mask(prefix, 32) as u32).to_be_bytes()),
+            IpAddr::V6(ip) => IpAddr::from((u128::from(ip) & mask(prefix, 128)).to_be_bytes()),
+        };
+        Self { addr, prefix }
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        match (self.addr, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                Self::new(ip, self.prefix).addr == self.addr
+            }
+            _ => false,
+        }
+    }
+}
+
+fn max_prefix(addr: IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+// mask keeping the [prefix] highest bits of an address of [width] bits.
+fn mask(prefix: u8, width: u8) -> u128 {
+    if prefix == 0 {
+        return 0;
+    }
+    let all = if width == 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    };
+    all & !((1u128 << (width - prefix)).wrapping_sub(1))
+}
+
+// Entries can be separated by commas, spaces or new lines.
+// Everything after a [#] on a line is a comment.
+pub fn parse_cidr_list(s: &str) -> Result<Vec<Cidr>> {
+    let mut list = vec![];
+    for line in s.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        for entry in line.split([',', ' ', '\t']).filter(|e| !e.is_empty()) {
+            list.push(entry.parse()?);
+        }
+    }
+    Ok(list)
+}
+
+// Read a list file, like the ban lists shared by the community to exclude spy nodes.
+pub fn read_cidr_list(path: &Path) -> Result<Vec<Cidr>> {
+    let string = fs::read_to_string(path)
+        .map_err(|e| anyhow!("could not read [{}]: {e}", path.display()))?;
+    parse_cidr_list(&string).map_err(|e| anyhow!("[{}]: {e}", path.display()))
+}
+
+//---------------------------------------------------------------------------------------------------- PrefixTable
+// Offline table associating networks to the ASN (Autonomous System Number) announcing them.
+// One entry per line: [ip/prefix ASN], the ASN can be written [13335] or [AS13335].
+// Lines starting with [#] are comments.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrefixTable {
+    // sorted by prefix, most specific first
+    entries: Vec<(Cidr, u32)>,
+}
+
+impl FromStr for PrefixTable {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut entries = vec![];
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(cidr), Some(asn), None) = (fields.next(), fields.next(), fields.next())
+            else {
+                bail!("line {}: expected [ip/prefix ASN], found [{line}]", i + 1);
+            };
+            let cidr = cidr
+                .parse::<Cidr>()
+                .map_err(|e| anyhow!("line {}: {e}", i + 1))?;
+            let asn = asn
+                .trim_start_matches("AS")
+                .parse::<u32>()
+                .map_err(|_| anyhow!("line {}: invalid ASN [{asn}]", i + 1))?;
+            entries.push((cidr, asn));
+        }
+        entries.sort_by(|a, b| b.0.prefix.cmp(&a.0.prefix));
+        Ok(Self { entries })
+    }
+}
+
+impl PrefixTable {
+    pub fn read(path: &Path) -> Result<Self> {
+        let string = fs::read_to_string(path)
+            .map_err(|e| anyhow!("could not read [{}]: {e}", path.display()))?;
+        string
+            .parse()
+            .map_err(|e| anyhow!("[{}]: {e}", path.display()))
+    }
+
+    // ASN of the most specific network containing the IP.
+    pub fn asn(&self, ip: IpAddr) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|(cidr, _)| cidr.contains(ip))
+            .map(|(_, asn)| *asn)
+    }
+
+    // Group of the IP used to avoid picking several nodes from the same network:
+    // its ASN if known, its /16 (IPv4) or /32 (IPv6) otherwise.
+    pub fn network_group(&self, ip: IpAddr) -> NetworkGroup {
+        match self.asn(ip) {
+            Some(asn) => NetworkGroup::Asn(asn),
+            None => {
+                let ip = ip.to_canonical();
+                let prefix = match ip {
+                    IpAddr::V4(_) => DIVERSITY_PREFIX_V4,
+                    IpAddr::V6(_) => DIVERSITY_PREFIX_V6,
+                };
+                NetworkGroup::Prefix(Cidr::new(ip, prefix))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NetworkGroup {
+    Asn(u32),
+    Prefix(Cidr),
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_cidr() {
+        let cidr = "192.168.1.77/16".parse::<Cidr>().unwrap();
+        assert_eq!(cidr.to_string(), "192.168.0.0/16");
+        assert!(cidr.contains("192.168.250.1".parse().unwrap()));
+        assert!(!cidr.contains("192.169.0.1".parse().unwrap()));
+        assert!(!cidr.contains("::1".parse().unwrap()));
+        // single ip
+        let cidr = "37.187.74.171".parse::<Cidr>().unwrap();
+        assert_eq!(cidr.prefix, 32);
+        assert!(cidr.contains("37.187.74.171".parse().unwrap()));
+        assert!(!cidr.contains("37.187.74.172".parse().unwrap()));
+        // ipv4 mapped ipv6 is treated as ipv4
+        assert!(cidr.contains("::ffff:37.187.74.171".parse().unwrap()));
+        // everything
+        let cidr = "0.0.0.0/0".parse::<Cidr>().unwrap();
+        assert!(cidr.contains("1.2.3.4".parse().unwrap()));
+        let cidr = "2001:db8::/32".parse::<Cidr>().unwrap();
+        assert!(cidr.contains("2001:db8:ffff::1".parse().unwrap()));
+        assert!(!cidr.contains("2001:db9::1".parse().unwrap()));
+        // errors
+        assert!("1.2.3.4/33".parse::<Cidr>().is_err());
+        assert!("1.2.3/8".parse::<Cidr>().is_err());
+        assert!("2001:db8::/129".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn parse_list() {
+        let list = parse_cidr_list(
+            "# spy nodes\n1.2.3.4, 5.6.0.0/16 # comment\n\n2001:db8::/32\n10.0.0.1\t10.0.0.2",
+        )
+        .unwrap();
+        assert_eq!(list.len(), 5);
+        assert_eq!(list[1], "5.6.0.0/16".parse().unwrap());
+        assert!(parse_cidr_list("").unwrap().is_empty());
+        assert!(parse_cidr_list("1.2.3.4, not_an_ip").is_err());
+    }
+
+    #[test]
+    fn prefix_table() {
+        let table = "# prefix asn\n1.0.0.0/8 AS100\n1.2.0.0/16 200\n\n2001:db8::/32 300\n"
+            .parse::<PrefixTable>()
+            .unwrap();
+        // most specific first
+        assert_eq!(table.asn("1.2.3.4".parse().unwrap()), Some(200));
+        assert_eq!(table.asn("1.3.3.4".parse().unwrap()), Some(100));
+        assert_eq!(table.asn("2001:db8::1".parse().unwrap()), Some(300));
+        assert_eq!(table.asn("8.8.8.8".parse().unwrap()), None);
+        // fallback to the /16
+        assert_eq!(
+            table.network_group("1.5.3.4".parse().unwrap()),
+            NetworkGroup::Asn(100)
+        );
+        assert_eq!(
+            table.network_group("8.8.8.8".parse().unwrap()),
+            table.network_group("8.8.4.4".parse().unwrap())
+        );
+        assert_ne!(
+            table.network_group("8.8.8.8".parse().unwrap()),
+            table.network_group("8.9.8.8".parse().unwrap())
+        );
+        // errors give the line
+        let err = "1.0.0.0/8 AS100\n1.2.0.0/16"
+            .parse::<PrefixTable>()
+            .unwrap_err();
+        assert!(err.to_string().starts_with("line 2"));
+        assert!("1.0.0.0/8 ASX".parse::<PrefixTable>().is_err());
+    }
+}
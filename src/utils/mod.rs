@@ -16,13 +16,19 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 pub mod constants;
+pub mod earnings;
 pub mod errors;
 pub mod ferris;
+#[cfg(test)]
+pub mod http_mock;
 pub mod human;
+pub mod ip_rules;
 pub mod macros;
+pub mod monero_address;
 pub mod node_latency;
 pub mod panic;
 pub mod regex;
 pub mod resets;
 pub mod sudo;
+pub mod view_key;
 pub mod xmr;
@@ -0,0 +1,566 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Decoder of Monero addresses.
+// An address is the Monero flavor of Base58 of:
+//     [network byte][public spend key: 32 bytes][public view key: 32 bytes]([payment id: 8 bytes])[checksum: 4 bytes]
+// The checksum is the first 4 bytes of the Keccak-256 hash of everything before it.
+// The payment id is only present in integrated addresses.
+
+use crate::helper::ProcessName;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use strum::EnumIter;
+
+//---------------------------------------------------------------------------------------------------- Const
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+// Monero Base58 encodes blocks of 8 bytes in 11 characters.
+const FULL_BLOCK_SIZE: usize = 8;
+const FULL_ENCODED_BLOCK_SIZE: usize = 11;
+// number of characters used to encode a block of [index] bytes.
+const ENCODED_BLOCK_SIZES: [usize; FULL_BLOCK_SIZE + 1] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+pub const KEY_SIZE: usize = 32;
+const PAYMENT_ID_SIZE: usize = 8;
+const CHECKSUM_SIZE: usize = 4;
+// length of primary addresses and subaddresses
+pub const ADDRESS_LEN: usize = 95;
+pub const INTEGRATED_ADDRESS_LEN: usize = 106;
+
+//---------------------------------------------------------------------------------------------------- Network/AddressType
+// Monero network used by Gupax. Stagenet and testnet coins have no value, they are used to test a setup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, EnumIter)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Stagenet,
+    Testnet,
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mainnet => write!(f, "mainnet"),
+            Self::Stagenet => write!(f, "stagenet"),
+            Self::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+// The names are the [nettype] returned by the [get_info] RPC of monerod.
+impl FromStr for Network {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "mainnet" => Ok(Self::Mainnet),
+            "stagenet" => Ok(Self::Stagenet),
+            "testnet" => Ok(Self::Testnet),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Network {
+    // Flag given to monerod, mainnet is the default.
+    pub fn monerod_flag(&self) -> Option<&'static str> {
+        match self {
+            Self::Mainnet => None,
+            Self::Stagenet => Some("--stagenet"),
+            Self::Testnet => Some("--testnet"),
+        }
+    }
+
+    // Monero ports of the other networks are the mainnet ones shifted by 10000 (testnet) or 20000 (stagenet).
+    // Only the ports around the default ones are shifted, a custom port is kept as is.
+    pub fn port(&self, mainnet_port: u16) -> u16 {
+        if !(18080..19000).contains(&mainnet_port) {
+            return mainnet_port;
+        }
+        match self {
+            Self::Mainnet => mainnet_port,
+            Self::Testnet => mainnet_port + 10000,
+            Self::Stagenet => mainnet_port + 20000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressType {
+    Primary,
+    Integrated,
+    Subaddress,
+}
+
+impl fmt::Display for AddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Primary => write!(f, "primary address"),
+            Self::Integrated => write!(f, "integrated address"),
+            Self::Subaddress => write!(f, "subaddress"),
+        }
+    }
+}
+
+// Network bytes of every network and address type.
+const NETWORK_BYTES: [(u64, Network, AddressType); 9] = [
+    (18, Network::Mainnet, AddressType::Primary),
+    (19, Network::Mainnet, AddressType::Integrated),
+    (42, Network::Mainnet, AddressType::Subaddress),
+    (24, Network::Stagenet, AddressType::Primary),
+    (25, Network::Stagenet, AddressType::Integrated),
+    (36, Network::Stagenet, AddressType::Subaddress),
+    (53, Network::Testnet, AddressType::Primary),
+    (54, Network::Testnet, AddressType::Integrated),
+    (63, Network::Testnet, AddressType::Subaddress),
+];
+
+//---------------------------------------------------------------------------------------------------- AddressError
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressError {
+    Length(usize),
+    Character(char),
+    Base58,
+    NetworkByte(u64),
+    Checksum,
+    Network { expected: Network, found: Network },
+    PrimaryRequired(ProcessName, AddressType),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Length(len) => write!(
+                f,
+                "Invalid length [{len}], an address has {ADDRESS_LEN} characters ({INTEGRATED_ADDRESS_LEN} if integrated)"
+            ),
+            Self::Character(c) => write!(
+                f,
+                "Invalid character [{c}], an address can not contain 0, O, I or l"
+            ),
+            Self::Base58 => write!(f, "Invalid Base58 encoding"),
+            Self::NetworkByte(byte) => write!(f, "Unknown network byte [{byte}]"),
+            Self::Checksum => write!(f, "Invalid checksum, the address is mistyped"),
+            Self::Network { expected, found } => {
+                write!(f, "This is a {found} address, {expected} is used")
+            }
+            Self::PrimaryRequired(process, found) => {
+                write!(f, "{process} requires a primary address, this is a {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+//---------------------------------------------------------------------------------------------------- MoneroAddress
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoneroAddress {
+    pub network: Network,
+    pub kind: AddressType,
+    pub spend_key: [u8; KEY_SIZE],
+    pub view_key: [u8; KEY_SIZE],
+    pub payment_id: Option<[u8; PAYMENT_ID_SIZE]>,
+}
+
+impl FromStr for MoneroAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, AddressError> {
+        if s.len() != ADDRESS_LEN && s.len() != INTEGRATED_ADDRESS_LEN {
+            return Err(AddressError::Length(s.chars().count()));
+        }
+        let bytes = base58_decode(s)?;
+        let (data, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if keccak256(data)[..CHECKSUM_SIZE] != *checksum {
+            return Err(AddressError::Checksum);
+        }
+        let (byte, data) = read_varint(data).ok_or(AddressError::Base58)?;
+        let (_, network, kind) = NETWORK_BYTES
+            .into_iter()
+            .find(|(b, _, _)| *b == byte)
+            .ok_or(AddressError::NetworkByte(byte))?;
+        let expected_len = match kind {
+            AddressType::Integrated => KEY_SIZE * 2 + PAYMENT_ID_SIZE,
+            _ => KEY_SIZE * 2,
+        };
+        if data.len() != expected_len {
+            return Err(AddressError::Length(s.len()));
+        }
+        let mut spend_key = [0; KEY_SIZE];
+        let mut view_key = [0; KEY_SIZE];
+        spend_key.copy_from_slice(&data[..KEY_SIZE]);
+        view_key.copy_from_slice(&data[KEY_SIZE..KEY_SIZE * 2]);
+        let payment_id = (kind == AddressType::Integrated).then(|| {
+            let mut payment_id = [0; PAYMENT_ID_SIZE];
+            payment_id.copy_from_slice(&data[KEY_SIZE * 2..]);
+            payment_id
+        });
+        Ok(Self {
+            network,
+            kind,
+            spend_key,
+            view_key,
+            payment_id,
+        })
+    }
+}
+
+impl MoneroAddress {
+    // Check that the address can be used by this process on this network.
+    // P2Pool (and XvB which uses the P2Pool address) can only pay out to a primary address.
+    pub fn check(&self, process: ProcessName, network: Network) -> Result<(), AddressError> {
+        if self.network != network {
+            return Err(AddressError::Network {
+                expected: network,
+                found: self.network,
+            });
+        }
+        if matches!(process, ProcessName::P2pool | ProcessName::Xvb)
+            && self.kind != AddressType::Primary
+        {
+            return Err(AddressError::PrimaryRequired(process, self.kind));
+        }
+        Ok(())
+    }
+}
+
+// Decode the address and check that it can be used by this process.
+pub fn check_address(
+    address: &str,
+    process: ProcessName,
+    network: Network,
+) -> Result<MoneroAddress, AddressError> {
+    let address = address.parse::<MoneroAddress>()?;
+    address.check(process, network)?;
+    Ok(address)
+}
+
+//---------------------------------------------------------------------------------------------------- Base58
+// Monero Base58 is not the Bitcoin one: the data is split in blocks of 8 bytes,
+// each one encoded in 11 characters, so the encoded length never depends on the leading zeros.
+fn base58_decode(s: &str) -> Result<Vec<u8>, AddressError> {
+    let s = s.as_bytes();
+    let last_block_size = ENCODED_BLOCK_SIZES
+        .iter()
+        .position(|size| *size == s.len() % FULL_ENCODED_BLOCK_SIZE)
+        .ok_or(AddressError::Length(s.len()))?;
+    let mut bytes = Vec::with_capacity(s.len() / FULL_ENCODED_BLOCK_SIZE * FULL_BLOCK_SIZE + 8);
+    for block in s.chunks(FULL_ENCODED_BLOCK_SIZE) {
+        let size = if block.len() == FULL_ENCODED_BLOCK_SIZE {
+            FULL_BLOCK_SIZE
+        } else {
+            last_block_size
+        };
+        let mut value: u64 = 0;
+        for c in block {
+            let digit = ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or(AddressError::Character(*c as char))?;
+            value = value
+                .checked_mul(58)
+                .and_then(|v| v.checked_add(digit as u64))
+                .ok_or(AddressError::Base58)?;
+        }
+        if size < FULL_BLOCK_SIZE && value >> (size * 8) != 0 {
+            return Err(AddressError::Base58);
+        }
+        bytes.extend_from_slice(&value.to_be_bytes()[FULL_BLOCK_SIZE - size..]);
+    }
+    Ok(bytes)
+}
+
+// The network byte is a varint, even if every known one fits in a single byte.
+// Transactions also use them for the indexes and lengths.
+pub fn read_varint(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value: u64 = 0;
+    for (i, byte) in data.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, &data[i + 1..]));
+        }
+    }
+    None
+}
+
+pub fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+//---------------------------------------------------------------------------------------------------- Keccak-256
+// Original Keccak-256 used by Monero, which differs from the standardized SHA3-256 by its padding.
+const KECCAK_RATE: usize = 136;
+const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+const KECCAK_ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+const KECCAK_PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for round_constant in KECCAK_ROUND_CONSTANTS {
+        // theta
+        let mut columns = [0u64; 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let t = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            for y in (0..25).step_by(5) {
+                state[y + x] ^= t;
+            }
+        }
+        // rho and pi
+        let mut last = state[1];
+        for (rotation, lane) in KECCAK_ROTATIONS.iter().zip(KECCAK_PI) {
+            let current = state[lane];
+            state[lane] = last.rotate_left(*rotation);
+            last = current;
+        }
+        // chi
+        for y in (0..25).step_by(5) {
+            let row = [
+                state[y],
+                state[y + 1],
+                state[y + 2],
+                state[y + 3],
+                state[y + 4],
+            ];
+            for x in 0..5 {
+                state[y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // iota
+        state[0] ^= round_constant;
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+    let mut absorb = |block: &[u8; KECCAK_RATE]| {
+        for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        keccak_f(&mut state);
+    };
+    let mut blocks = data.chunks_exact(KECCAK_RATE);
+    for block in blocks.by_ref() {
+        absorb(block.try_into().unwrap());
+    }
+    let remainder = blocks.remainder();
+    let mut last = [0u8; KECCAK_RATE];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] ^= 0x01;
+    last[KECCAK_RATE - 1] ^= 0x80;
+    absorb(&last);
+    let mut hash = [0u8; 32];
+    for (bytes, lane) in hash.chunks_exact_mut(8).zip(state) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    hash
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ADDRESS: &str = "4A5Dwt2qKwKEQrZfo4aBkSNtvDDAzSFbAJcyFkdW5RwDh9U4WgeZrgKT4hUoE2gv8h6NmsNMTyjsEL8eSLMbABds5rYFWnw";
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    // Encoder only needed to build addresses of every type from the keys of a known address.
+    fn encode(network_byte: u8, address: &MoneroAddress, payment_id: Option<[u8; 8]>) -> String {
+        let mut data = vec![network_byte];
+        data.extend_from_slice(&address.spend_key);
+        data.extend_from_slice(&address.view_key);
+        if let Some(payment_id) = payment_id {
+            data.extend_from_slice(&payment_id);
+        }
+        let checksum = keccak256(&data);
+        data.extend_from_slice(&checksum[..CHECKSUM_SIZE]);
+        let mut encoded = String::new();
+        for block in data.chunks(FULL_BLOCK_SIZE) {
+            let mut value = block.iter().fold(0u64, |v, b| (v << 8) | *b as u64);
+            let mut chars = vec![ALPHABET[0]; ENCODED_BLOCK_SIZES[block.len()]];
+            for c in chars.iter_mut().rev() {
+                *c = ALPHABET[(value % 58) as usize];
+                value /= 58;
+            }
+            encoded.push_str(std::str::from_utf8(&chars).unwrap());
+        }
+        encoded
+    }
+
+    #[test]
+    fn network() {
+        for network in [Network::Mainnet, Network::Stagenet, Network::Testnet] {
+            assert_eq!(network.to_string().parse::<Network>(), Ok(network));
+        }
+        assert!("regtest".parse::<Network>().is_err());
+        assert_eq!(Network::Mainnet.port(18081), 18081);
+        assert_eq!(Network::Testnet.port(18081), 28081);
+        assert_eq!(Network::Stagenet.port(18083), 38083);
+        // custom ports are not shifted
+        assert_eq!(Network::Stagenet.port(3333), 3333);
+        assert_eq!(Network::Mainnet.monerod_flag(), None);
+        assert_eq!(Network::Stagenet.monerod_flag(), Some("--stagenet"));
+    }
+
+    #[test]
+    fn keccak() {
+        assert_eq!(
+            hex(&keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex(&keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+    }
+
+    #[test]
+    fn decode_address() {
+        let address = ADDRESS.parse::<MoneroAddress>().unwrap();
+        assert_eq!(address.network, Network::Mainnet);
+        assert_eq!(address.kind, AddressType::Primary);
+        assert_eq!(address.payment_id, None);
+        // the encoder used by the next tests gives back the same address
+        assert_eq!(encode(18, &address, None), ADDRESS);
+        assert!(check_address(ADDRESS, ProcessName::P2pool, Network::Mainnet).is_ok());
+        for other in [
+            "44hintoFpuo3ugKfcqJvh5BmrsTRpnTasJmetKC4VXCt6QDtbHVuixdTtsm6Ptp7Y8haXnJ6j8Gj2dra8CKy5ewz7Vi9CYW",
+            "4AGJScWSv45E28pmwck9YRP21KuwGx6fuMYV9kTxXFnWEij5FVEUyccBs7ExDy419DJXRPw3u57TH5BaGbsHTdnf6SvY5p5",
+        ] {
+            assert_eq!(
+                other.parse::<MoneroAddress>().unwrap().kind,
+                AddressType::Primary
+            );
+        }
+    }
+
+    #[test]
+    fn address_types() {
+        let primary = ADDRESS.parse::<MoneroAddress>().unwrap();
+        // subaddress
+        let subaddress = encode(42, &primary, None);
+        assert!(subaddress.starts_with('8'));
+        let decoded = subaddress.parse::<MoneroAddress>().unwrap();
+        assert_eq!(decoded.kind, AddressType::Subaddress);
+        assert!(check_address(&subaddress, ProcessName::Xmrig, Network::Mainnet).is_ok());
+        assert!(check_address(&subaddress, ProcessName::XmrigProxy, Network::Mainnet).is_ok());
+        let err = check_address(&subaddress, ProcessName::P2pool, Network::Mainnet).unwrap_err();
+        assert_eq!(
+            err,
+            AddressError::PrimaryRequired(ProcessName::P2pool, AddressType::Subaddress)
+        );
+        assert!(err.to_string().contains("requires a primary address"));
+        // integrated
+        let integrated = encode(19, &primary, Some([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(integrated.len(), INTEGRATED_ADDRESS_LEN);
+        let decoded = integrated.parse::<MoneroAddress>().unwrap();
+        assert_eq!(decoded.kind, AddressType::Integrated);
+        assert_eq!(decoded.payment_id, Some([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(decoded.spend_key, primary.spend_key);
+        assert!(check_address(&integrated, ProcessName::Xvb, Network::Mainnet).is_err());
+        // stagenet and testnet
+        let stagenet = encode(24, &primary, None);
+        assert!(stagenet.starts_with('5'));
+        assert_eq!(
+            stagenet.parse::<MoneroAddress>().unwrap().network,
+            Network::Stagenet
+        );
+        assert_eq!(
+            check_address(&stagenet, ProcessName::P2pool, Network::Mainnet),
+            Err(AddressError::Network {
+                expected: Network::Mainnet,
+                found: Network::Stagenet
+            })
+        );
+        assert!(check_address(&stagenet, ProcessName::P2pool, Network::Stagenet).is_ok());
+        let testnet = encode(63, &primary, None);
+        let decoded = testnet.parse::<MoneroAddress>().unwrap();
+        assert_eq!(
+            (decoded.network, decoded.kind),
+            (Network::Testnet, AddressType::Subaddress)
+        );
+        // unknown network byte with a valid checksum
+        assert_eq!(
+            encode(20, &primary, None).parse::<MoneroAddress>(),
+            Err(AddressError::NetworkByte(20))
+        );
+    }
+
+    #[test]
+    fn invalid_address() {
+        // one character changed
+        let mut mistyped = ADDRESS.to_string();
+        mistyped.replace_range(50..51, "A");
+        assert_ne!(mistyped, ADDRESS);
+        assert_eq!(
+            mistyped.parse::<MoneroAddress>(),
+            Err(AddressError::Checksum)
+        );
+        assert_eq!(
+            ADDRESS[..94].parse::<MoneroAddress>(),
+            Err(AddressError::Length(94))
+        );
+        assert_eq!("".parse::<MoneroAddress>(), Err(AddressError::Length(0)));
+        let mut bad_char = ADDRESS.to_string();
+        bad_char.replace_range(10..11, "0");
+        assert_eq!(
+            bad_char.parse::<MoneroAddress>(),
+            Err(AddressError::Character('0'))
+        );
+        // last block overflowing its 5 bytes
+        let overflow = format!("{}zzzzzzz", &ADDRESS[..88]);
+        assert_eq!(overflow.parse::<MoneroAddress>(), Err(AddressError::Base58));
+    }
+}
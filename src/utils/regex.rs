@@ -17,7 +17,10 @@
 
 // Some regexes used throughout Gupax.
 
-use crate::{disk::node::Node, helper::xvb::nodes::Pool};
+use crate::{
+    disk::{node::Node, state::XvbPoolEndpoint},
+    helper::xvb::nodes::Pool,
+};
 use log::warn;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -32,7 +35,6 @@ pub static XMRIG_REGEX: Lazy<XmrigRegex> = Lazy::new(XmrigRegex::new);
 #[derive(Clone, Debug)]
 pub struct Regexes {
     pub name: Regex,
-    pub address: Regex,
     pub ipv4: Regex,
     pub domain: Regex,
     pub port: Regex,
@@ -44,24 +46,11 @@ impl Regexes {
     fn new() -> Self {
         Self {
 			name: Regex::new("^[A-Za-z0-9-_.]+( [A-Za-z0-9-_.]+)*$").unwrap(),
-			address: Regex::new("^4[A-Za-z1-9]+$").unwrap(), // This still needs to check for (l, I, o, 0)
 			ipv4: Regex::new(r#"^((25[0-5]|(2[0-4]|1\d|[1-9]|)\d)\.?\b){4}$"#).unwrap(),
 			domain: Regex::new(r#"^[A-Za-z0-9-.]+[A-Za-z0-9-]+$"#).unwrap(),
 			port: Regex::new(r#"^([1-9][0-9]{0,3}|[1-5][0-9]{4}|6[0-4][0-9]{3}|65[0-4][0-9]{2}|655[0-2][0-9]|6553[0-5])$"#).unwrap(),
 		}
     }
-
-    #[inline]
-    // Check if a Monero address is correct.
-    // This actually only checks for length & Base58, and doesn't do any checksum validation
-    // (the last few bytes of a Monero address are a Keccak hash checksum) so some invalid addresses can trick this function.
-    pub fn addr_ok(address: &str) -> bool {
-        address.len() == 95
-            && REGEXES.address.is_match(address)
-            && !address.contains('0')
-            && !address.contains('O')
-            && !address.contains('l')
-    }
 }
 
 //---------------------------------------------------------------------------------------------------- [P2poolRegex]
@@ -175,12 +164,21 @@ pub fn p2pool_monero_node(s: &str) -> Option<Node> {
     }
     None
 }
-pub fn detect_pool_xmrig(s: &str, proxy_port: u16, p2pool_port: u16) -> Option<Pool> {
+pub fn detect_pool_xmrig(
+    s: &str,
+    proxy_port: u16,
+    p2pool_port: u16,
+    xvb_pools: &[XvbPoolEndpoint],
+) -> Option<Pool> {
     static CURRENT_SHARE: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"(use pool|new job from) (?P<pool>.*:\d{1,5})(| diff)").unwrap());
     if let Some(c) = CURRENT_SHARE.captures(s)
         && let Some(m) = c.name("pool")
     {
+        // XvB pools set by the user can be local too.
+        if let Some(endpoint) = xvb_pools.iter().find(|p| p.address() == m.as_str()) {
+            return Some(Pool::XvB(endpoint.clone()));
+        }
         match m.as_str() {
             // if user change address of local p2pool, it could create issue
             x if x.contains("127.0.0.1") => {
@@ -195,12 +193,6 @@ pub fn detect_pool_xmrig(s: &str, proxy_port: u16, p2pool_port: u16) -> Option<P
                     return Some(Pool::Custom("127.0.0.1".to_string(), port));
                 }
             }
-            "eu.xmrvsbeast.com:4247" => {
-                return Some(Pool::XvBEurope);
-            }
-            "na.xmrvsbeast.com:4247" => {
-                return Some(Pool::XvBNorthAmerica);
-            }
             x => {
                 let (ip, port) = x.split_once(":").unwrap_or_default();
                 if let Ok(port) = port.parse() {
@@ -258,6 +250,20 @@ pub fn pplns_window_nb_blocks(l: &str) -> Option<u64> {
     }
     None
 }
+// sidechain height, difficulty and effort of a share found by the local P2Pool
+pub fn share_found(l: &str) -> Option<(u64, u64, f64)> {
+    static LINE_SHARE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"SHARE FOUND: mainchain height \d+, sidechain height (?P<height>\d+), diff (?P<diff>\d+),.* effort (?P<effort>[0-9.]+)%").unwrap()
+    });
+    if let Some(c) = LINE_SHARE.captures(l)
+        && let Some(Ok(height)) = c.name("height").map(|m| m.as_str().parse::<u64>())
+        && let Some(Ok(diff)) = c.name("diff").map(|m| m.as_str().parse::<u64>())
+        && let Some(Ok(effort)) = c.name("effort").map(|m| m.as_str().parse::<f64>())
+    {
+        return Some((height, diff, effort));
+    }
+    None
+}
 pub fn contains_node(l: &str) -> bool {
     static LINE_SHARE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(Monero node|host )").unwrap());
     LINE_SHARE.is_match(l)
@@ -316,10 +322,6 @@ mod test {
     fn build_regexes() {
         let r = Regexes::new();
         assert!(Regex::is_match(&r.name, "_this_ is... a n-a-m-e."));
-        assert!(Regex::is_match(
-            &r.address,
-            "44hintoFpuo3ugKfcqJvh5BmrsTRpnTasJmetKC4VXCt6QDtbHVuixdTtsm6Ptp7Y8haXnJ6j8Gj2dra8CKy5ewz7Vi9CYW"
-        ));
         assert!(Regex::is_match(&r.ipv4, "192.168.1.2"));
         assert!(Regex::is_match(&r.ipv4, "127.0.0.1"));
         assert!(Regex::is_match(&r.domain, "sub.domain.com"));
@@ -372,4 +374,14 @@ mod test {
         );
         assert_eq!(r.new_job.find(text2).unwrap().as_str(), "new job");
     }
+
+    #[test]
+    fn share_found_line() {
+        let line = "NOTICE  2024-05-01 12:34:56.7890 StratumServer SHARE FOUND: mainchain height 3141592, sidechain height 9876543, diff 123456789, client 127.0.0.1:54321, user gupax, effort 87.654%";
+        assert_eq!(share_found(line), Some((9876543, 123456789, 87.654)));
+        assert_eq!(
+            share_found("NOTICE  2024-05-01 12:34:56.7890 P2Pool You received a payout"),
+            None
+        );
+    }
 }
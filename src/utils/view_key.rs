@@ -0,0 +1,259 @@
+// Gupax
+//
+// Copyright (c) 2024-2025 Cyrix126
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// Private view key of the P2Pool wallet.
+// It can not spend anything, but it finds every output received by the address.
+// An output [i] of a transaction with the public key [R] belongs to the address (A, B) if:
+//     derivation = 8·a·R
+//     output key = Keccak(derivation || varint(i)) mod l · G + B
+// Outputs created since the view tags hard fork also carry the first byte of
+//     Keccak("view_tag" || derivation || varint(i))
+// which allows to skip most of the outputs not belonging to the address without the point arithmetic.
+//
+// The view key reveals the payouts of the wallet, so it is only written to disk encrypted:
+//     [salt: 16 bytes][nonce: 12 bytes][ChaCha20-Poly1305 ciphertext of the key: 48 bytes]
+// The encryption key is derived from a passphrase with Argon2id, the whole is stored in hex.
+
+use crate::utils::monero_address::{KEY_SIZE, MoneroAddress, keccak256, write_varint};
+use argon2::Argon2;
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, aead::Aead};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_TABLE, edwards::CompressedEdwardsY, scalar::Scalar,
+};
+use rand::{Rng, rng};
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+//---------------------------------------------------------------------------------------------------- Const
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+// ChaCha20-Poly1305 appends a 16 bytes tag to the ciphertext.
+const ENCRYPTED_SIZE: usize = SALT_SIZE + NONCE_SIZE + KEY_SIZE + 16;
+
+//---------------------------------------------------------------------------------------------------- ViewKeyError
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViewKeyError {
+    Format,
+    Address,
+    Encrypted,
+    Passphrase,
+}
+
+impl fmt::Display for ViewKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Format => write!(f, "A private view key is 64 hexadecimal characters"),
+            Self::Address => write!(f, "This private view key does not belong to the address"),
+            Self::Encrypted => write!(f, "The encrypted view key is corrupted"),
+            Self::Passphrase => write!(f, "Wrong passphrase"),
+        }
+    }
+}
+
+impl std::error::Error for ViewKeyError {}
+
+//---------------------------------------------------------------------------------------------------- ViewKey
+// The scalar is wiped from memory when dropped.
+#[derive(Clone)]
+pub struct ViewKey(Scalar);
+
+// Never print the key itself.
+impl fmt::Debug for ViewKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ViewKey(***)")
+    }
+}
+
+impl Drop for ViewKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FromStr for ViewKey {
+    type Err = ViewKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes: [u8; KEY_SIZE] = hex_decode(s.trim())
+            .and_then(|b| b.try_into().ok())
+            .ok_or(ViewKeyError::Format)?;
+        let key = Option::from(Scalar::from_canonical_bytes(bytes));
+        bytes.zeroize();
+        key.map(Self).ok_or(ViewKeyError::Format)
+    }
+}
+
+impl ViewKey {
+    pub fn public_key(&self) -> [u8; KEY_SIZE] {
+        (&self.0 * ED25519_BASEPOINT_TABLE).compress().to_bytes()
+    }
+
+    // The key must belong to the address, or no output would ever be found.
+    pub fn check(&self, address: &MoneroAddress) -> Result<(), ViewKeyError> {
+        if self.public_key() == address.view_key {
+            Ok(())
+        } else {
+            Err(ViewKeyError::Address)
+        }
+    }
+
+    // Shared secret between the wallet and the transaction with the public key [tx_public_key].
+    // None if the transaction public key is not a valid point.
+    pub fn derivation(&self, tx_public_key: &[u8; KEY_SIZE]) -> Option<[u8; KEY_SIZE]> {
+        let point = CompressedEdwardsY(*tx_public_key).decompress()?;
+        Some((self.0 * point).mul_by_cofactor().compress().to_bytes())
+    }
+
+    pub fn encrypt(&self, passphrase: &str) -> String {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        rng().fill(&mut salt);
+        rng().fill(&mut nonce);
+        let cipher = cipher(passphrase, &salt);
+        // encryption with a valid key and nonce can not fail
+        let ciphertext = cipher
+            .encrypt(&nonce.into(), self.0.as_bytes().as_slice())
+            .expect("ChaCha20-Poly1305 encryption failed");
+        let mut out = Vec::with_capacity(ENCRYPTED_SIZE);
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        hex_encode(&out)
+    }
+
+    pub fn decrypt(encrypted: &str, passphrase: &str) -> Result<Self, ViewKeyError> {
+        let data = hex_decode(encrypted.trim())
+            .filter(|d| d.len() == ENCRYPTED_SIZE)
+            .ok_or(ViewKeyError::Encrypted)?;
+        let (salt, data) = data.split_at(SALT_SIZE);
+        let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+        let nonce: [u8; NONCE_SIZE] = nonce.try_into().map_err(|_| ViewKeyError::Encrypted)?;
+        let mut plaintext = cipher(passphrase, salt)
+            .decrypt(&nonce.into(), ciphertext)
+            .map_err(|_| ViewKeyError::Passphrase)?;
+        let bytes: Option<[u8; KEY_SIZE]> = plaintext.as_slice().try_into().ok();
+        plaintext.zeroize();
+        let mut bytes = bytes.ok_or(ViewKeyError::Encrypted)?;
+        let key = Option::from(Scalar::from_canonical_bytes(bytes));
+        bytes.zeroize();
+        key.map(Self).ok_or(ViewKeyError::Encrypted)
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; KEY_SIZE];
+    // only fails with a salt shorter than 8 bytes or an output of the wrong size
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .expect("Argon2 key derivation failed");
+    let cipher = ChaCha20Poly1305::new(&key.into());
+    key.zeroize();
+    cipher
+}
+
+//---------------------------------------------------------------------------------------------------- Outputs
+fn hash_to_scalar(derivation: &[u8; KEY_SIZE], index: u64) -> Scalar {
+    let mut data = derivation.to_vec();
+    write_varint(index, &mut data);
+    Scalar::from_bytes_mod_order(keccak256(&data))
+}
+
+// Key of the output [index] paying the public spend key, given the derivation with the transaction.
+pub fn output_key(
+    derivation: &[u8; KEY_SIZE],
+    index: u64,
+    spend_key: &[u8; KEY_SIZE],
+) -> Option<[u8; KEY_SIZE]> {
+    let spend_key = CompressedEdwardsY(*spend_key).decompress()?;
+    let point = &hash_to_scalar(derivation, index) * ED25519_BASEPOINT_TABLE + spend_key;
+    Some(point.compress().to_bytes())
+}
+
+pub fn view_tag(derivation: &[u8; KEY_SIZE], index: u64) -> u8 {
+    let mut data = b"view_tag".to_vec();
+    data.extend_from_slice(derivation);
+    write_varint(index, &mut data);
+    keccak256(&data)[0]
+}
+
+//---------------------------------------------------------------------------------------------------- Hex
+pub fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+pub fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+//---------------------------------------------------------------------------------------------------- TESTS
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    // Wallet of the recorded blocks in [helper::payout_scanner]
+    pub const ADDRESS: &str = "46jbG9wopmsNqQJq2kw9jW4uWafN11htkN82GF6KioB3P95SBcB5yUm8wWPpegmTtjZQ1TTYe7FPrLL7rMYqXAXR5rJDTqb";
+    pub const VIEW_KEY: &str = "effff54e7ac1e2754aa4c4b165005e8034307234ec66ed8412a34d74afea7f0f";
+
+    #[test]
+    fn parse_view_key() {
+        let address: MoneroAddress = ADDRESS.parse().unwrap();
+        let key: ViewKey = VIEW_KEY.parse().unwrap();
+        assert_eq!(key.check(&address), Ok(()));
+        // a key of another wallet
+        let other: ViewKey = "0100000000000000000000000000000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(other.check(&address), Err(ViewKeyError::Address));
+        // not a key
+        assert!("effff54e".parse::<ViewKey>().is_err());
+        assert!(VIEW_KEY.replace('e', "z").parse::<ViewKey>().is_err());
+        // not reduced modulo l
+        assert!(
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+                .parse::<ViewKey>()
+                .is_err()
+        );
+        assert_eq!(format!("{key:?}"), "ViewKey(***)");
+    }
+
+    #[test]
+    fn encrypt_view_key() {
+        let key: ViewKey = VIEW_KEY.parse().unwrap();
+        let encrypted = key.encrypt("correct horse battery staple");
+        assert_eq!(encrypted.len(), ENCRYPTED_SIZE * 2);
+        assert!(!encrypted.contains(VIEW_KEY));
+        // salt and nonce are random
+        assert_ne!(encrypted, key.encrypt("correct horse battery staple"));
+        let decrypted = ViewKey::decrypt(&encrypted, "correct horse battery staple").unwrap();
+        assert_eq!(decrypted.public_key(), key.public_key());
+        assert_eq!(
+            ViewKey::decrypt(&encrypted, "wrong").unwrap_err(),
+            ViewKeyError::Passphrase
+        );
+        assert_eq!(
+            ViewKey::decrypt(&encrypted[2..], "correct horse battery staple").unwrap_err(),
+            ViewKeyError::Encrypted
+        );
+    }
+}
@@ -257,6 +257,17 @@ impl PayoutOrd {
         *self = Self(vec);
     }
 
+    pub fn iter(&self) -> std::slice::Iter<'_, (String, AtomicUnit, HumanNumber)> {
+        self.0.iter()
+    }
+
+    // Monero blocks of the payouts, the unknown ones are skipped.
+    pub fn blocks(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0
+            .iter()
+            .filter_map(|(_, _, block)| block.to_string().replace(',', "").parse().ok())
+    }
+
     // Takes the wrapper types, and pushes to existing [Self]
     pub fn push(&mut self, date: String, atomic_unit: AtomicUnit, block: HumanNumber) {
         self.0.push((date, atomic_unit, block));